@@ -0,0 +1,186 @@
+use tracing::info;
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE, accounts::AccountAchievementQuery, client::ArtifactsClient, fetch_all_pages,
+        monsters::MonsterQuery, resources::ResourceQuery,
+    },
+    bot::strategy::Strategy,
+    goals::MIN_WIN_RATE,
+    models::{
+        achievement::{AccountAchievement, AchievementType},
+        character::Character,
+        monster::Monster,
+        resource::Resource,
+    },
+    simulator::{self, CombatStats},
+};
+
+/// An unfinished achievement and the strategy that advances it.
+#[derive(Debug, Clone)]
+pub struct AchievementStep {
+    pub achievement: AccountAchievement,
+    pub strategy: Strategy,
+}
+
+/// Picks strategies that advance an account's unfinished achievements.
+///
+/// Only achievements counting kills, monster drops or gathered items can be
+/// planned, as they map directly to a fight or gather strategy.
+#[derive(Debug, Clone)]
+pub struct AchievementPlanner {
+    resources: Vec<Resource>,
+    monsters: Vec<Monster>,
+}
+
+impl AchievementPlanner {
+    pub fn new(resources: Vec<Resource>, monsters: Vec<Monster>) -> Self {
+        Self {
+            resources,
+            monsters,
+        }
+    }
+
+    /// Creates a planner from the full resource and monster databases.
+    pub async fn load(api: &ArtifactsClient) -> anyhow::Result<Self> {
+        let resources = fetch_all_pages(|page| async move {
+            api.get_resources(
+                &ResourceQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let monsters = fetch_all_pages(|page| async move {
+            api.get_monsters(
+                &MonsterQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        Ok(Self::new(resources, monsters))
+    }
+
+    /// Returns whether `character` reliably beats `monster` at full HP.
+    fn can_beat(character: &Character, monster: &Monster) -> bool {
+        let mut stats = CombatStats::from(character);
+        stats.hp = stats.max_hp;
+        simulator::simulate(&stats, &CombatStats::from(monster), 100).win_rate >= MIN_WIN_RATE
+    }
+
+    /// Returns the strategy that advances `achievement` for `character`, if
+    /// the character can make progress on it.
+    pub fn strategy(
+        &self,
+        character: &Character,
+        achievement: &AccountAchievement,
+    ) -> Option<Strategy> {
+        let target = achievement.achievement.target.as_deref()?;
+
+        match achievement.achievement.achievement_type {
+            AchievementType::CombatKill => self
+                .monsters
+                .iter()
+                .find(|m| m.code == target && Self::can_beat(character, m))
+                .map(|m| Strategy::Fight {
+                    monster: m.code.clone(),
+                }),
+            // The drop rate is "1 in `rate`", so the lowest rate drops most.
+            AchievementType::CombatDrop => self
+                .monsters
+                .iter()
+                .filter_map(|m| Some((m, m.drops.iter().find(|d| d.code == target)?.rate)))
+                .filter(|(m, _)| Self::can_beat(character, m))
+                .min_by_key(|(_, rate)| *rate)
+                .map(|(m, _)| Strategy::Fight {
+                    monster: m.code.clone(),
+                }),
+            AchievementType::Gathering => self
+                .resources
+                .iter()
+                .filter(|r| r.level <= character.skill_level(r.skill))
+                .filter_map(|r| Some((r, r.drops.iter().find(|d| d.code == target)?.rate)))
+                .min_by_key(|(_, rate)| *rate)
+                .map(|(r, _)| Strategy::Gather {
+                    resource: r.code.clone(),
+                }),
+            _ => None,
+        }
+    }
+
+    /// Returns the unfinished achievements `character` can advance, the most
+    /// points first, then the closest to completion.
+    pub fn plan(
+        &self,
+        character: &Character,
+        achievements: &[AccountAchievement],
+    ) -> Vec<AchievementStep> {
+        let mut steps = achievements
+            .iter()
+            .filter(|a| !a.is_completed())
+            .filter_map(|a| {
+                Some(AchievementStep {
+                    strategy: self.strategy(character, a)?,
+                    achievement: a.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        steps.sort_by(|a, b| {
+            b.achievement
+                .achievement
+                .points
+                .cmp(&a.achievement.achievement.points)
+                .then_with(|| {
+                    b.achievement
+                        .progress()
+                        .total_cmp(&a.achievement.progress())
+                })
+        });
+
+        steps
+    }
+
+    /// Returns the best step for `character`, logging the choice.
+    pub fn next(
+        &self,
+        character: &Character,
+        achievements: &[AccountAchievement],
+    ) -> Option<AchievementStep> {
+        let step = self.plan(character, achievements).into_iter().next()?;
+        info!(
+            target: "achievements",
+            "{}: {} to advance {} ({}/{})",
+            character.name,
+            step.strategy,
+            step.achievement.achievement.name,
+            step.achievement.current,
+            step.achievement.achievement.total
+        );
+        Some(step)
+    }
+}
+
+/// Fetches every achievement of `account` along with its progress.
+pub async fn load_progress(
+    api: &ArtifactsClient,
+    account: &str,
+) -> anyhow::Result<Vec<AccountAchievement>> {
+    let achievements = fetch_all_pages(|page| async move {
+        api.get_account_achievements(
+            account,
+            &AccountAchievementQuery::default()
+                .page_number(page)
+                .page_size(MAX_PAGE_SIZE),
+        )
+        .await
+    })
+    .await?;
+
+    Ok(achievements)
+}
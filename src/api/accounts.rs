@@ -0,0 +1,148 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::{
+        account::{Account, BankDetails},
+        achievement::{AccountAchievement, AchievementType},
+        character::Character,
+    },
+};
+
+make_error!(GetAccountError,
+    404 => AccountNotFound
+        => "Account not found",
+);
+
+make_error!(GetAccountAchievementsError,
+    404 => AccountNotFound
+        => "Account not found",
+);
+
+make_error!(GetAccountCharactersError,
+    404 => AccountNotFound
+        => "Account not found",
+);
+
+make_error!(GetMyDetailsError);
+
+make_error!(GetBankDetailsError);
+
+// Query parameters for fetching an account's achievements
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct AccountAchievementQuery {
+    #[serde(rename = "type")]
+    pub achievement_type: Option<AchievementType>,
+    pub completed: Option<bool>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches the public details of an account.
+    pub async fn get_account(
+        &self,
+        account: &str,
+    ) -> Result<Account, ArtifactsError<GetAccountError>> {
+        debug!("Fetching account: {}", account);
+
+        let url = format!("{}/accounts/{}", self.base_url, account);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let account = ArtifactsApiResponse::<Account>::parse_json(resp).await?;
+        Ok(account)
+    }
+
+    /// Fetches an account's progress on every achievement.
+    pub async fn get_account_achievements(
+        &self,
+        account: &str,
+        query: &AccountAchievementQuery,
+    ) -> Result<Vec<AccountAchievement>, ArtifactsError<GetAccountAchievementsError>> {
+        debug!(
+            "Fetching achievements of {} with query: {}",
+            account,
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/accounts/{}/achievements", self.base_url, account);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let achievements =
+            ArtifactsApiResponse::<Vec<AccountAchievement>>::parse_json(resp).await?;
+        Ok(achievements)
+    }
+
+    /// Fetches the characters of an account.
+    pub async fn get_account_characters(
+        &self,
+        account: &str,
+    ) -> Result<Vec<Character>, ArtifactsError<GetAccountCharactersError>> {
+        debug!("Fetching characters of account: {}", account);
+
+        let url = format!("{}/accounts/{}/characters", self.base_url, account);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let characters = ArtifactsApiResponse::<Vec<Character>>::parse_json(resp).await?;
+        Ok(characters)
+    }
+
+    /// Fetches the details of the authenticated user's account.
+    pub async fn get_my_details(&self) -> Result<Account, ArtifactsError<GetMyDetailsError>> {
+        debug!("Fetching account details");
+
+        let url = format!("{}/my/details", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let account = ArtifactsApiResponse::<Account>::parse_json(resp).await?;
+        Ok(account)
+    }
+
+    /// Fetches the size and gold of the authenticated user's bank.
+    pub async fn get_bank_details(
+        &self,
+    ) -> Result<BankDetails, ArtifactsError<GetBankDetailsError>> {
+        debug!("Fetching bank details");
+
+        let url = format!("{}/my/bank", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let bank = ArtifactsApiResponse::<BankDetails>::parse_json(resp).await?;
+        Ok(bank)
+    }
+}
@@ -0,0 +1,75 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::achievement::{Achievement, AchievementType},
+};
+
+make_error!(GetAchievementsError);
+
+make_error!(GetAchievementError,
+    404 => AchievementNotFound
+        => "Achievement not found",
+);
+
+// Query parameters for fetching achievements
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct AchievementQuery {
+    #[serde(rename = "type")]
+    pub achievement_type: Option<AchievementType>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches achievements based on the provided query parameters.
+    pub async fn get_achievements(
+        &self,
+        query: &AchievementQuery,
+    ) -> Result<Vec<Achievement>, ArtifactsError<GetAchievementsError>> {
+        debug!(
+            "Fetching achievements with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/achievements", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let achievements = ArtifactsApiResponse::<Vec<Achievement>>::parse_json(resp).await?;
+        Ok(achievements)
+    }
+
+    /// Fetches an achievement by code.
+    pub async fn get_achievement(
+        &self,
+        code: &str,
+    ) -> Result<Achievement, ArtifactsError<GetAchievementError>> {
+        debug!("Fetching achievement: {}", code);
+
+        let url = format!("{}/achievements/{}", self.base_url, code);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let achievement = ArtifactsApiResponse::<Achievement>::parse_json(resp).await?;
+        Ok(achievement)
+    }
+}
@@ -0,0 +1,50 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::{item::SimpleItem, types::ItemCode},
+};
+
+make_error!(GetBankItemsError);
+
+// Query parameters for fetching bank items
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct BankItemQuery {
+    pub item_code: Option<ItemCode>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches the items stored in the authenticated user's bank.
+    pub async fn get_bank_items(
+        &self,
+        query: &BankItemQuery,
+    ) -> Result<Vec<SimpleItem>, ArtifactsError<GetBankItemsError>> {
+        debug!(
+            "Fetching bank items with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/my/bank/items", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let items = ArtifactsApiResponse::<Vec<SimpleItem>>::parse_json(resp).await?;
+        Ok(items)
+    }
+}
@@ -3,7 +3,7 @@ use tracing::debug;
 use crate::{
     api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
     make_error,
-    models::character::Character,
+    models::{character::Character, types::CharacterName},
 };
 
 make_error!(GetCharacterError,
@@ -20,7 +20,7 @@ impl ArtifactsClient {
     /// Fetches a character by name.
     pub async fn get_character(
         &self,
-        name: &str,
+        name: &CharacterName,
     ) -> Result<Character, ArtifactsError<GetCharacterError>> {
         debug!("Fetching character: {}", name);
 
@@ -39,7 +39,7 @@ impl ArtifactsClient {
     /// Deletes a character by name.
     pub async fn delete_character(
         &self,
-        name: &str,
+        name: &CharacterName,
     ) -> Result<Character, ArtifactsError<DeleteCharacterError>> {
         debug!("Deleting character: {}", name);
 
@@ -2,9 +2,14 @@ use std::time::Duration;
 
 use derivative::Derivative;
 use reqwest_middleware::{ClientBuilder, Extension};
-use reqwest_retry::{Jitter, RetryTransientMiddleware, policies::ExponentialBackoff};
+use reqwest_retry::{Jitter, policies::ExponentialBackoff};
 use reqwest_tracing::{OtelName, TracingMiddleware};
 
+use crate::{
+    api::retry::{RetryMiddleware, RetryPolicies},
+    metrics::MetricsMiddleware,
+};
+
 /// Custom client for interacting with the Artifacts API.
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
@@ -33,16 +38,27 @@ impl ArtifactsClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        let retry_policy = ExponentialBackoff::builder()
-            .retry_bounds(Duration::from_secs(1), Duration::from_secs(60))
-            .jitter(Jitter::Bounded)
-            .base(2)
-            .build_with_total_retry_duration(Duration::from_secs(60));
+        let backoff = |max_delay: u64, total: u64| {
+            ExponentialBackoff::builder()
+                .retry_bounds(Duration::from_secs(1), Duration::from_secs(max_delay))
+                .jitter(Jitter::Bounded)
+                .base(2)
+                .build_with_total_retry_duration(Duration::from_secs(total))
+        };
+        // Reads are cheap to repeat, and a bot cannot do anything until its
+        // action goes through, so both keep trying for longer than requests
+        // that change something else, e.g. creating a character.
+        let retry_policies = RetryPolicies {
+            data: backoff(60, 120),
+            action: backoff(30, 120),
+            other: backoff(10, 30),
+        };
 
         let client = ClientBuilder::new(client)
             .with_init(Extension(OtelName("artifacts-client".into())))
             .with(TracingMiddleware::default())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .with(RetryMiddleware::new(retry_policies))
+            .with(MetricsMiddleware)
             .build();
 
         Self {
@@ -0,0 +1,80 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::{
+        event::{ActiveEvent, Event},
+        map::MapContentType,
+    },
+};
+
+make_error!(GetEventsError);
+
+make_error!(GetActiveEventsError);
+
+// Query parameters for fetching events
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct EventQuery {
+    #[serde(rename = "type")]
+    pub content_type: Option<MapContentType>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches every event that can spawn during the season.
+    pub async fn get_events(
+        &self,
+        query: &EventQuery,
+    ) -> Result<Vec<Event>, ArtifactsError<GetEventsError>> {
+        debug!(
+            "Fetching events with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/events", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let events = ArtifactsApiResponse::<Vec<Event>>::parse_json(resp).await?;
+        Ok(events)
+    }
+
+    /// Fetches the events that are currently spawned.
+    pub async fn get_active_events(
+        &self,
+        query: &EventQuery,
+    ) -> Result<Vec<ActiveEvent>, ArtifactsError<GetActiveEventsError>> {
+        debug!(
+            "Fetching active events with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/events/active", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let events = ArtifactsApiResponse::<Vec<ActiveEvent>>::parse_json(resp).await?;
+        Ok(events)
+    }
+}
@@ -0,0 +1,95 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::{
+        grand_exchange::{GeOrder, GeOrderHistory},
+        types::ItemCode,
+    },
+};
+
+make_error!(GetGeOrdersError);
+
+make_error!(GetGeHistoryError);
+
+// Query parameters for fetching Grand Exchange sell orders
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct GeOrderQuery {
+    pub code: Option<ItemCode>,
+    pub seller: Option<String>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+// Query parameters for fetching the sale history of an item
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct GeHistoryQuery {
+    pub seller: Option<String>,
+    pub buyer: Option<String>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches the sell orders currently listed on the Grand Exchange.
+    pub async fn get_ge_orders(
+        &self,
+        query: &GeOrderQuery,
+    ) -> Result<Vec<GeOrder>, ArtifactsError<GetGeOrdersError>> {
+        debug!(
+            "Fetching Grand Exchange orders with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/grandexchange/orders", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let orders = ArtifactsApiResponse::<Vec<GeOrder>>::parse_json(resp).await?;
+        Ok(orders)
+    }
+
+    /// Fetches the recent sales of the item with the given code.
+    pub async fn get_ge_history(
+        &self,
+        code: &ItemCode,
+        query: &GeHistoryQuery,
+    ) -> Result<Vec<GeOrderHistory>, ArtifactsError<GetGeHistoryError>> {
+        debug!(
+            "Fetching Grand Exchange history for {} with query: {}",
+            code,
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/grandexchange/history/{}", self.base_url, code);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let history = ArtifactsApiResponse::<Vec<GeOrderHistory>>::parse_json(resp).await?;
+        Ok(history)
+    }
+}
@@ -0,0 +1,77 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::{item::Item, skill::Skill, types::ItemCode},
+};
+
+make_error!(GetAllItemsError);
+
+make_error!(GetItemError,
+    404 => ItemNotFound
+        => "Item not found",
+);
+
+// Query parameters for fetching items
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct ItemQuery {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub item_type: Option<String>,
+    pub craft_skill: Option<Skill>,
+    pub craft_material: Option<ItemCode>,
+    pub min_level: Option<u32>,
+    pub max_level: Option<u32>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches items based on the provided query parameters.
+    pub async fn get_items(
+        &self,
+        query: &ItemQuery,
+    ) -> Result<Vec<Item>, ArtifactsError<GetAllItemsError>> {
+        debug!(
+            "Fetching items with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/items", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let items = ArtifactsApiResponse::<Vec<Item>>::parse_json(resp).await?;
+        Ok(items)
+    }
+
+    /// Fetches a specific item by its code.
+    pub async fn get_item(&self, code: &ItemCode) -> Result<Item, ArtifactsError<GetItemError>> {
+        debug!("Fetching item with code: {}", code);
+
+        let url = format!("{}/items/{}", self.base_url, code);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let item = ArtifactsApiResponse::<Item>::parse_json(resp).await?;
+        Ok(item)
+    }
+}
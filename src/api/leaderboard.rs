@@ -0,0 +1,97 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::leaderboard::{
+        AccountLeaderboardEntry, AccountLeaderboardSort, CharacterLeaderboardEntry,
+        CharacterLeaderboardSort,
+    },
+};
+
+make_error!(GetCharactersLeaderboardError);
+
+make_error!(GetAccountsLeaderboardError);
+
+// Query parameters for fetching the characters leaderboard
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct CharacterLeaderboardQuery {
+    pub sort: Option<CharacterLeaderboardSort>,
+    /// Only return the character with this name.
+    pub name: Option<String>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+// Query parameters for fetching the accounts leaderboard
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct AccountLeaderboardQuery {
+    pub sort: Option<AccountLeaderboardSort>,
+    /// Only return the account with this name.
+    pub name: Option<String>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches the characters leaderboard, best first.
+    pub async fn get_characters_leaderboard(
+        &self,
+        query: &CharacterLeaderboardQuery,
+    ) -> Result<Vec<CharacterLeaderboardEntry>, ArtifactsError<GetCharactersLeaderboardError>> {
+        debug!(
+            "Fetching characters leaderboard with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/leaderboard/characters", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let entries =
+            ArtifactsApiResponse::<Vec<CharacterLeaderboardEntry>>::parse_json(resp).await?;
+        Ok(entries)
+    }
+
+    /// Fetches the accounts leaderboard, best first.
+    pub async fn get_accounts_leaderboard(
+        &self,
+        query: &AccountLeaderboardQuery,
+    ) -> Result<Vec<AccountLeaderboardEntry>, ArtifactsError<GetAccountsLeaderboardError>> {
+        debug!(
+            "Fetching accounts leaderboard with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/leaderboard/accounts", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let entries =
+            ArtifactsApiResponse::<Vec<AccountLeaderboardEntry>>::parse_json(resp).await?;
+        Ok(entries)
+    }
+}
@@ -0,0 +1,64 @@
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, MAX_PAGE_SIZE, client::ArtifactsClient},
+    make_error,
+    models::{log::LogEntry, types::CharacterName},
+};
+
+make_error!(GetCharacterLogsError,
+    404 => LogsNotFound
+        => "Logs not found",
+    498 => CharacterNotFound
+        => "Character not found",
+);
+
+make_error!(GetAccountLogsError,
+    404 => LogsNotFound
+        => "Logs not found",
+);
+
+impl ArtifactsClient {
+    /// Fetches a page of the actions logged for one of the user's
+    /// characters, newest first.
+    pub async fn get_character_logs(
+        &self,
+        name: &CharacterName,
+        page: u32,
+    ) -> Result<Vec<LogEntry>, ArtifactsError<GetCharacterLogsError>> {
+        debug!("Fetching logs of {} (page {})", name, page);
+
+        let url = format!("{}/my/logs/{}", self.base_url, name);
+        let resp = self
+            .client
+            .get(url)
+            .query(&[("page", page), ("size", MAX_PAGE_SIZE)])
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let logs = ArtifactsApiResponse::<Vec<LogEntry>>::parse_json(resp).await?;
+        Ok(logs)
+    }
+
+    /// Fetches a page of the actions logged for every character of the
+    /// account, newest first.
+    pub async fn get_account_logs(
+        &self,
+        page: u32,
+    ) -> Result<Vec<LogEntry>, ArtifactsError<GetAccountLogsError>> {
+        debug!("Fetching account logs (page {})", page);
+
+        let url = format!("{}/my/logs", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&[("page", page), ("size", MAX_PAGE_SIZE)])
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let logs = ArtifactsApiResponse::<Vec<LogEntry>>::parse_json(resp).await?;
+        Ok(logs)
+    }
+}
@@ -6,7 +6,10 @@ use tracing::debug;
 use crate::{
     api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
     make_error,
-    models::map::{Map, MapContentType},
+    models::{
+        map::{Map, MapContentType},
+        types::Position,
+    },
 };
 
 make_error!(GetAllMapsError);
@@ -18,12 +21,14 @@ make_error!(GetMapError,
 
 // Query parameters for fetching maps
 #[skip_serializing_none]
-#[derive(Default, Debug, Setters, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
 #[setters(strip_option)]
 pub struct MapQuery {
     pub content_type: Option<MapContentType>,
     pub content_code: Option<String>,
+    #[serde(rename = "page")]
     pub page_number: Option<u32>,
+    #[serde(rename = "size")]
     pub page_size: Option<u32>,
 }
 
@@ -52,11 +57,11 @@ impl ArtifactsClient {
         Ok(char)
     }
 
-    /// Fetches a specific map by its coordinates (x, y).
-    pub async fn get_map(&self, x: i32, y: i32) -> Result<Map, ArtifactsError<GetMapError>> {
-        debug!("Fetching map at coordinates: ({}, {})", x, y);
+    /// Fetches a specific map by its coordinates.
+    pub async fn get_map(&self, position: Position) -> Result<Map, ArtifactsError<GetMapError>> {
+        debug!("Fetching map at coordinates: {}", position);
 
-        let url = format!("{}/maps/{}/{}", self.base_url, x, y);
+        let url = format!("{}/maps/{}/{}", self.base_url, position.x, position.y);
         let resp = self
             .client
             .get(url)
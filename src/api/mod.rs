@@ -5,16 +5,64 @@ use thiserror::Error;
 
 use crate::api::client::ArtifactsClient;
 
+/// Artifacts API module that provides functionality to interact with Accounts.
+pub mod accounts;
+/// Artifacts API module that provides functionality to interact with Achievements.
+pub mod achievements;
+/// Artifacts API module that provides functionality to interact with the user's Bank.
+pub mod bank;
 /// Artifacts API module that provides functionality to interact with Characters.
 pub mod characters;
 /// Provides a custom client for the Artifacts API.
 pub mod client;
+/// Artifacts API module that provides functionality to interact with world Events.
+pub mod events;
+/// Artifacts API module that provides functionality to interact with the Grand Exchange.
+pub mod grand_exchange;
+/// Artifacts API module that provides functionality to interact with Items.
+pub mod items;
+/// Artifacts API module that provides functionality to interact with the Leaderboards.
+pub mod leaderboard;
+/// Artifacts API module that provides the action logs of the user's Characters.
+pub mod logs;
 /// Artifacts API module that provides functionality to interact with Maps.
 pub mod maps;
 /// Artifacts API module that provides functionality to interact with Monsters.
 pub mod monsters;
 /// Artifacts API module that provides functionality to interact with the user's Characters.
 pub mod my_characters;
+/// Artifacts API module that provides functionality to interact with Resources.
+pub mod resources;
+/// Provides the retry policy applied to every request to the Artifacts API.
+pub mod retry;
+/// Artifacts API module that provides the server status.
+pub mod server;
+
+/// The largest page size accepted by the Artifacts API.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Repeatedly calls `fetch` with increasing page numbers (starting at 1) until
+/// a page with fewer than [`MAX_PAGE_SIZE`] entries is returned, collecting
+/// all results.
+pub async fn fetch_all_pages<T, E, F, Fut>(mut fetch: F) -> Result<Vec<T>, E>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, E>>,
+{
+    let mut all = Vec::new();
+
+    for page in 1.. {
+        let items = fetch(page).await?;
+        let len = items.len();
+        all.extend(items);
+
+        if len < MAX_PAGE_SIZE as usize {
+            break;
+        }
+    }
+
+    Ok(all)
+}
 
 /// Actions that can be executed against the Artifacts API.
 pub trait Action {
@@ -6,7 +6,10 @@ use tracing::debug;
 use crate::{
     api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
     make_error,
-    models::monster::Monster,
+    models::{
+        monster::Monster,
+        types::{ItemCode, MonsterCode},
+    },
 };
 
 make_error!(GetAllMonstersError);
@@ -18,14 +21,16 @@ make_error!(GetMonsterError,
 
 // Query parameters for fetching monsters
 #[skip_serializing_none]
-#[derive(Default, Debug, Setters, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
 #[setters(strip_option)]
 pub struct MonsterQuery {
     pub name: Option<String>,
-    pub drop: Option<String>,
+    pub drop: Option<ItemCode>,
     pub min_level: Option<u32>,
     pub max_level: Option<u32>,
+    #[serde(rename = "page")]
     pub page_number: Option<u32>,
+    #[serde(rename = "size")]
     pub page_size: Option<u32>,
 }
 
@@ -57,7 +62,7 @@ impl ArtifactsClient {
     /// Fetches a specific monster by its code.
     pub async fn get_monster(
         &self,
-        code: &str,
+        code: &MonsterCode,
     ) -> Result<Monster, ArtifactsError<GetMonsterError>> {
         debug!("Fetching monster with code: {}", code);
 
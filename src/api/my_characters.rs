@@ -3,9 +3,19 @@ use tracing::debug;
 use crate::{
     api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
     make_error,
+    metrics::{ActionGains, Metrics},
     models::{
-        character::Character, fight::CharacterFightData, movement::CharacterMovementData,
+        bank::BankItemTransaction,
+        character::Character,
+        cooldown::Cooldown,
+        equipment::{EquipRequestData, EquipmentSlot},
+        fight::CharacterFightData,
+        grand_exchange::GeOrderTransaction,
+        item::{SimpleItem, UseItemData},
+        movement::CharacterMovementData,
         rest::CharacterRestData,
+        skill::SkillData,
+        types::{CharacterName, ItemCode, Position},
     },
 };
 
@@ -46,6 +56,156 @@ make_error!(CharacterMoveError,
         => "Character is in cooldown",
 );
 
+make_error!(CharacterGatherError,
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    493 => SkillLevelTooLow
+        => "Character's skill level is too low",
+    497 => CharacterInventoryFull
+        => "Character inventory is full",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+    598 => ResourceNotFound
+        => "Resource not found on this map",
+);
+
+make_error!(CharacterCraftError,
+    404 => ItemNotFound
+        => "Craft not found",
+    478 => MissingItems
+        => "Missing required items",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    493 => SkillLevelTooLow
+        => "Character's skill level is too low",
+    497 => CharacterInventoryFull
+        => "Character inventory is full",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+    598 => WorkshopNotFound
+        => "Workshop not found on this map",
+);
+
+make_error!(CharacterBankWithdrawError,
+    404 => ItemNotFound
+        => "Item not found",
+    461 => TransactionAlreadyInProgress
+        => "A transaction is already in progress on this item",
+    478 => MissingItems
+        => "Missing items in the bank",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    497 => CharacterInventoryFull
+        => "Character inventory is full",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+    598 => BankNotFound
+        => "Bank not found on this map",
+);
+
+make_error!(CharacterBankDepositError,
+    404 => ItemNotFound
+        => "Item not found",
+    461 => TransactionAlreadyInProgress
+        => "A transaction is already in progress on this item",
+    462 => BankFull
+        => "Bank is full",
+    478 => MissingItems
+        => "Missing items or insufficient quantity",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+    598 => BankNotFound
+        => "Bank not found on this map",
+);
+
+make_error!(CharacterUseItemError,
+    404 => ItemNotFound
+        => "Item not found",
+    476 => ItemNotConsumable
+        => "Item is not consumable",
+    478 => MissingItem
+        => "Missing item or insufficient quantity",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    496 => ConditionsNotMet
+        => "Character does not meet the item's conditions",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+);
+
+make_error!(CharacterEquipError,
+    404 => ItemNotFound
+        => "Item not found",
+    478 => MissingItem
+        => "Missing item or insufficient quantity",
+    485 => ItemAlreadyEquipped
+        => "Item is already equipped",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    491 => SlotNotEmpty
+        => "Slot is not empty",
+    496 => ConditionsNotMet
+        => "Character does not meet the item's conditions",
+    497 => CharacterInventoryFull
+        => "Character inventory is full",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+);
+
+make_error!(CharacterGeSellError,
+    404 => ItemNotFound
+        => "Item not found",
+    433 => TooManyOrders
+        => "Too many Grand Exchange orders",
+    437 => ItemNotTradeable
+        => "Item cannot be sold on the Grand Exchange",
+    478 => MissingItem
+        => "Missing item or insufficient quantity",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+    598 => GrandExchangeNotFound
+        => "Grand Exchange not found on this map",
+);
+
+make_error!(CharacterUnequipError,
+    404 => ItemNotFound
+        => "Item not found",
+    486 => ActionAlreadyInProgressForCharacter
+        => "Action already in progress for character",
+    491 => SlotEmpty
+        => "Slot is empty",
+    497 => CharacterInventoryFull
+        => "Character inventory is full",
+    498 => CharacterNotFound
+        => "Character not found",
+    499 => CharacterInCooldown
+        => "Character is in cooldown",
+);
+
+/// Counts an action performed by `name` in [`Metrics::global`], whichever
+/// part of the client sent it.
+fn record_action(name: &CharacterName, action: &str, cooldown: &Cooldown, gains: ActionGains) {
+    Metrics::global().record_action(name, action, cooldown.total_seconds, gains);
+}
+
 impl ArtifactsClient {
     /// Fetches all characters for the authenticated user.
     pub async fn get_characters(
@@ -68,7 +228,7 @@ impl ArtifactsClient {
     /// Initiates a fight with the specified character.
     pub async fn fight(
         &self,
-        name: &str,
+        name: &CharacterName,
     ) -> Result<CharacterFightData, ArtifactsError<CharacterFightError>> {
         debug!("Fighting with character: {}", name);
 
@@ -85,14 +245,20 @@ impl ArtifactsClient {
             .send()
             .await?;
 
-        let char = ArtifactsApiResponse::<_>::parse_json(resp).await?;
+        let char = ArtifactsApiResponse::<CharacterFightData>::parse_json(resp).await?;
+        record_action(
+            name,
+            "fight",
+            &char.cooldown,
+            ActionGains::from(&char.fight),
+        );
         Ok(char)
     }
 
     /// Rests the specified character.
     pub async fn rest(
         &self,
-        name: &str,
+        name: &CharacterName,
     ) -> Result<CharacterRestData, ArtifactsError<CharacterRestError>> {
         debug!("Resting character: {}", name);
 
@@ -109,22 +275,22 @@ impl ArtifactsClient {
             .send()
             .await?;
 
-        let char = ArtifactsApiResponse::<_>::parse_json(resp).await?;
+        let char = ArtifactsApiResponse::<CharacterRestData>::parse_json(resp).await?;
+        record_action(name, "rest", &char.cooldown, ActionGains::default());
         Ok(char)
     }
 
     /// Moves the specified character to the given coordinates.
     pub async fn move_character(
         &self,
-        name: &str,
-        x: i32,
-        y: i32,
+        name: &CharacterName,
+        to: Position,
     ) -> Result<CharacterMovementData, ArtifactsError<CharacterMoveError>> {
-        debug!("Moving character: {} to ({}, {})", name, x, y);
+        debug!("Moving character: {} to {}", name, to);
 
         let body = serde_json::json!({
-            "x": x,
-            "y": y,
+            "x": to.x,
+            "y": to.y,
         });
 
         let url = format!("{}/my/{}/action/move", self.base_url, name);
@@ -136,7 +302,234 @@ impl ArtifactsClient {
             .send()
             .await?;
 
-        let char = ArtifactsApiResponse::<_>::parse_json(resp).await?;
+        let char = ArtifactsApiResponse::<CharacterMovementData>::parse_json(resp).await?;
+        record_action(name, "move", &char.cooldown, ActionGains::default());
         Ok(char)
     }
+
+    /// Gathers the resource on the specified character's current map.
+    pub async fn gather(
+        &self,
+        name: &CharacterName,
+    ) -> Result<SkillData, ArtifactsError<CharacterGatherError>> {
+        debug!("Gathering with character: {}", name);
+
+        let url = format!("{}/my/{}/action/gathering", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<SkillData>::parse_json(resp).await?;
+        record_action(
+            name,
+            "gather",
+            &data.cooldown,
+            ActionGains::xp(data.details.xp),
+        );
+        Ok(data)
+    }
+
+    /// Crafts `quantity` of the given item at the workshop on the specified
+    /// character's current map.
+    pub async fn craft(
+        &self,
+        name: &CharacterName,
+        code: &ItemCode,
+        quantity: u32,
+    ) -> Result<SkillData, ArtifactsError<CharacterCraftError>> {
+        debug!("Crafting {} x{} with character: {}", code, quantity, name);
+
+        let body = serde_json::json!({
+            "code": code,
+            "quantity": quantity,
+        });
+
+        let url = format!("{}/my/{}/action/crafting", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(&body)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<SkillData>::parse_json(resp).await?;
+        record_action(
+            name,
+            "craft",
+            &data.cooldown,
+            ActionGains::xp(data.details.xp),
+        );
+        Ok(data)
+    }
+
+    /// Withdraws the given items from the bank into the specified character's
+    /// inventory. The character must be on a bank map.
+    pub async fn withdraw_items(
+        &self,
+        name: &CharacterName,
+        items: &[SimpleItem],
+    ) -> Result<BankItemTransaction, ArtifactsError<CharacterBankWithdrawError>> {
+        debug!("Withdrawing items for character: {}: {:?}", name, items);
+
+        let url = format!("{}/my/{}/action/bank/withdraw/item", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(items)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<BankItemTransaction>::parse_json(resp).await?;
+        record_action(name, "withdraw", &data.cooldown, ActionGains::default());
+        Ok(data)
+    }
+
+    /// Deposits the given items from the specified character's inventory into
+    /// the bank. The character must be on a bank map.
+    pub async fn deposit_items(
+        &self,
+        name: &CharacterName,
+        items: &[SimpleItem],
+    ) -> Result<BankItemTransaction, ArtifactsError<CharacterBankDepositError>> {
+        debug!("Depositing items for character: {}: {:?}", name, items);
+
+        let url = format!("{}/my/{}/action/bank/deposit/item", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(items)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<BankItemTransaction>::parse_json(resp).await?;
+        record_action(name, "deposit", &data.cooldown, ActionGains::default());
+        Ok(data)
+    }
+
+    /// Uses `quantity` of a consumable item from the specified character's
+    /// inventory.
+    pub async fn use_item(
+        &self,
+        name: &CharacterName,
+        code: &ItemCode,
+        quantity: u32,
+    ) -> Result<UseItemData, ArtifactsError<CharacterUseItemError>> {
+        debug!("Using {} x{} with character: {}", code, quantity, name);
+
+        let body = serde_json::json!({
+            "code": code,
+            "quantity": quantity,
+        });
+
+        let url = format!("{}/my/{}/action/use", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(&body)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<UseItemData>::parse_json(resp).await?;
+        record_action(name, "use_item", &data.cooldown, ActionGains::default());
+        Ok(data)
+    }
+
+    /// Equips an item from the specified character's inventory into `slot`.
+    pub async fn equip(
+        &self,
+        name: &CharacterName,
+        code: &ItemCode,
+        slot: EquipmentSlot,
+    ) -> Result<EquipRequestData, ArtifactsError<CharacterEquipError>> {
+        debug!(
+            "Equipping {} in slot {} for character: {}",
+            code, slot, name
+        );
+
+        let body = serde_json::json!({
+            "code": code,
+            "slot": slot,
+        });
+
+        let url = format!("{}/my/{}/action/equip", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(&body)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<EquipRequestData>::parse_json(resp).await?;
+        record_action(name, "equip", &data.cooldown, ActionGains::default());
+        Ok(data)
+    }
+
+    /// Unequips the item in `slot` into the specified character's inventory.
+    pub async fn unequip(
+        &self,
+        name: &CharacterName,
+        slot: EquipmentSlot,
+    ) -> Result<EquipRequestData, ArtifactsError<CharacterUnequipError>> {
+        debug!("Unequipping slot {} for character: {}", slot, name);
+
+        let body = serde_json::json!({
+            "slot": slot,
+        });
+
+        let url = format!("{}/my/{}/action/unequip", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(&body)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<EquipRequestData>::parse_json(resp).await?;
+        record_action(name, "unequip", &data.cooldown, ActionGains::default());
+        Ok(data)
+    }
+
+    /// Creates a Grand Exchange sell order for `quantity` of `code` from the
+    /// specified character's inventory, at `price` per item. The character
+    /// must be on a Grand Exchange map.
+    pub async fn ge_sell(
+        &self,
+        name: &CharacterName,
+        code: &ItemCode,
+        quantity: u32,
+        price: u32,
+    ) -> Result<GeOrderTransaction, ArtifactsError<CharacterGeSellError>> {
+        debug!(
+            "Creating sell order for {} x{} at {} for character: {}",
+            code, quantity, price, name
+        );
+
+        let body = serde_json::json!({
+            "code": code,
+            "quantity": quantity,
+            "price": price,
+        });
+
+        let url = format!("{}/my/{}/action/grandexchange/sell", self.base_url, name);
+        let resp = self
+            .client
+            .post(url)
+            .json(&body)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let data = ArtifactsApiResponse::<GeOrderTransaction>::parse_json(resp).await?;
+        record_action(name, "sell", &data.cooldown, ActionGains::default());
+        Ok(data)
+    }
 }
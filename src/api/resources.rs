@@ -0,0 +1,81 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::{
+        resource::Resource,
+        skill::Skill,
+        types::{ItemCode, ResourceCode},
+    },
+};
+
+make_error!(GetAllResourcesError);
+
+make_error!(GetResourceError,
+    404 => ResourceNotFound
+        => "Resource not found",
+);
+
+// Query parameters for fetching resources
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, Setters, Serialize, Deserialize)]
+#[setters(strip_option)]
+pub struct ResourceQuery {
+    pub skill: Option<Skill>,
+    pub drop: Option<ItemCode>,
+    pub min_level: Option<u32>,
+    pub max_level: Option<u32>,
+    #[serde(rename = "page")]
+    pub page_number: Option<u32>,
+    #[serde(rename = "size")]
+    pub page_size: Option<u32>,
+}
+
+impl ArtifactsClient {
+    /// Fetches resources based on the provided query parameters.
+    pub async fn get_resources(
+        &self,
+        query: &ResourceQuery,
+    ) -> Result<Vec<Resource>, ArtifactsError<GetAllResourcesError>> {
+        debug!(
+            "Fetching resources with query: {}",
+            serde_json::to_string(query)
+                .unwrap_or_else(|_| "Failed to serialize query".to_string())
+        );
+
+        let url = format!("{}/resources", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .query(&query)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let resources = ArtifactsApiResponse::<Vec<Resource>>::parse_json(resp).await?;
+        Ok(resources)
+    }
+
+    /// Fetches a specific resource by its code.
+    pub async fn get_resource(
+        &self,
+        code: &ResourceCode,
+    ) -> Result<Resource, ArtifactsError<GetResourceError>> {
+        debug!("Fetching resource with code: {}", code);
+
+        let url = format!("{}/resources/{}", self.base_url, code);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let resource = ArtifactsApiResponse::<Resource>::parse_json(resp).await?;
+        Ok(resource)
+    }
+}
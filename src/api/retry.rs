@@ -0,0 +1,479 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use http::Extensions;
+use reqwest::{Method, Request, Response, StatusCode};
+use reqwest_middleware::{Middleware, Next, Result};
+use reqwest_retry::{RetryDecision, RetryPolicy, Retryable, default_on_request_failure};
+use tracing::{debug, warn};
+
+use crate::{
+    api::{ArtifactsApiResponse, characters::GetCharacterError},
+    cooldown,
+    metrics::Metrics,
+    models::character::Character,
+};
+
+/// Returned by action endpoints while a previous action of the same character
+/// is still being processed.
+const ACTION_IN_PROGRESS: u16 = 486;
+
+/// How long to wait before retrying an action rejected because the previous
+/// one is still being processed, which only takes a moment.
+const ACTION_IN_PROGRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// The most times an action is retried while the previous one is still being
+/// processed.
+const MAX_IN_PROGRESS_RETRIES: u32 = 10;
+
+/// The longest wait announced by a rate limited response that is honoured.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// The headers a rate limited response can announce the wait in, as seconds
+/// to wait or as the Unix time the limit resets at.
+const RATE_LIMIT_RESET_HEADERS: [&str; 2] = ["x-ratelimit-reset", "ratelimit-reset"];
+
+/// The kind of endpoint a request targets, which decides which failures can
+/// safely be retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndpointCategory {
+    /// Reads data without changing anything, so can always be retried.
+    Data,
+    /// Makes the named character perform an action, e.g. fight or craft.
+    /// Retrying one that was applied would perform it twice.
+    Action { character: String },
+    /// Any other request that changes something, e.g. creating a character.
+    Other,
+}
+
+impl EndpointCategory {
+    pub fn of(method: &Method, path: &str) -> Self {
+        if *method == Method::GET {
+            return Self::Data;
+        }
+
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+        match segments.as_slice() {
+            ["my", character, "action", ..] => Self::Action {
+                character: character.to_string(),
+            },
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The backoff used to retry the requests of each [`EndpointCategory`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicies<P> {
+    pub data: P,
+    pub action: P,
+    pub other: P,
+}
+
+impl<P> RetryPolicies<P> {
+    fn of(&self, category: &EndpointCategory) -> &P {
+        match category {
+            EndpointCategory::Data => &self.data,
+            EndpointCategory::Action { .. } => &self.action,
+            EndpointCategory::Other => &self.other,
+        }
+    }
+}
+
+/// Returns how long a rate limited response asks to wait before retrying,
+/// from its `Retry-After` header or its rate limit reset header.
+fn rate_limit_wait(resp: &Response) -> Option<Duration> {
+    let header = |name: &str| resp.headers().get(name)?.to_str().ok();
+
+    let wait = match header("retry-after") {
+        Some(value) => match value.trim().parse::<u64>() {
+            Ok(seconds) => Duration::from_secs(seconds),
+            Err(_) => {
+                let at = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+                (at.with_timezone(&Utc) - Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+            }
+        },
+        None => {
+            let value = RATE_LIMIT_RESET_HEADERS.into_iter().find_map(header)?;
+            let value = value.trim().parse::<f64>().ok()?;
+            // Values too large to be a wait are a Unix time.
+            let seconds = if value > 1e9 {
+                value - Utc::now().timestamp() as f64
+            } else {
+                value
+            };
+            Duration::try_from_secs_f64(seconds.max(0.0)).ok()?
+        }
+    };
+
+    Some(wait.min(MAX_RATE_LIMIT_WAIT))
+}
+
+/// What to do with the result of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Done,
+    Retry,
+    /// The request may or may not have been applied, so it is only retried
+    /// once it is known not to have been.
+    RetryIfNotApplied,
+}
+
+impl Verdict {
+    fn of(category: &EndpointCategory, result: &Result<Response>) -> Self {
+        let is_action = matches!(category, EndpointCategory::Action { .. });
+
+        match result {
+            Ok(resp) => match resp.status() {
+                // Rejected before being processed, whatever the endpoint.
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => Self::Retry,
+                status if status.as_u16() == ACTION_IN_PROGRESS && is_action => Self::Retry,
+                status if status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT => {
+                    match category {
+                        EndpointCategory::Data => Self::Retry,
+                        EndpointCategory::Action { .. } => Self::RetryIfNotApplied,
+                        EndpointCategory::Other => Self::Done,
+                    }
+                }
+                _ => Self::Done,
+            },
+            // The request never reached the server.
+            Err(reqwest_middleware::Error::Reqwest(e)) if e.is_connect() => Self::Retry,
+            Err(e) => match default_on_request_failure(e) {
+                Some(Retryable::Transient) => match category {
+                    EndpointCategory::Data => Self::Retry,
+                    EndpointCategory::Action { .. } => Self::RetryIfNotApplied,
+                    EndpointCategory::Other => Self::Done,
+                },
+                _ => Self::Done,
+            },
+        }
+    }
+}
+
+/// Retries failed requests to the Artifacts API after the delays of the
+/// [`RetryPolicy`] of their [`EndpointCategory`], according to the Artifacts
+/// status codes.
+///
+/// Rate limited requests wait as long as the response asks, if it says, and
+/// actions rejected because the previous one is still being processed are
+/// retried after a short fixed delay.
+///
+/// Actions that fail ambiguously, e.g. because the connection dropped after
+/// they were sent, are only retried if the character's cooldown shows they
+/// were not applied, so a fight is never performed twice.
+#[derive(Debug)]
+pub struct RetryMiddleware<P> {
+    policies: RetryPolicies<P>,
+}
+
+impl<P: RetryPolicy> RetryMiddleware<P> {
+    pub fn new(policies: RetryPolicies<P>) -> Self {
+        Self { policies }
+    }
+
+    /// Returns how long to wait before retrying a request that failed with
+    /// `result`, or `None` to give up.
+    fn delay(
+        &self,
+        category: &EndpointCategory,
+        result: &Result<Response>,
+        start: SystemTime,
+        retries: u32,
+        in_progress: u32,
+    ) -> Option<Duration> {
+        let status = result.as_ref().ok().map(Response::status);
+
+        if status.is_some_and(|s| s.as_u16() == ACTION_IN_PROGRESS) {
+            return (in_progress < MAX_IN_PROGRESS_RETRIES).then_some(ACTION_IN_PROGRESS_DELAY);
+        }
+
+        let RetryDecision::Retry { execute_after } =
+            self.policies.of(category).should_retry(start, retries)
+        else {
+            return None;
+        };
+        let backoff = execute_after
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO);
+
+        match result {
+            Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS => {
+                Some(rate_limit_wait(resp).unwrap_or(backoff))
+            }
+            _ => Some(backoff),
+        }
+    }
+
+    /// Returns whether an action sent by `character` at `sent` was applied,
+    /// which started a cooldown ending after it was sent. Characters only act
+    /// once their cooldown expired, so an earlier one predates the action.
+    async fn was_applied(
+        &self,
+        req: &Request,
+        character: &str,
+        sent: DateTime<Utc>,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> anyhow::Result<bool> {
+        let mut url = req.url().clone();
+        url.set_path(&format!("/characters/{}", character));
+        url.set_query(None);
+
+        let resp = next.run(Request::new(Method::GET, url), extensions).await?;
+        let character =
+            ArtifactsApiResponse::<Character>::parse_json::<GetCharacterError>(resp).await?;
+
+        Ok(character.cooldown_expiration > sent)
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: RetryPolicy + Send + Sync + 'static> Middleware for RetryMiddleware<P> {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let category = EndpointCategory::of(req.method(), req.url().path());
+        let start = SystemTime::now();
+        let mut retries = 0;
+        let mut in_progress = 0;
+
+        loop {
+            let attempt = req.try_clone().ok_or_else(|| {
+                reqwest_middleware::Error::Middleware(anyhow::anyhow!(
+                    "Request body cannot be cloned for retries"
+                ))
+            })?;
+
+            let sent = cooldown::now();
+            let result = next.clone().run(attempt, extensions).await;
+
+            let retry = match (Verdict::of(&category, &result), &category) {
+                (Verdict::Done, _) => false,
+                (Verdict::Retry, _) => true,
+                (Verdict::RetryIfNotApplied, EndpointCategory::Action { character }) => {
+                    match self
+                        .was_applied(&req, character, sent, extensions, next.clone())
+                        .await
+                    {
+                        Ok(applied) => {
+                            if applied {
+                                warn!(
+                                    "{} {} failed after being applied, not retrying",
+                                    req.method(),
+                                    req.url().path()
+                                );
+                            }
+                            !applied
+                        }
+                        Err(e) => {
+                            warn!(
+                                "Could not check whether {} {} was applied, not retrying: {}",
+                                req.method(),
+                                req.url().path(),
+                                e
+                            );
+                            false
+                        }
+                    }
+                }
+                (Verdict::RetryIfNotApplied, _) => false,
+            };
+
+            if !retry {
+                return result;
+            }
+
+            let Some(delay) = self.delay(&category, &result, start, retries, in_progress) else {
+                debug!(
+                    "Giving up on {} {} after {} retries",
+                    req.method(),
+                    req.url().path(),
+                    retries + in_progress
+                );
+                return result;
+            };
+
+            let status = result.as_ref().ok().map(Response::status);
+            if status.is_some_and(|s| s.as_u16() == ACTION_IN_PROGRESS) {
+                debug!(
+                    "{} {} is waiting for the previous action, retrying in {:?}",
+                    req.method(),
+                    req.url().path(),
+                    delay
+                );
+                in_progress += 1;
+            } else {
+                warn!(
+                    "Retrying {} {} in {:?} (attempt #{})",
+                    req.method(),
+                    req.url().path(),
+                    delay,
+                    retries + 1
+                );
+                retries += 1;
+            }
+
+            if status == Some(StatusCode::TOO_MANY_REQUESTS) {
+                Metrics::global().record_rate_limit_wait(delay);
+            }
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest_retry::policies::ExponentialBackoff;
+
+    use super::*;
+
+    fn response(status: u16, headers: &[(&str, &str)]) -> Result<Response> {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Ok(Response::from(builder.body("").unwrap()))
+    }
+
+    fn action() -> EndpointCategory {
+        EndpointCategory::Action {
+            character: "hero".to_string(),
+        }
+    }
+
+    fn middleware() -> RetryMiddleware<ExponentialBackoff> {
+        let policy = |retries| ExponentialBackoff::builder().build_with_max_retries(retries);
+        RetryMiddleware::new(RetryPolicies {
+            data: policy(3),
+            action: policy(3),
+            other: policy(0),
+        })
+    }
+
+    #[test]
+    fn categorizes_endpoints() {
+        assert_eq!(
+            EndpointCategory::of(&Method::GET, "/my/hero/action/fight"),
+            EndpointCategory::Data
+        );
+        assert_eq!(
+            EndpointCategory::of(&Method::POST, "/my/hero/action/fight"),
+            action()
+        );
+        assert_eq!(
+            EndpointCategory::of(&Method::POST, "/characters/create"),
+            EndpointCategory::Other
+        );
+    }
+
+    #[test]
+    fn retries_rejected_requests_on_every_endpoint() {
+        for category in [EndpointCategory::Data, action(), EndpointCategory::Other] {
+            for status in [429, 503] {
+                assert_eq!(
+                    Verdict::of(&category, &response(status, &[])),
+                    Verdict::Retry
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn retries_actions_in_progress() {
+        assert_eq!(Verdict::of(&action(), &response(486, &[])), Verdict::Retry);
+        assert_eq!(
+            Verdict::of(&EndpointCategory::Other, &response(486, &[])),
+            Verdict::Done
+        );
+    }
+
+    #[test]
+    fn checks_ambiguous_actions_before_retrying() {
+        assert_eq!(
+            Verdict::of(&EndpointCategory::Data, &response(502, &[])),
+            Verdict::Retry
+        );
+        assert_eq!(
+            Verdict::of(&action(), &response(502, &[])),
+            Verdict::RetryIfNotApplied
+        );
+        assert_eq!(
+            Verdict::of(&EndpointCategory::Other, &response(502, &[])),
+            Verdict::Done
+        );
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        for status in [200, 404, 497, 499] {
+            assert_eq!(
+                Verdict::of(&action(), &response(status, &[])),
+                Verdict::Done
+            );
+        }
+    }
+
+    #[test]
+    fn reads_rate_limit_waits() {
+        let wait = |headers| rate_limit_wait(&response(429, headers).unwrap());
+
+        assert_eq!(wait(&[("retry-after", "3")]), Some(Duration::from_secs(3)));
+        assert_eq!(
+            wait(&[("x-ratelimit-reset", "1.5")]),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(wait(&[("retry-after", "3600")]), Some(MAX_RATE_LIMIT_WAIT));
+        assert_eq!(wait(&[]), None);
+
+        let reset = (Utc::now().timestamp() + 30).to_string();
+        let waited = wait(&[("ratelimit-reset", &reset)]).unwrap();
+        assert!(waited > Duration::from_secs(28) && waited <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn waits_as_long_as_rate_limited_responses_ask() {
+        let delay = middleware().delay(
+            &EndpointCategory::Data,
+            &response(429, &[("retry-after", "7")]),
+            SystemTime::now(),
+            0,
+            0,
+        );
+        assert_eq!(delay, Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn retries_actions_in_progress_after_a_fixed_delay() {
+        let middleware = middleware();
+        let delay = |in_progress| {
+            middleware.delay(
+                &action(),
+                &response(486, &[]),
+                SystemTime::now(),
+                3,
+                in_progress,
+            )
+        };
+
+        // The backoff's own retries are not used up by busy actions.
+        assert_eq!(delay(0), Some(ACTION_IN_PROGRESS_DELAY));
+        assert_eq!(delay(MAX_IN_PROGRESS_RETRIES), None);
+    }
+
+    #[test]
+    fn uses_the_policy_of_the_category() {
+        let middleware = middleware();
+        let delay =
+            |category| middleware.delay(&category, &response(503, &[]), SystemTime::now(), 0, 0);
+
+        assert!(delay(EndpointCategory::Data).is_some());
+        assert!(delay(EndpointCategory::Other).is_none());
+    }
+}
@@ -0,0 +1,29 @@
+use tracing::debug;
+
+use crate::{
+    api::{ArtifactsApiResponse, ArtifactsError, client::ArtifactsClient},
+    make_error,
+    models::status::ServerStatus,
+};
+
+make_error!(GetServerStatusError);
+
+impl ArtifactsClient {
+    /// Fetches the server status, including its current time and the season.
+    pub async fn server_status(
+        &self,
+    ) -> Result<ServerStatus, ArtifactsError<GetServerStatusError>> {
+        debug!("Fetching server status");
+
+        let url = format!("{}/", self.base_url);
+        let resp = self
+            .client
+            .get(url)
+            .bearer_auth(self.api_token.clone())
+            .send()
+            .await?;
+
+        let status = ArtifactsApiResponse::<ServerStatus>::parse_json(resp).await?;
+        Ok(status)
+    }
+}
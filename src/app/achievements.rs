@@ -0,0 +1,217 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Cell, LineGauge, Paragraph, Row, Table, TableState},
+};
+
+use crate::{
+    achievements::{self, AchievementPlanner},
+    api::client::ArtifactsClient,
+    bot::strategy::Strategy,
+    models::{achievement::AccountAchievement, character::Character},
+};
+
+/// The account's achievement progress, and the planner used to suggest how
+/// to advance it.
+#[derive(Debug, Clone, Default)]
+pub struct AchievementsState {
+    pub account: Option<String>,
+    pub achievements: Vec<AccountAchievement>,
+    pub planner: Option<AchievementPlanner>,
+    /// Whether completed achievements are listed too.
+    pub show_completed: bool,
+    /// The index of the selected row in [`AchievementsState::rows`].
+    pub selected: usize,
+    pub loading: bool,
+}
+
+impl AchievementsState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the authenticated account's achievements and the planner data.
+    pub async fn load(api: &ArtifactsClient) -> anyhow::Result<Self> {
+        let account = api.get_my_details().await?.username;
+        let achievements = achievements::load_progress(api, &account).await?;
+        let planner = AchievementPlanner::load(api).await?;
+
+        Ok(Self {
+            account: Some(account),
+            achievements,
+            planner: Some(planner),
+            ..Self::default()
+        })
+    }
+
+    /// Replaces the achievements with freshly loaded ones, keeping the
+    /// selection.
+    pub fn refresh(&mut self, loaded: Self) {
+        self.account = loaded.account;
+        self.achievements = loaded.achievements;
+        self.planner = loaded.planner;
+        self.loading = false;
+        self.selected = self.selected.min(self.rows().len().saturating_sub(1));
+    }
+
+    /// Returns the achievements to show, closest to completion first.
+    pub fn rows(&self) -> Vec<&AccountAchievement> {
+        let mut rows = self
+            .achievements
+            .iter()
+            .filter(|a| self.show_completed || !a.is_completed())
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| {
+            a.is_completed()
+                .cmp(&b.is_completed())
+                .then_with(|| b.progress().total_cmp(&a.progress()))
+        });
+        rows
+    }
+
+    pub fn selected_achievement(&self) -> Option<&AccountAchievement> {
+        self.rows().get(self.selected).copied()
+    }
+
+    /// Moves the selection by `offset`, wrapping around.
+    pub fn select_offset(&mut self, offset: isize) {
+        let len = self.rows().len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + offset).rem_euclid(len) as usize;
+        }
+    }
+
+    pub fn toggle_completed(&mut self) {
+        self.show_completed = !self.show_completed;
+        self.selected = 0;
+    }
+
+    /// The strategy that advances `achievement` for `character`, if any.
+    pub fn suggestion(
+        &self,
+        character: &Character,
+        achievement: &AccountAchievement,
+    ) -> Option<Strategy> {
+        if achievement.is_completed() {
+            return None;
+        }
+        self.planner.as_ref()?.strategy(character, achievement)
+    }
+}
+
+/// Lists the account's achievements with their progress and the strategy
+/// suggested for the selected character.
+#[derive(Debug)]
+pub struct AchievementsWidget<'a> {
+    character: Option<&'a Character>,
+}
+
+impl<'a> AchievementsWidget<'a> {
+    pub fn new(character: Option<&'a Character>) -> Self {
+        Self { character }
+    }
+}
+
+impl StatefulWidget for &AchievementsWidget<'_> {
+    type State = AchievementsState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let completed = state
+            .achievements
+            .iter()
+            .filter(|a| a.is_completed())
+            .collect::<Vec<_>>();
+        let points = completed.iter().map(|a| a.achievement.points).sum::<u32>();
+        let title = format!(
+            " Achievements: {} | {} points ",
+            state.account.as_deref().unwrap_or("-"),
+            points
+        );
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let rows = state.rows();
+        if rows.is_empty() {
+            let message = if state.loading {
+                "Loading achievements..."
+            } else {
+                "No achievements"
+            };
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(inner, buf);
+            return;
+        }
+
+        let [gauge_area, table_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+
+        let total = state.achievements.len();
+        LineGauge::default()
+            .filled_style(Style::default().fg(Color::Green))
+            .ratio(completed.len() as f64 / total.max(1) as f64)
+            .label(format!("Completed {}/{}", completed.len(), total))
+            .render(gauge_area, buf);
+
+        let table_rows = rows.iter().map(|a| {
+            let suggestion = self
+                .character
+                .and_then(|character| state.suggestion(character, a))
+                .map(|strategy| strategy.to_string())
+                .unwrap_or_default();
+            let progress = format!(
+                "{}/{} ({:.0}%)",
+                a.current.min(a.achievement.total),
+                a.achievement.total,
+                100.0 * a.progress()
+            );
+            let style = if a.is_completed() {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+
+            Row::new(vec![
+                Cell::from(a.achievement.name.clone()),
+                Cell::from(format!("{:?}", a.achievement.achievement_type)),
+                Cell::from(a.achievement.target.clone().unwrap_or_default()),
+                Cell::from(progress),
+                Cell::from(a.achievement.points.to_string()),
+                Cell::from(suggestion),
+            ])
+            .style(style)
+        });
+
+        let suggested = match self.character {
+            Some(character) => format!("Suggested for {}", character.name),
+            None => "Suggested".to_string(),
+        };
+        let table = Table::new(
+            table_rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Length(18),
+                Constraint::Length(6),
+                Constraint::Fill(2),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Name".to_string(),
+                "Type".to_string(),
+                "Target".to_string(),
+                "Progress".to_string(),
+                "Points".to_string(),
+                suggested,
+            ])
+            .bold(),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut table_state = TableState::default().with_selected(Some(state.selected));
+        StatefulWidget::render(table, table_area, buf, &mut table_state);
+    }
+}
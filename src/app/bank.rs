@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+};
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE, bank::BankItemQuery, client::ArtifactsClient, fetch_all_pages,
+        items::ItemQuery,
+    },
+    models::{
+        item::{Item, SimpleItem},
+        types::ItemCode,
+    },
+};
+
+/// The column the bank contents are sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BankSort {
+    #[default]
+    Code,
+    QuantityDesc,
+    Type,
+    LevelDesc,
+}
+
+impl BankSort {
+    pub fn next(&self) -> Self {
+        match self {
+            BankSort::Code => BankSort::QuantityDesc,
+            BankSort::QuantityDesc => BankSort::Type,
+            BankSort::Type => BankSort::LevelDesc,
+            BankSort::LevelDesc => BankSort::Code,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BankSort::Code => "code",
+            BankSort::QuantityDesc => "quantity",
+            BankSort::Type => "type",
+            BankSort::LevelDesc => "level",
+        }
+    }
+}
+
+/// The contents of the bank, with the item details needed to sort and filter
+/// them.
+#[derive(Debug, Clone, Default)]
+pub struct BankWidgetState {
+    pub bank: Vec<SimpleItem>,
+    pub items: HashMap<ItemCode, Item>,
+    pub sort: BankSort,
+    /// Only items of this type are shown, if set.
+    pub filter: Option<String>,
+    /// The index of the selected row in [`BankWidgetState::rows`].
+    pub selected: usize,
+    pub loading: bool,
+}
+
+impl BankWidgetState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the bank contents and every item.
+    pub async fn load(api: &ArtifactsClient) -> anyhow::Result<Self> {
+        let bank = fetch_all_pages(|page| async move {
+            api.get_bank_items(
+                &BankItemQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let items = fetch_all_pages(|page| async move {
+            api.get_items(
+                &ItemQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        Ok(Self {
+            bank,
+            items: items.into_iter().map(|i| (i.code.clone(), i)).collect(),
+            ..Self::default()
+        })
+    }
+
+    /// Replaces the contents with freshly loaded ones, keeping the sort,
+    /// filter and selection.
+    pub fn refresh(&mut self, loaded: Self) {
+        self.bank = loaded.bank;
+        self.items = loaded.items;
+        self.loading = false;
+        self.selected = self.selected.min(self.rows().len().saturating_sub(1));
+    }
+
+    fn item_type(&self, code: &str) -> &str {
+        self.items.get(code).map_or("", |i| i.item_type.as_str())
+    }
+
+    fn level(&self, code: &str) -> u32 {
+        self.items.get(code).map_or(0, |i| i.level)
+    }
+
+    /// Returns the bank items to show, filtered and sorted.
+    pub fn rows(&self) -> Vec<&SimpleItem> {
+        let mut rows = self
+            .bank
+            .iter()
+            .filter(|item| {
+                self.filter
+                    .as_deref()
+                    .is_none_or(|filter| self.item_type(&item.code) == filter)
+            })
+            .collect::<Vec<_>>();
+
+        match self.sort {
+            BankSort::Code => rows.sort_by(|a, b| a.code.cmp(&b.code)),
+            BankSort::QuantityDesc => rows.sort_by_key(|item| std::cmp::Reverse(item.quantity)),
+            BankSort::Type => rows.sort_by(|a, b| {
+                self.item_type(&a.code)
+                    .cmp(self.item_type(&b.code))
+                    .then_with(|| a.code.cmp(&b.code))
+            }),
+            BankSort::LevelDesc => rows.sort_by(|a, b| {
+                self.level(&b.code)
+                    .cmp(&self.level(&a.code))
+                    .then_with(|| a.code.cmp(&b.code))
+            }),
+        }
+
+        rows
+    }
+
+    pub fn selected_item(&self) -> Option<&SimpleItem> {
+        self.rows().get(self.selected).copied()
+    }
+
+    /// Moves the selection by `offset`, wrapping around.
+    pub fn select_offset(&mut self, offset: isize) {
+        let len = self.rows().len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + offset).rem_euclid(len) as usize;
+        }
+    }
+
+    pub fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.selected = 0;
+    }
+
+    /// Cycles the type filter through the types of the items in the bank,
+    /// then back to showing everything.
+    pub fn cycle_filter(&mut self) {
+        let mut types = self
+            .bank
+            .iter()
+            .map(|item| self.item_type(&item.code))
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>();
+        types.sort();
+        types.dedup();
+
+        let next = match &self.filter {
+            None => types.first(),
+            Some(filter) => types
+                .iter()
+                .position(|t| t == filter)
+                .and_then(|i| types.get(i + 1)),
+        };
+
+        self.filter = next.map(|t| t.to_string());
+        self.selected = 0;
+    }
+}
+
+/// Lists the bank contents.
+#[derive(Debug, Default)]
+pub struct BankWidget;
+
+impl BankWidget {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl StatefulWidget for &BankWidget {
+    type State = BankWidgetState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let rows = state.rows();
+        let title = format!(
+            " Bank ({} items) | Sort: {} | Type: {} ",
+            rows.len(),
+            state.sort.label(),
+            state.filter.as_deref().unwrap_or("all")
+        );
+        let block = Block::default().borders(Borders::ALL).title(title);
+
+        if rows.is_empty() {
+            let message = if state.loading {
+                "Loading bank..."
+            } else {
+                "No items"
+            };
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let table_rows = rows.iter().map(|slot| {
+            let item = state.items.get(&slot.code);
+            Row::new(vec![
+                Cell::from(slot.code.to_string()),
+                Cell::from(item.map(|i| i.name.clone()).unwrap_or_default()),
+                Cell::from(item.map(|i| i.item_type.clone()).unwrap_or_default()),
+                Cell::from(item.map(|i| i.level.to_string()).unwrap_or_default()),
+                Cell::from(slot.quantity.to_string()),
+            ])
+        });
+
+        let table = Table::new(
+            table_rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Length(6),
+                Constraint::Length(10),
+            ],
+        )
+        .header(Row::new(vec!["Code", "Name", "Type", "Level", "Quantity"]).bold())
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(block);
+
+        let mut table_state = TableState::default().with_selected(Some(state.selected));
+        StatefulWidget::render(table, area, buf, &mut table_state);
+    }
+}
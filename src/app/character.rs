@@ -0,0 +1,265 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table, Tabs},
+};
+
+use crate::{
+    models::{character::Character, equipment::EquipmentSlot, fight::Element, skill::Skill},
+    simulator::CombatStats,
+};
+
+/// The tabs of the character detail pane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CharacterTab {
+    #[default]
+    Inventory,
+    Equipment,
+    Skills,
+    Stats,
+    Task,
+}
+
+impl CharacterTab {
+    pub const ALL: [CharacterTab; 5] = [
+        CharacterTab::Inventory,
+        CharacterTab::Equipment,
+        CharacterTab::Skills,
+        CharacterTab::Stats,
+        CharacterTab::Task,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            CharacterTab::Inventory => "Inventory",
+            CharacterTab::Equipment => "Equipment",
+            CharacterTab::Skills => "Skills",
+            CharacterTab::Stats => "Stats",
+            CharacterTab::Task => "Task",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|t| t == self).unwrap_or(0)
+    }
+
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Draws `ratio` as a labelled gauge, clamping it to `0..=1`.
+fn ratio_gauge(ratio: f64, label: String, color: Color) -> Gauge<'static> {
+    Gauge::default()
+        .gauge_style(Style::default().fg(color).bg(Color::Black))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(label)
+}
+
+fn ratio(value: u32, total: u32) -> f64 {
+    if total > 0 {
+        value as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// Shows the details of a single character, one tab at a time.
+#[derive(Debug)]
+pub struct CharacterDetailWidget<'a> {
+    character: Option<&'a Character>,
+    tab: CharacterTab,
+}
+
+impl<'a> CharacterDetailWidget<'a> {
+    pub fn new(character: Option<&'a Character>, tab: CharacterTab) -> Self {
+        Self { character, tab }
+    }
+
+    fn render_inventory(character: &Character, area: Rect, buf: &mut Buffer) {
+        let [gauge_area, table_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+
+        let used = character.inventory_count();
+        ratio_gauge(
+            ratio(used, character.inventory_max_items),
+            format!("{}/{} items", used, character.inventory_max_items),
+            Color::Blue,
+        )
+        .render(gauge_area, buf);
+
+        let rows = character
+            .inventory
+            .iter()
+            .filter(|slot| slot.quantity > 0)
+            .map(|slot| {
+                Row::new(vec![
+                    Cell::from(slot.slot.to_string()),
+                    Cell::from(slot.code.to_string()),
+                    Cell::from(slot.quantity.to_string()),
+                ])
+            });
+
+        Widget::render(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(6),
+                    Constraint::Fill(1),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(Row::new(vec!["Slot", "Item", "Quantity"]).bold()),
+            table_area,
+            buf,
+        );
+    }
+
+    fn render_equipment(character: &Character, area: Rect, buf: &mut Buffer) {
+        let rows = EquipmentSlot::ALL.iter().map(|slot| {
+            Row::new(vec![
+                Cell::from(slot.to_string()),
+                Cell::from(
+                    character
+                        .equipped(*slot)
+                        .map_or("-", |c| c.as_str())
+                        .to_string(),
+                ),
+            ])
+        });
+
+        Widget::render(
+            Table::new(rows, [Constraint::Length(12), Constraint::Fill(1)])
+                .header(Row::new(vec!["Slot", "Item"]).bold()),
+            area,
+            buf,
+        );
+    }
+
+    fn render_skills(character: &Character, area: Rect, buf: &mut Buffer) {
+        let areas = Layout::vertical(
+            std::iter::repeat_n(Constraint::Length(1), Skill::ALL.len()).collect::<Vec<_>>(),
+        )
+        .split(area);
+
+        for (skill, area) in Skill::ALL.iter().zip(areas.iter()) {
+            let [label_area, gauge_area] =
+                Layout::horizontal([Constraint::Length(22), Constraint::Fill(1)]).areas(*area);
+
+            Paragraph::new(format!("{} Lv. {}", skill, character.skill_level(*skill)))
+                .render(label_area, buf);
+
+            let (xp, max_xp) = character.skill_xp(*skill);
+            ratio_gauge(
+                ratio(xp, max_xp),
+                format!("{}/{} XP", xp, max_xp),
+                Color::Green,
+            )
+            .render(gauge_area, buf);
+        }
+    }
+
+    fn render_stats(character: &Character, area: Rect, buf: &mut Buffer) {
+        let stats = CombatStats::from(character);
+
+        let [summary_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        Paragraph::new(vec![
+            Line::from(format!(
+                "Level {} | HP {}/{} | {} gold",
+                character.level, character.hp, character.max_hp, character.gold
+            )),
+            Line::from(format!(
+                "Damage +{}% | Critical strike {}%",
+                stats.dmg, stats.critical_strike
+            )),
+        ])
+        .render(summary_area, buf);
+
+        let rows = Element::ALL.iter().map(|element| {
+            Row::new(vec![
+                Cell::from(element.as_str()),
+                Cell::from(stats.attack.get(*element).to_string()),
+                Cell::from(format!("{}%", stats.dmg_element.get(*element))),
+                Cell::from(format!("{}%", stats.res.get(*element))),
+            ])
+        });
+
+        Widget::render(
+            Table::new(rows, [Constraint::Length(10); 4])
+                .header(Row::new(vec!["Element", "Attack", "Damage", "Resist"]).bold()),
+            table_area,
+            buf,
+        );
+    }
+
+    fn render_task(character: &Character, area: Rect, buf: &mut Buffer) {
+        if character.task.is_empty() {
+            Paragraph::new("No active task")
+                .style(Style::default().fg(Color::Gray))
+                .render(area, buf);
+            return;
+        }
+
+        let [label_area, gauge_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(area);
+
+        Paragraph::new(format!("{} task: {}", character.task_type, character.task))
+            .render(label_area, buf);
+
+        ratio_gauge(
+            ratio(character.task_progress, character.task_total),
+            format!("{}/{}", character.task_progress, character.task_total),
+            Color::Magenta,
+        )
+        .render(gauge_area, buf);
+    }
+}
+
+impl Widget for &CharacterDetailWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.character {
+            Some(c) => format!(" Lv. {} {} ", c.level, c.name),
+            None => " Character ".to_string(),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let Some(character) = self.character else {
+            Paragraph::new("No character selected")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(inner, buf);
+            return;
+        };
+
+        let [tabs_area, content_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+
+        Tabs::new(CharacterTab::ALL.iter().map(|t| t.title()))
+            .select(self.tab.index())
+            .highlight_style(Style::default().fg(Color::Yellow).bold())
+            .render(tabs_area, buf);
+
+        match self.tab {
+            CharacterTab::Inventory => {
+                CharacterDetailWidget::render_inventory(character, content_area, buf)
+            }
+            CharacterTab::Equipment => {
+                CharacterDetailWidget::render_equipment(character, content_area, buf)
+            }
+            CharacterTab::Skills => {
+                CharacterDetailWidget::render_skills(character, content_area, buf)
+            }
+            CharacterTab::Stats => {
+                CharacterDetailWidget::render_stats(character, content_area, buf)
+            }
+            CharacterTab::Task => CharacterDetailWidget::render_task(character, content_area, buf),
+        }
+    }
+}
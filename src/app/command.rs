@@ -0,0 +1,397 @@
+use tracing::{error, info};
+
+use crate::{
+    api::client::ArtifactsClient,
+    bot::{
+        BotContext,
+        control::{BotCommand, BotController, BotStatus, OneOffAction},
+        server::ControlClient,
+        strategy::Strategy,
+    },
+    cooldown,
+    models::{character::Character, equipment::EquipmentSlot},
+    world::WorldState,
+};
+
+/// The commands accepted by the palette, in the order they are suggested.
+const COMMANDS: [&str; 10] = [
+    "move", "fight", "rest", "gather", "craft", "deposit", "equip", "pause", "resume", "strategy",
+];
+
+/// A command entered in the palette for the selected character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteCommand {
+    Action(OneOffAction),
+    PauseBot,
+    ResumeBot,
+    /// Switch the bot to another strategy.
+    SetStrategy(Strategy),
+}
+
+impl std::str::FromStr for PaletteCommand {
+    type Err = anyhow::Error;
+
+    /// Parses commands such as `move 1 2`, `craft copper 5`, `deposit all`,
+    /// `equip wooden_stick weapon`, `pause bot` or `strategy fight chicken`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow::anyhow!("Invalid command: {}", s);
+
+        let action = match s.split_whitespace().collect::<Vec<_>>()[..] {
+            ["move", x, y] => OneOffAction::Move {
+                x: x.parse().map_err(|_| invalid())?,
+                y: y.parse().map_err(|_| invalid())?,
+            },
+            ["fight"] => OneOffAction::Fight,
+            ["rest"] => OneOffAction::Rest,
+            ["gather"] => OneOffAction::Gather,
+            ["craft", code] => OneOffAction::Craft {
+                code: code.into(),
+                quantity: 1,
+            },
+            ["craft", code, quantity] => OneOffAction::Craft {
+                code: code.into(),
+                quantity: quantity.parse().map_err(|_| invalid())?,
+            },
+            ["deposit", "all"] => OneOffAction::DepositAll,
+            ["equip", code, slot] => OneOffAction::Equip {
+                code: code.into(),
+                slot: slot.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+            },
+            ["pause", "bot"] => return Ok(PaletteCommand::PauseBot),
+            ["resume", "bot"] => return Ok(PaletteCommand::ResumeBot),
+            ["strategy", ref strategy @ ..] if !strategy.is_empty() => {
+                return Ok(PaletteCommand::SetStrategy(strategy.join(" ").parse()?));
+            }
+            _ => return Err(invalid()),
+        };
+
+        Ok(PaletteCommand::Action(action))
+    }
+}
+
+/// The bots the palette can pause, resume or queue actions for.
+#[derive(Debug, Clone)]
+pub enum BotHandle {
+    /// Bots running in this process.
+    Local(BotController),
+    /// Bots running in another process, reached through its control server.
+    Remote(ControlClient),
+}
+
+impl BotHandle {
+    /// Returns whether a bot that can still act is running for `name`.
+    async fn is_running(&self, name: &str) -> bool {
+        let status = match self {
+            BotHandle::Local(controller) => controller.character(name).map(|c| c.status),
+            BotHandle::Remote(client) => client.character(name).await.ok().map(|c| c.status),
+        };
+
+        matches!(status, Some(BotStatus::Running | BotStatus::Paused))
+    }
+
+    async fn send(&self, name: &str, command: BotCommand) -> anyhow::Result<()> {
+        match self {
+            BotHandle::Local(controller) => Ok(controller.send(name, command)?),
+            BotHandle::Remote(client) => match command {
+                BotCommand::Pause => client.pause(name).await,
+                BotCommand::Resume => client.resume(name).await,
+                BotCommand::Stop => client.stop(name).await,
+                BotCommand::SetStrategy(strategy) => client.set_strategy(name, &strategy).await,
+                BotCommand::Queue(action) => client.queue(name, &action).await,
+            },
+        }
+    }
+}
+
+impl PaletteCommand {
+    /// Runs the command for `character`, logging the result.
+    ///
+    /// Actions for a character with a running bot are queued on the bot so
+    /// they do not race its cooldowns. Otherwise they are performed directly
+    /// once the character's cooldown has expired, and their outcome is stored
+    /// in `world`.
+    pub async fn run(
+        self,
+        api: ArtifactsClient,
+        bots: Option<BotHandle>,
+        world: Option<WorldState>,
+        character: Character,
+    ) {
+        let name = character.name.clone();
+
+        let result = match (self, bots) {
+            (PaletteCommand::PauseBot, Some(bots)) => bots
+                .send(&name, BotCommand::Pause)
+                .await
+                .map(|()| format!("Pausing the bot for {}", name)),
+            (PaletteCommand::ResumeBot, Some(bots)) => bots
+                .send(&name, BotCommand::Resume)
+                .await
+                .map(|()| format!("Resuming the bot for {}", name)),
+            (PaletteCommand::SetStrategy(strategy), Some(bots)) => bots
+                .send(&name, BotCommand::SetStrategy(strategy.clone()))
+                .await
+                .map(|()| format!("Switching {} to {}", name, strategy)),
+            (
+                PaletteCommand::PauseBot
+                | PaletteCommand::ResumeBot
+                | PaletteCommand::SetStrategy(_),
+                None,
+            ) => Err(anyhow::anyhow!("No bots are running")),
+            (PaletteCommand::Action(action), Some(bots)) if bots.is_running(&name).await => bots
+                .send(&name, BotCommand::Queue(action.clone()))
+                .await
+                .map(|()| format!("Queued {} for {}", action, name)),
+            (PaletteCommand::Action(action), _) => {
+                let ctx = BotContext::new(api);
+                cooldown::sleep_until_expired(character.cooldown_expiration).await;
+
+                let result = action.perform(&ctx, &character).await;
+                if let Some(world) = &world {
+                    match &result {
+                        Ok(outcome) => world.record(outcome),
+                        Err(_) => world.refresh(),
+                    }
+                }
+
+                result.map(|outcome| {
+                    format!(
+                        "{}: {} done ({}s cooldown)",
+                        name, action, outcome.cooldown_seconds
+                    )
+                })
+            }
+        };
+
+        match result {
+            Ok(message) => info!(target: "command", "{}", message),
+            Err(e) => error!(target: "command", "{}", e),
+        }
+    }
+}
+
+/// The `:` prompt used to enter a [`PaletteCommand`].
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub input: String,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+    }
+
+    /// Returns the possible values of the word being typed, given the
+    /// selected character.
+    pub fn candidates(&self, character: Option<&Character>) -> Vec<String> {
+        let words = self.input.split_whitespace().collect::<Vec<_>>();
+        let (done, partial) = if self.input.is_empty() || self.input.ends_with(' ') {
+            (&words[..], "")
+        } else {
+            (&words[..words.len() - 1], words[words.len() - 1])
+        };
+
+        let inventory = || {
+            let mut codes = character
+                .map(|c| {
+                    c.inventory
+                        .iter()
+                        .filter(|slot| slot.quantity > 0)
+                        .map(|slot| slot.code.to_string())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            codes.sort();
+            codes.dedup();
+            codes
+        };
+
+        let options = match done {
+            [] => COMMANDS.iter().map(|c| c.to_string()).collect(),
+            ["deposit"] => vec!["all".to_string()],
+            ["pause"] | ["resume"] => vec!["bot".to_string()],
+            ["strategy"] => ["fight", "gather", "script", "tree"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            ["equip"] => inventory(),
+            ["equip", _] => EquipmentSlot::ALL.iter().map(|s| s.to_string()).collect(),
+            _ => Vec::new(),
+        };
+
+        options
+            .into_iter()
+            .filter(|option| option.starts_with(partial))
+            .collect()
+    }
+
+    /// Completes the word being typed to the longest prefix shared by its
+    /// candidates, adding a space if there is only one.
+    pub fn complete(&mut self, character: Option<&Character>) {
+        let candidates = self.candidates(character);
+        let Some(first) = candidates.first() else {
+            return;
+        };
+
+        let prefix = candidates.iter().skip(1).fold(first.clone(), |prefix, c| {
+            prefix
+                .chars()
+                .zip(c.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect()
+        });
+
+        let start = self.input.rfind(' ').map_or(0, |i| i + 1);
+        self.input.truncate(start);
+        self.input.push_str(&prefix);
+        if candidates.len() == 1 {
+            self.input.push(' ');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::character::fixtures;
+
+    fn parse(s: &str) -> PaletteCommand {
+        s.parse().unwrap()
+    }
+
+    fn palette(input: &str) -> CommandPalette {
+        CommandPalette {
+            input: input.to_string(),
+        }
+    }
+
+    fn character() -> Character {
+        fixtures::character(json!({
+            "inventory": [
+                { "slot": 1, "code": "wooden_stick", "quantity": 1 },
+                { "slot": 2, "code": "wooden_shield", "quantity": 1 },
+                { "slot": 3, "code": "", "quantity": 0 },
+            ],
+        }))
+    }
+
+    #[test]
+    fn parses_actions() {
+        assert_eq!(
+            parse("move -1 2"),
+            PaletteCommand::Action(OneOffAction::Move { x: -1, y: 2 })
+        );
+        assert_eq!(parse("fight"), PaletteCommand::Action(OneOffAction::Fight));
+        assert_eq!(
+            parse("craft copper"),
+            PaletteCommand::Action(OneOffAction::Craft {
+                code: "copper".into(),
+                quantity: 1,
+            })
+        );
+        assert_eq!(
+            parse("craft copper 5"),
+            PaletteCommand::Action(OneOffAction::Craft {
+                code: "copper".into(),
+                quantity: 5,
+            })
+        );
+        assert_eq!(
+            parse("deposit all"),
+            PaletteCommand::Action(OneOffAction::DepositAll)
+        );
+        assert_eq!(
+            parse("equip wooden_stick weapon"),
+            PaletteCommand::Action(OneOffAction::Equip {
+                code: "wooden_stick".into(),
+                slot: EquipmentSlot::Weapon,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_bot_commands() {
+        assert_eq!(parse("pause bot"), PaletteCommand::PauseBot);
+        assert_eq!(parse("resume bot"), PaletteCommand::ResumeBot);
+        assert_eq!(
+            parse("strategy fight chicken"),
+            PaletteCommand::SetStrategy(Strategy::Fight {
+                monster: "chicken".into()
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_commands() {
+        for command in [
+            "",
+            "dance",
+            "move 1",
+            "move a b",
+            "craft copper many",
+            "deposit",
+            "equip wooden_stick hand",
+            "strategy",
+            "strategy swim",
+        ] {
+            assert!(
+                command.parse::<PaletteCommand>().is_err(),
+                "{:?} should be invalid",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn suggests_commands() {
+        assert_eq!(palette("").candidates(None), COMMANDS);
+        assert_eq!(palette("re").candidates(None), ["rest", "resume"]);
+        assert_eq!(palette("deposit ").candidates(None), ["all"]);
+    }
+
+    #[test]
+    fn suggests_items_and_slots_to_equip() {
+        let character = character();
+        assert_eq!(
+            palette("equip wooden_").candidates(Some(&character)),
+            ["wooden_shield", "wooden_stick"]
+        );
+        assert!(palette("equip ").candidates(None).is_empty());
+        assert_eq!(
+            palette("equip wooden_stick ring").candidates(None),
+            ["ring1", "ring2"]
+        );
+    }
+
+    #[test]
+    fn completes_to_the_shared_prefix() {
+        let mut palette = palette("re");
+        palette.complete(None);
+        assert_eq!(palette.input, "res");
+
+        palette.input.push('u');
+        palette.complete(None);
+        assert_eq!(palette.input, "resume ");
+
+        palette.complete(None);
+        assert_eq!(palette.input, "resume bot ");
+    }
+
+    #[test]
+    fn leaves_input_without_candidates() {
+        let mut palette = palette("dance");
+        palette.complete(None);
+        assert_eq!(palette.input, "dance");
+    }
+}
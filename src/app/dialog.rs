@@ -0,0 +1,156 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{bot::control::OneOffAction, models::types::ItemCode};
+
+/// The kind of action a [`ConfirmDialog`] performs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogKind {
+    Withdraw,
+    Sell,
+}
+
+/// An editable numeric field of a [`ConfirmDialog`].
+#[derive(Debug, Clone)]
+pub struct DialogField {
+    pub label: &'static str,
+    pub value: String,
+}
+
+/// Asks for confirmation, and the quantity and price, before withdrawing an
+/// item or listing it on the Grand Exchange.
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    pub kind: DialogKind,
+    pub code: ItemCode,
+    /// The character that performs the action.
+    pub character: String,
+    pub fields: Vec<DialogField>,
+    /// The index of the field being edited.
+    pub focus: usize,
+}
+
+impl ConfirmDialog {
+    pub fn withdraw(code: &ItemCode, character: &str, quantity: u32) -> Self {
+        Self {
+            kind: DialogKind::Withdraw,
+            code: code.clone(),
+            character: character.to_string(),
+            fields: vec![DialogField {
+                label: "Quantity",
+                value: quantity.to_string(),
+            }],
+            focus: 0,
+        }
+    }
+
+    pub fn sell(code: &ItemCode, character: &str, quantity: u32, price: Option<u32>) -> Self {
+        Self {
+            kind: DialogKind::Sell,
+            code: code.clone(),
+            character: character.to_string(),
+            fields: vec![
+                DialogField {
+                    label: "Quantity",
+                    value: quantity.to_string(),
+                },
+                DialogField {
+                    label: "Price each",
+                    value: price.map(|p| p.to_string()).unwrap_or_default(),
+                },
+            ],
+            focus: 0,
+        }
+    }
+
+    /// Types a digit into the focused field.
+    pub fn push(&mut self, c: char) {
+        if c.is_ascii_digit() {
+            self.fields[self.focus].value.push(c);
+        }
+    }
+
+    pub fn pop(&mut self) {
+        self.fields[self.focus].value.pop();
+    }
+
+    pub fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % self.fields.len();
+    }
+
+    fn value(&self, index: usize) -> anyhow::Result<u32> {
+        let field = &self.fields[index];
+        match field.value.parse::<u32>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(anyhow::anyhow!("{} must be a positive number", field.label)),
+        }
+    }
+
+    /// Returns the action to perform with the entered values.
+    pub fn action(&self) -> anyhow::Result<OneOffAction> {
+        let code = self.code.clone();
+
+        Ok(match self.kind {
+            DialogKind::Withdraw => OneOffAction::Withdraw {
+                code,
+                quantity: self.value(0)?,
+            },
+            DialogKind::Sell => OneOffAction::Sell {
+                code,
+                quantity: self.value(0)?,
+                price: self.value(1)?,
+            },
+        })
+    }
+}
+
+impl Widget for &ConfirmDialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.kind {
+            DialogKind::Withdraw => format!(" Withdraw {} to {} ", self.code, self.character),
+            DialogKind::Sell => format!(" Sell {} from {} ", self.code, self.character),
+        };
+
+        let mut lines = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let line = Line::from(format!("{}: {}", field.label, field.value));
+                if i == self.focus {
+                    line.style(Style::default().fg(Color::Yellow).bold())
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if self.kind == DialogKind::Sell
+            && let (Ok(quantity), Ok(price)) = (self.value(0), self.value(1))
+        {
+            lines.push(Line::from(format!(
+                "Total: {}",
+                quantity.saturating_mul(price)
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Tab: Next field | y/Enter: Confirm | n/Esc: Cancel").gray());
+
+        let width = (title.len() as u16 + 4).max(54).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width.saturating_sub(width)) / 2,
+            area.y + (area.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .render(popup, buf);
+    }
+}
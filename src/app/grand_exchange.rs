@@ -0,0 +1,178 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table},
+};
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE,
+        client::ArtifactsClient,
+        fetch_all_pages,
+        grand_exchange::{GeHistoryQuery, GeOrderQuery},
+    },
+    models::{
+        grand_exchange::{GeOrder, GeOrderHistory},
+        types::ItemCode,
+    },
+};
+
+/// The sell orders and recent sales of a single item.
+#[derive(Debug, Clone, Default)]
+pub struct GrandExchangeState {
+    /// The item shown, if one has been selected.
+    pub code: Option<ItemCode>,
+    /// Current sell orders, cheapest first.
+    pub orders: Vec<GeOrder>,
+    /// Recent sales, oldest first.
+    pub history: Vec<GeOrderHistory>,
+    pub loading: bool,
+}
+
+impl GrandExchangeState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the sell orders and sale history of `code`.
+    pub async fn load(api: &ArtifactsClient, code: &ItemCode) -> anyhow::Result<Self> {
+        let mut orders = fetch_all_pages(|page| async move {
+            api.get_ge_orders(
+                &GeOrderQuery::default()
+                    .code(code.clone())
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+        orders.sort_by_key(|o| o.price);
+
+        let mut history = api
+            .get_ge_history(code, &GeHistoryQuery::default().page_size(MAX_PAGE_SIZE))
+            .await?;
+        history.sort_by_key(|h| h.sold_at);
+
+        Ok(Self {
+            code: Some(code.clone()),
+            orders,
+            history,
+            loading: false,
+        })
+    }
+
+    /// The price to suggest for a new sell order: just below the cheapest
+    /// current order, or the last sale price if there are none.
+    pub fn suggested_price(&self) -> Option<u32> {
+        self.orders
+            .first()
+            .map(|o| o.price.saturating_sub(1).max(1))
+            .or_else(|| self.history.last().map(|h| h.price))
+    }
+}
+
+/// Shows the current sell orders and price history of an item.
+#[derive(Debug, Default)]
+pub struct GrandExchangeWidget;
+
+impl GrandExchangeWidget {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl StatefulWidget for &GrandExchangeWidget {
+    type State = GrandExchangeState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let title = match &state.code {
+            Some(code) => format!(" Grand Exchange: {} ", code),
+            None => " Grand Exchange ".to_string(),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if state.code.is_none() || state.loading {
+            let message = if state.loading {
+                "Loading orders..."
+            } else {
+                "Select an item in the bank"
+            };
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(inner, buf);
+            return;
+        }
+
+        let [orders_area, history_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(inner);
+
+        let order_rows = state.orders.iter().map(|order| {
+            Row::new(vec![
+                Cell::from(order.price.to_string()),
+                Cell::from(order.quantity.to_string()),
+                Cell::from(order.seller.clone()),
+            ])
+        });
+        Widget::render(
+            Table::new(
+                order_rows,
+                [
+                    Constraint::Length(10),
+                    Constraint::Length(10),
+                    Constraint::Fill(1),
+                ],
+            )
+            .header(Row::new(vec!["Price", "Quantity", "Seller"]).bold())
+            .block(
+                Block::default()
+                    .borders(Borders::RIGHT)
+                    .title(format!("Orders ({})", state.orders.len())),
+            ),
+            orders_area,
+            buf,
+        );
+
+        let [sparkline_area, sales_area] =
+            Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).areas(history_area);
+
+        let prices = state
+            .history
+            .iter()
+            .map(|h| h.price as u64)
+            .collect::<Vec<_>>();
+        let (min, max) = (
+            prices.iter().min().copied().unwrap_or(0),
+            prices.iter().max().copied().unwrap_or(0),
+        );
+        Sparkline::default()
+            .block(Block::default().title(format!("Price history ({}-{})", min, max)))
+            .data(&prices)
+            .style(Style::default().fg(Color::Yellow))
+            .render(sparkline_area, buf);
+
+        let sale_rows = state.history.iter().rev().map(|sale| {
+            Row::new(vec![
+                Cell::from(sale.sold_at.format("%m-%d %H:%M").to_string()),
+                Cell::from(sale.price.to_string()),
+                Cell::from(sale.quantity.to_string()),
+                Cell::from(sale.buyer.clone()),
+            ])
+        });
+        Widget::render(
+            Table::new(
+                sale_rows,
+                [
+                    Constraint::Length(12),
+                    Constraint::Length(8),
+                    Constraint::Length(8),
+                    Constraint::Fill(1),
+                ],
+            )
+            .header(Row::new(vec!["Sold", "Price", "Quantity", "Buyer"]).bold()),
+            sales_area,
+            buf,
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE, client::ArtifactsClient, fetch_all_pages, maps::MapQuery,
+        monsters::MonsterQuery, resources::ResourceQuery,
+    },
+    models::{
+        character::Character,
+        map::{Map, MapContentType},
+        monster::Monster,
+        resource::Resource,
+        types::{MonsterCode, Position, ResourceCode},
+    },
+};
+
+/// The largest zoom level. A tile is `2 * zoom` cells wide and `zoom` cells
+/// high.
+const MAX_ZOOM: u16 = 3;
+
+/// The world map, with the monsters and resources needed to describe its
+/// tiles.
+#[derive(Debug, Clone)]
+pub struct MapWidgetState {
+    pub tiles: HashMap<Position, Map>,
+    pub monsters: HashMap<MonsterCode, Monster>,
+    pub resources: HashMap<ResourceCode, Resource>,
+    /// The tile under the cursor, which the view is centred on.
+    pub cursor: Position,
+    pub zoom: u16,
+    /// Whether the info popup for the tile under the cursor is shown.
+    pub show_info: bool,
+}
+
+impl Default for MapWidgetState {
+    fn default() -> Self {
+        Self {
+            tiles: HashMap::new(),
+            monsters: HashMap::new(),
+            resources: HashMap::new(),
+            cursor: Position::default(),
+            zoom: 1,
+            show_info: false,
+        }
+    }
+}
+
+impl MapWidgetState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every map, monster and resource.
+    pub async fn load(api: &ArtifactsClient) -> anyhow::Result<Self> {
+        let maps = fetch_all_pages(|page| async move {
+            api.get_maps(
+                &MapQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let monsters = fetch_all_pages(|page| async move {
+            api.get_monsters(
+                &MonsterQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let resources = fetch_all_pages(|page| async move {
+            api.get_resources(
+                &ResourceQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        Ok(Self {
+            tiles: maps.into_iter().map(|m| (m.position, m)).collect(),
+            monsters: monsters.into_iter().map(|m| (m.code.clone(), m)).collect(),
+            resources: resources.into_iter().map(|r| (r.code.clone(), r)).collect(),
+            ..Self::default()
+        })
+    }
+
+    /// Moves the cursor by `(dx, dy)` tiles.
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.cursor = Position::new(self.cursor.x + dx, self.cursor.y + dy);
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + 1).min(MAX_ZOOM);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = self.zoom.saturating_sub(1).max(1);
+    }
+
+    pub fn toggle_info(&mut self) {
+        self.show_info = !self.show_info;
+    }
+
+    /// Moves the cursor to the character after the one currently under it.
+    pub fn focus_next_character(&mut self, characters: &[Character]) {
+        if characters.is_empty() {
+            return;
+        }
+
+        let next = characters
+            .iter()
+            .position(|c| c.position == self.cursor)
+            .map_or(0, |i| (i + 1) % characters.len());
+
+        self.cursor = characters[next].position;
+    }
+
+    /// Describes the tile under the cursor, for the info popup.
+    fn describe(&self, characters: &[Character]) -> Vec<Line<'static>> {
+        let cursor = self.cursor;
+        let mut lines = Vec::new();
+
+        let Some(map) = self.tiles.get(&cursor) else {
+            lines.push(Line::from(format!("{} Unexplored", cursor)));
+            return lines;
+        };
+
+        lines.push(Line::from(format!("{} {}", cursor, map.name)).bold());
+
+        match &map.content {
+            Some(content) => {
+                lines.push(Line::from(format!(
+                    "{:?}: {}",
+                    content.content_type, content.code
+                )));
+
+                match content.content_type {
+                    MapContentType::Monster => {
+                        if let Some(monster) = content
+                            .monster_code()
+                            .and_then(|code| self.monsters.get(&code))
+                        {
+                            lines.push(Line::from(format!(
+                                "{} | Lv. {} | HP {}",
+                                monster.name, monster.level, monster.hp
+                            )));
+                        }
+                    }
+                    MapContentType::Resource => {
+                        if let Some(resource) = content
+                            .resource_code()
+                            .and_then(|code| self.resources.get(&code))
+                        {
+                            lines.push(Line::from(format!(
+                                "{} | {} Lv. {}",
+                                resource.name, resource.skill, resource.level
+                            )));
+                            let drops = resource
+                                .drops
+                                .iter()
+                                .map(|d| d.code.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            lines.push(Line::from(format!("Drops: {}", drops)));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None => lines.push(Line::from("No content")),
+        }
+
+        let here = characters
+            .iter()
+            .filter(|c| c.position == cursor)
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        if !here.is_empty() {
+            lines.push(Line::from(format!("Characters: {}", here.join(", "))));
+        }
+
+        lines
+    }
+}
+
+/// The colour of a tile with the given content.
+fn tile_color(content_type: Option<MapContentType>) -> Color {
+    match content_type {
+        Some(MapContentType::Monster) => Color::Red,
+        Some(MapContentType::Resource) => Color::Green,
+        Some(MapContentType::Workshop) => Color::Yellow,
+        Some(MapContentType::Bank) => Color::Blue,
+        Some(MapContentType::GrandExchange) => Color::Magenta,
+        Some(MapContentType::TasksMaster) => Color::Cyan,
+        Some(MapContentType::Npc) => Color::LightYellow,
+        None => Color::DarkGray,
+    }
+}
+
+/// Draws the world grid with a marker for each character.
+#[derive(Debug, Default)]
+pub struct MapWidget<'a> {
+    characters: &'a [Character],
+}
+
+impl<'a> MapWidget<'a> {
+    pub fn new(characters: &'a [Character]) -> Self {
+        Self { characters }
+    }
+}
+
+impl StatefulWidget for &MapWidget<'_> {
+    type State = MapWidgetState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Map {} ", state.cursor));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if state.tiles.is_empty() {
+            Paragraph::new("Loading map...")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(inner, buf);
+            return;
+        }
+
+        let tile_width = state.zoom * 2;
+        let tile_height = state.zoom;
+        let columns = (inner.width / tile_width) as i32;
+        let rows = (inner.height / tile_height) as i32;
+        let left = state.cursor.x - columns / 2;
+        let top = state.cursor.y - rows / 2;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let position = Position::new(left + column, top + row);
+                let tile_area = Rect::new(
+                    inner.x + column as u16 * tile_width,
+                    inner.y + row as u16 * tile_height,
+                    tile_width,
+                    tile_height,
+                );
+
+                let Some(map) = state.tiles.get(&position) else {
+                    continue;
+                };
+
+                let content_type = map.content.as_ref().map(|c| c.content_type);
+                let mut style = Style::default()
+                    .bg(tile_color(content_type))
+                    .fg(Color::White);
+                if position == state.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                buf.set_style(tile_area, style);
+
+                // Mark characters by the first letter of their name, or fall
+                // back to the content code when zoomed in enough to read it.
+                let markers = self
+                    .characters
+                    .iter()
+                    .filter(|c| c.position == position)
+                    .filter_map(|c| c.name.chars().next())
+                    .collect::<String>();
+
+                let label = if !markers.is_empty() {
+                    Span::styled(markers, style.add_modifier(Modifier::BOLD))
+                } else if state.zoom > 1 {
+                    Span::styled(
+                        map.content
+                            .as_ref()
+                            .map(|c| c.code.to_string())
+                            .unwrap_or_default(),
+                        style,
+                    )
+                } else {
+                    continue;
+                };
+
+                buf.set_span(tile_area.x, tile_area.y, &label, tile_width);
+            }
+        }
+
+        if state.show_info {
+            let lines = state.describe(self.characters);
+            let width = lines
+                .iter()
+                .map(|l| l.width() as u16 + 4)
+                .max()
+                .unwrap_or(20)
+                .min(inner.width);
+            let height = (lines.len() as u16 + 2).min(inner.height);
+            let popup = Rect::new(
+                inner.x + inner.width.saturating_sub(width),
+                inner.y,
+                width,
+                height,
+            );
+
+            Clear.render(popup, buf);
+            Paragraph::new(lines)
+                .block(Block::default().borders(Borders::ALL).title(" Tile "))
+                .render(popup, buf);
+        }
+    }
+}
@@ -0,0 +1,943 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures::{FutureExt, StreamExt};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
+};
+use tracing::error;
+use tui_logger::{TuiLoggerLevelOutput, TuiLoggerSmartWidget, TuiWidgetEvent, TuiWidgetState};
+
+use crate::api::client::ArtifactsClient;
+use crate::{
+    cooldown,
+    models::{character::Character, item::SimpleItem, types::ItemCode},
+    stats::SessionStats,
+    world::WorldState,
+};
+
+use self::{
+    achievements::{AchievementsState, AchievementsWidget},
+    bank::{BankWidget, BankWidgetState},
+    character::{CharacterDetailWidget, CharacterTab},
+    command::{BotHandle, CommandPalette, PaletteCommand},
+    dialog::ConfirmDialog,
+    grand_exchange::{GrandExchangeState, GrandExchangeWidget},
+    map::{MapWidget, MapWidgetState},
+    stats::StatsWidget,
+};
+
+/// The achievement progress panel.
+pub mod achievements;
+/// The bank browser panel.
+pub mod bank;
+/// The character detail pane.
+pub mod character;
+/// The `:` command palette for manual actions.
+pub mod command;
+/// The confirmation dialog for bank and Grand Exchange actions.
+pub mod dialog;
+/// The Grand Exchange orders and price history panel.
+pub mod grand_exchange;
+/// The world map panel.
+pub mod map;
+/// The session statistics dashboard.
+pub mod stats;
+
+#[derive(Debug, Clone, Default)]
+pub struct CharacterWidgetState {
+    pub characters: Vec<Character>,
+    pub direction: Direction,
+    /// The index of the character shown in the detail pane.
+    pub selected: usize,
+}
+
+impl CharacterWidgetState {
+    pub fn characters(mut self, characters: Vec<Character>) -> Self {
+        self.characters = characters;
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+}
+
+impl CharacterWidgetState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the selected character, if any.
+    pub fn selected_character(&self) -> Option<&Character> {
+        self.characters.get(self.selected)
+    }
+
+    /// Moves the selection by `offset`, wrapping around.
+    pub fn select_offset(&mut self, offset: isize) {
+        let len = self.characters.len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + offset).rem_euclid(len) as usize;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CharacterWidget;
+
+impl CharacterWidget {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl StatefulWidget for &CharacterWidget {
+    type State = CharacterWidgetState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let characters = state.characters.clone();
+        let num_chars = characters.len();
+
+        if num_chars == 0 {
+            Paragraph::new("No characters available")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(area, buf);
+            return;
+        }
+
+        let constraints = std::iter::repeat_n(Constraint::Fill(1), num_chars).collect::<Vec<_>>();
+
+        let areas = if state.direction == Direction::Horizontal {
+            Layout::horizontal(constraints).split(area)
+        } else {
+            Layout::vertical(constraints).split(area)
+        };
+
+        for (i, (char, area)) in characters.iter().zip(areas.iter()).enumerate() {
+            let [name_area, hp_bar_area, xp_bar_area] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(*area);
+
+            let level_text = format!("Lv. {} {}", char.level, char.name);
+            let [name_area, cooldown_area] = Layout::horizontal([
+                Constraint::Length(level_text.len() as u16 + 2),
+                Constraint::Fill(1),
+            ])
+            .areas(name_area);
+
+            let name_style = if i == state.selected {
+                Style::default().fg(Color::Yellow).bold()
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            Paragraph::new(Text::from(format!("Lv. {} {}", char.level, char.name)))
+                .style(name_style)
+                .render(name_area, buf);
+
+            let remaining_sec = cooldown::remaining(char.cooldown_expiration).num_seconds();
+
+            if remaining_sec > 0 {
+                Paragraph::new(Text::from(format!("(Cooldown: {}s)", remaining_sec as u32)))
+                    .style(Style::default().fg(Color::Yellow))
+                    .render(cooldown_area, buf);
+            }
+
+            // HP bar
+            let [hp_label_area, hp_bar_area] =
+                Layout::horizontal([Constraint::Length(12), Constraint::Max(20)])
+                    .areas(hp_bar_area);
+
+            Paragraph::new(Text::from(format!("HP: {}/{}", char.hp, char.max_hp)))
+                .render(hp_label_area, buf);
+
+            let hp_ratio = if char.max_hp > 0 {
+                char.hp as f64 / char.max_hp as f64
+            } else {
+                0.0
+            };
+
+            let hp_percentage = (100.0 * hp_ratio).round() as u16;
+
+            let color = if hp_percentage < 20 {
+                Color::Red
+            } else if hp_percentage < 50 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+
+            Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(Color::Black))
+                .ratio(hp_ratio)
+                .label(format!("{}%", hp_percentage))
+                .render(hp_bar_area, buf);
+
+            // XP bar
+            let [xp_label_area, xp_bar_area] =
+                Layout::horizontal([Constraint::Length(12), Constraint::Max(20)])
+                    .areas(xp_bar_area);
+
+            Paragraph::new(Text::from(format!("XP: {}/{}", char.xp, char.max_xp)))
+                .render(xp_label_area, buf);
+
+            let xp_ratio = if char.max_xp > 0 {
+                char.xp as f64 / char.max_xp as f64
+            } else {
+                0.0
+            };
+
+            let xp_percentage = (100.0 * xp_ratio).round() as u16;
+
+            Gauge::default()
+                .gauge_style(Style::default().fg(Color::Blue).bg(Color::Black))
+                .ratio(xp_ratio)
+                .label(format!("{}%", xp_percentage))
+                .render(xp_bar_area, buf);
+        }
+    }
+}
+
+/// The panel shown next to the character overview.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum View {
+    #[default]
+    Logs,
+    Map,
+    Character,
+    Bank,
+    GrandExchange,
+    Stats,
+    Achievements,
+}
+
+pub struct App {
+    client: ArtifactsClient,
+    bots: Option<BotHandle>,
+    /// Where the outcomes of actions performed directly are stored.
+    world: Option<WorldState>,
+    running: bool,
+    event_stream: EventStream,
+    view: View,
+    character_tab: CharacterTab,
+    /// The command being entered, while the palette is open.
+    palette: Option<CommandPalette>,
+    /// The action waiting for confirmation, if any.
+    dialog: Option<ConfirmDialog>,
+    tui_widget_state: TuiWidgetState,
+    character_widget: Arc<Mutex<CharacterWidgetState>>,
+    map_widget: Arc<Mutex<MapWidgetState>>,
+    bank_widget: Arc<Mutex<BankWidgetState>>,
+    grand_exchange: Arc<Mutex<GrandExchangeState>>,
+    stats: Arc<Mutex<SessionStats>>,
+    achievements: Arc<Mutex<AchievementsState>>,
+}
+
+impl App {
+    pub fn new(client: ArtifactsClient) -> Self {
+        Self {
+            client,
+            bots: None,
+            world: None,
+            running: false,
+            event_stream: EventStream::new(),
+            view: View::default(),
+            character_tab: CharacterTab::default(),
+            palette: None,
+            dialog: None,
+            tui_widget_state: TuiWidgetState::new()
+                .set_default_display_level(tui_logger::LevelFilter::Info),
+            character_widget: Arc::new(Mutex::new(CharacterWidgetState::new())),
+            map_widget: Arc::new(Mutex::new(MapWidgetState::new())),
+            bank_widget: Arc::new(Mutex::new(BankWidgetState::new())),
+            grand_exchange: Arc::new(Mutex::new(GrandExchangeState::new())),
+            stats: Arc::new(Mutex::new(SessionStats::new())),
+            achievements: Arc::new(Mutex::new(AchievementsState::new())),
+        }
+    }
+
+    /// Sets the bots that palette commands pause, resume and queue actions
+    /// for.
+    pub fn bots(mut self, bots: BotHandle) -> Self {
+        self.bots = Some(bots);
+        self
+    }
+
+    /// Sets the world state updated by actions performed without a bot.
+    pub fn world(mut self, world: WorldState) -> Self {
+        self.world = Some(world);
+        self
+    }
+
+    pub fn character_widget_state(&self) -> Arc<Mutex<CharacterWidgetState>> {
+        self.character_widget.clone()
+    }
+
+    pub fn map_widget_state(&self) -> Arc<Mutex<MapWidgetState>> {
+        self.map_widget.clone()
+    }
+
+    /// Returns the statistics shown in the stats view, to be fed with bot
+    /// events.
+    pub fn session_stats(&self) -> Arc<Mutex<SessionStats>> {
+        self.stats.clone()
+    }
+
+    pub async fn run<B: Backend>(mut self, mut terminal: Terminal<B>) -> anyhow::Result<()> {
+        self.running = true;
+
+        while self.running {
+            terminal.draw(|f| {
+                f.render_widget(&self, f.area());
+            })?;
+            self.handle_crossterm_events().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_crossterm_events(&mut self) -> anyhow::Result<()> {
+        tokio::select! {
+            event = self.event_stream.next().fuse() => {
+                if let Some(Ok(evt)) = event {
+                    match evt {
+                        Event::Key(key)
+                            if key.kind == KeyEventKind::Press
+                                => self.on_key_event(key),
+                        Event::Mouse(_) => {}
+                        Event::Resize(_, _) => {}
+                        _ => {}
+                    }
+                }
+            }
+            _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {
+                // Sleep for a short duration to avoid busy waiting.
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the key events and updates the state of [`App`].
+    fn on_key_event(&mut self, key: KeyEvent) {
+        if self.dialog.is_some() {
+            return self.on_dialog_key_event(key);
+        }
+        if self.palette.is_some() {
+            return self.on_palette_key_event(key);
+        }
+
+        match key.code {
+            KeyCode::Char(':') => {
+                self.palette = Some(CommandPalette::new());
+                return;
+            }
+            KeyCode::Char('q') => return self.quit(),
+            KeyCode::Char('c') | KeyCode::Char('C') if key.modifiers == KeyModifiers::CONTROL => {
+                return self.quit();
+            }
+            _ => {}
+        }
+
+        match self.view {
+            View::Logs => self.on_log_key_event(key),
+            View::Map => self.on_map_key_event(key),
+            View::Character => self.on_character_key_event(key),
+            View::Bank => self.on_bank_key_event(key),
+            View::GrandExchange => self.on_grand_exchange_key_event(key),
+            View::Stats => self.on_stats_key_event(key),
+            View::Achievements => self.on_achievements_key_event(key),
+        }
+    }
+
+    fn on_log_key_event(&mut self, key: KeyEvent) {
+        let state = &mut self.tui_widget_state;
+
+        match key.code {
+            KeyCode::Char('m') => self.view = View::Map,
+            KeyCode::Char('d') => self.view = View::Character,
+            KeyCode::Char('b') => self.open_bank(),
+            KeyCode::Char('s') => self.view = View::Stats,
+            KeyCode::Char('a') => self.open_achievements(),
+            KeyCode::Char(' ') => state.transition(TuiWidgetEvent::SpaceKey),
+            KeyCode::Esc => state.transition(TuiWidgetEvent::EscapeKey),
+            KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => {
+                state.transition(TuiWidgetEvent::PrevPageKey)
+            }
+            KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => {
+                state.transition(TuiWidgetEvent::NextPageKey)
+            }
+            KeyCode::Up => state.transition(TuiWidgetEvent::UpKey),
+            KeyCode::Down => state.transition(TuiWidgetEvent::DownKey),
+            KeyCode::Left => state.transition(TuiWidgetEvent::LeftKey),
+            KeyCode::Right => state.transition(TuiWidgetEvent::RightKey),
+            KeyCode::Char('+') => state.transition(TuiWidgetEvent::PlusKey),
+            KeyCode::Char('-') => state.transition(TuiWidgetEvent::MinusKey),
+            KeyCode::Char('h') => state.transition(TuiWidgetEvent::HideKey),
+            KeyCode::Char('f') => state.transition(TuiWidgetEvent::FocusKey),
+            _ => {}
+        }
+    }
+
+    fn on_map_key_event(&mut self, key: KeyEvent) {
+        let characters = self
+            .character_widget
+            .lock()
+            .map(|state| state.characters.clone())
+            .unwrap_or_default();
+        let Ok(mut state) = self.map_widget.lock() else {
+            return;
+        };
+
+        let step = if key.modifiers.contains(KeyModifiers::SHIFT) {
+            5
+        } else {
+            1
+        };
+
+        match key.code {
+            KeyCode::Char('m') | KeyCode::Esc => self.view = View::Logs,
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => state.pan(0, -step),
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => state.pan(0, step),
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => state.pan(-step, 0),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => state.pan(step, 0),
+            KeyCode::Char('+') | KeyCode::Char('=') => state.zoom_in(),
+            KeyCode::Char('-') => state.zoom_out(),
+            KeyCode::Char('i') | KeyCode::Enter => state.toggle_info(),
+            KeyCode::Char('c') => state.focus_next_character(&characters),
+            _ => {}
+        }
+    }
+
+    fn on_character_key_event(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('d') | KeyCode::Esc => self.view = View::Logs,
+            KeyCode::Left | KeyCode::BackTab => self.character_tab = self.character_tab.previous(),
+            KeyCode::Right | KeyCode::Tab => self.character_tab = self.character_tab.next(),
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('1'..='9') => {
+                let Ok(mut state) = self.character_widget.lock() else {
+                    return;
+                };
+                match key.code {
+                    KeyCode::Up => state.select_offset(-1),
+                    KeyCode::Down => state.select_offset(1),
+                    KeyCode::Char(c) => {
+                        let index = c as usize - '1' as usize;
+                        if index < state.characters.len() {
+                            state.selected = index;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_bank_key_event(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('b') | KeyCode::Esc => self.view = View::Logs,
+            KeyCode::Char('r') => self.refresh_bank(),
+            KeyCode::Char('c') => self.select_next_character(),
+            KeyCode::Char('g') | KeyCode::Enter => {
+                let code = self.selected_bank_item().map(|item| item.code);
+                if let Some(code) = code {
+                    self.load_grand_exchange(&code);
+                    self.view = View::GrandExchange;
+                }
+            }
+            KeyCode::Char('w') => {
+                let item = self.selected_bank_item();
+                match (item, self.selected_character()) {
+                    (Some(item), Some(character)) => {
+                        // Only suggest what fits in the inventory.
+                        let space = character
+                            .inventory_max_items
+                            .saturating_sub(character.inventory_count());
+                        let quantity = item.quantity.min(space).max(1);
+
+                        self.dialog = Some(ConfirmDialog::withdraw(
+                            &item.code,
+                            &character.name,
+                            quantity,
+                        ));
+                    }
+                    (_, None) => error!(target: "command", "No character selected"),
+                    (None, _) => {}
+                }
+            }
+            _ => {
+                let Ok(mut state) = self.bank_widget.lock() else {
+                    return;
+                };
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => state.select_offset(-1),
+                    KeyCode::Down | KeyCode::Char('j') => state.select_offset(1),
+                    KeyCode::Char('s') => state.cycle_sort(),
+                    KeyCode::Char('t') => state.cycle_filter(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn on_grand_exchange_key_event(&mut self, key: KeyEvent) {
+        let code = self
+            .grand_exchange
+            .lock()
+            .ok()
+            .and_then(|state| state.code.clone());
+
+        match key.code {
+            KeyCode::Char('g') | KeyCode::Esc => self.view = View::Bank,
+            KeyCode::Char('c') => self.select_next_character(),
+            KeyCode::Char('r') => {
+                if let Some(code) = code {
+                    self.load_grand_exchange(&code);
+                }
+            }
+            KeyCode::Char('s') => {
+                let Some(code) = code else {
+                    return;
+                };
+                let Some(character) = self.selected_character() else {
+                    return error!(target: "command", "No character selected");
+                };
+                let price = self
+                    .grand_exchange
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.suggested_price());
+                let quantity = character.inventory_quantity(&code).max(1);
+
+                self.dialog = Some(ConfirmDialog::sell(&code, &character.name, quantity, price));
+            }
+            _ => {}
+        }
+    }
+
+    fn on_stats_key_event(&mut self, key: KeyEvent) {
+        let Ok(mut state) = self.character_widget.lock() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char('s') | KeyCode::Esc => self.view = View::Logs,
+            KeyCode::Up => state.select_offset(-1),
+            KeyCode::Down | KeyCode::Char('c') => state.select_offset(1),
+            _ => {}
+        }
+    }
+
+    fn on_achievements_key_event(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('a') | KeyCode::Esc => self.view = View::Logs,
+            KeyCode::Char('r') => self.refresh_achievements(),
+            KeyCode::Char('c') => self.select_next_character(),
+            KeyCode::Char('p') | KeyCode::Enter => {
+                let character = self.selected_character();
+                let strategy = self.achievements.lock().ok().and_then(|state| {
+                    let achievement = state.selected_achievement()?;
+                    state.suggestion(character.as_ref()?, achievement)
+                });
+                match strategy {
+                    Some(strategy) => {
+                        self.spawn_command(PaletteCommand::SetStrategy(strategy), character)
+                    }
+                    None => error!(target: "command", "No strategy advances this achievement"),
+                }
+            }
+            _ => {
+                let Ok(mut state) = self.achievements.lock() else {
+                    return;
+                };
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') => state.select_offset(-1),
+                    KeyCode::Down | KeyCode::Char('j') => state.select_offset(1),
+                    KeyCode::Char('t') => state.toggle_completed(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn on_dialog_key_event(&mut self, key: KeyEvent) {
+        let Some(dialog) = self.dialog.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('n') => self.dialog = None,
+            KeyCode::Enter | KeyCode::Char('y') => {
+                let action = dialog.action();
+                let character = self.selected_character();
+                match action {
+                    Ok(action) => {
+                        self.dialog = None;
+                        self.spawn_command(PaletteCommand::Action(action), character);
+                    }
+                    // Keep the dialog open so the value can be fixed.
+                    Err(e) => error!(target: "command", "{}", e),
+                }
+            }
+            KeyCode::Tab => dialog.next_field(),
+            KeyCode::Backspace => dialog.pop(),
+            KeyCode::Char(c) => dialog.push(c),
+            _ => {}
+        }
+    }
+
+    fn on_palette_key_event(&mut self, key: KeyEvent) {
+        let character = self.selected_character();
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => self.palette = None,
+            KeyCode::Enter => {
+                let input = palette.input.clone();
+                self.palette = None;
+                self.run_command(&input, character);
+            }
+            KeyCode::Tab => palette.complete(character.as_ref()),
+            KeyCode::Backspace => palette.pop(),
+            KeyCode::Char(c) => palette.push(c),
+            _ => {}
+        }
+    }
+
+    /// Runs a palette command for `character` in the background. The result
+    /// is written to the log.
+    fn run_command(&self, input: &str, character: Option<Character>) {
+        if input.trim().is_empty() {
+            return;
+        }
+
+        match input.parse::<PaletteCommand>() {
+            Ok(command) => self.spawn_command(command, character),
+            Err(e) => error!(target: "command", "{}", e),
+        }
+    }
+
+    fn spawn_command(&self, command: PaletteCommand, character: Option<Character>) {
+        let Some(character) = character else {
+            return error!(target: "command", "No character selected");
+        };
+
+        tokio::spawn(command.run(
+            self.client.clone(),
+            self.bots.clone(),
+            self.world.clone(),
+            character,
+        ));
+    }
+
+    fn selected_character(&self) -> Option<Character> {
+        self.character_widget
+            .lock()
+            .ok()
+            .and_then(|state| state.selected_character().cloned())
+    }
+
+    fn select_next_character(&self) {
+        if let Ok(mut state) = self.character_widget.lock() {
+            state.select_offset(1);
+        }
+    }
+
+    fn selected_bank_item(&self) -> Option<SimpleItem> {
+        self.bank_widget
+            .lock()
+            .ok()
+            .and_then(|state| state.selected_item().cloned())
+    }
+
+    /// Shows the bank, loading it the first time.
+    fn open_bank(&mut self) {
+        self.view = View::Bank;
+
+        let loaded = self
+            .bank_widget
+            .lock()
+            .is_ok_and(|state| state.loading || !state.items.is_empty());
+        if !loaded {
+            self.refresh_bank();
+        }
+    }
+
+    /// Reloads the bank contents in the background.
+    fn refresh_bank(&self) {
+        if let Ok(mut state) = self.bank_widget.lock() {
+            state.loading = true;
+        }
+
+        let client = self.client.clone();
+        let bank_widget = self.bank_widget.clone();
+        tokio::spawn(async move {
+            let result = BankWidgetState::load(&client).await;
+            let Ok(mut state) = bank_widget.lock() else {
+                return;
+            };
+            match result {
+                Ok(loaded) => state.refresh(loaded),
+                Err(e) => {
+                    state.loading = false;
+                    error!("Failed to load the bank: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Shows the achievements, loading them the first time.
+    fn open_achievements(&mut self) {
+        self.view = View::Achievements;
+
+        let loaded = self
+            .achievements
+            .lock()
+            .is_ok_and(|state| state.loading || state.account.is_some());
+        if !loaded {
+            self.refresh_achievements();
+        }
+    }
+
+    /// Reloads the achievement progress in the background.
+    fn refresh_achievements(&self) {
+        if let Ok(mut state) = self.achievements.lock() {
+            state.loading = true;
+        }
+
+        let client = self.client.clone();
+        let achievements = self.achievements.clone();
+        tokio::spawn(async move {
+            let result = AchievementsState::load(&client).await;
+            let Ok(mut state) = achievements.lock() else {
+                return;
+            };
+            match result {
+                Ok(loaded) => state.refresh(loaded),
+                Err(e) => {
+                    state.loading = false;
+                    error!("Failed to load achievements: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Loads the Grand Exchange orders and history of `code` in the
+    /// background.
+    fn load_grand_exchange(&self, code: &ItemCode) {
+        if let Ok(mut state) = self.grand_exchange.lock() {
+            *state = GrandExchangeState {
+                code: Some(code.clone()),
+                loading: true,
+                ..GrandExchangeState::default()
+            };
+        }
+
+        let client = self.client.clone();
+        let grand_exchange = self.grand_exchange.clone();
+        let code = code.clone();
+        tokio::spawn(async move {
+            let result = GrandExchangeState::load(&client, &code).await;
+            let Ok(mut state) = grand_exchange.lock() else {
+                return;
+            };
+            // Ignore the result if another item was selected meanwhile.
+            if state.code.as_deref() != Some(code.as_str()) {
+                return;
+            }
+            match result {
+                Ok(loaded) => *state = loaded,
+                Err(e) => {
+                    state.loading = false;
+                    error!("Failed to load Grand Exchange orders for {}: {}", code, e);
+                }
+            }
+        });
+    }
+
+    /// Set running to false to quit the application.
+    fn quit(&mut self) {
+        self.running = false;
+    }
+}
+
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [app_area, log_area] = if area.height >= 40 {
+            Layout::vertical([Constraint::Percentage(20), Constraint::Fill(1)]).areas(area)
+        } else {
+            Layout::horizontal([Constraint::Max(40), Constraint::Fill(1)]).areas(area)
+        };
+
+        if let Ok(mut state) = self.character_widget.lock() {
+            state.direction = if app_area.height >= 40 {
+                Direction::Vertical
+            } else {
+                Direction::Horizontal
+            };
+
+            let area = app_area.inner(Margin::new(1, 1));
+            CharacterWidget::new().render(area, buf, &mut state);
+        }
+
+        let [log_area, help_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(log_area);
+
+        let (help, min_width): (Vec<Line>, u16) = match self.view {
+            View::Map => {
+                let characters = self
+                    .character_widget
+                    .lock()
+                    .map(|state| state.characters.clone())
+                    .unwrap_or_default();
+
+                if let Ok(mut state) = self.map_widget.lock() {
+                    MapWidget::new(&characters).render(log_area, buf, &mut state);
+                }
+
+                (
+                    vec![
+                        "Q: Quit | m/Esc: Back to logs | ←/→/↑/↓ or h/j/k/l: Pan (Shift: fast)"
+                            .into(),
+                        "+/-: Zoom | i/Enter: Tile info | c: Next character | :: Command".into(),
+                    ],
+                    100,
+                )
+            }
+            View::Character => {
+                if let Ok(state) = self.character_widget.lock() {
+                    CharacterDetailWidget::new(state.selected_character(), self.character_tab)
+                        .render(log_area, buf);
+                }
+
+                (
+                    vec![
+                        "Q: Quit | d/Esc: Back to logs | ←/→ or Tab: Switch tab".into(),
+                        "↑/↓ or 1-9: Select character | :: Command".into(),
+                    ],
+                    100,
+                )
+            }
+            View::Bank => {
+                if let Ok(mut state) = self.bank_widget.lock() {
+                    BankWidget::new().render(log_area, buf, &mut state);
+                }
+
+                (
+                    vec![
+                        "Q: Quit | b/Esc: Back to logs | ↑/↓: Select item | s: Sort | t: Filter type"
+                            .into(),
+                        "w: Withdraw to selected character | g/Enter: Grand Exchange | r: Refresh | c: Next character"
+                            .into(),
+                    ],
+                    120,
+                )
+            }
+            View::GrandExchange => {
+                if let Ok(mut state) = self.grand_exchange.lock() {
+                    GrandExchangeWidget::new().render(log_area, buf, &mut state);
+                }
+
+                (
+                    vec![
+                        "Q: Quit | g/Esc: Back to bank | s: Sell from selected character".into(),
+                        "r: Refresh | c: Next character | :: Command".into(),
+                    ],
+                    100,
+                )
+            }
+            View::Stats => {
+                let character = self.selected_character();
+                if let Ok(stats) = self.stats.lock() {
+                    StatsWidget::new(&stats, character.as_ref().map(|c| c.name.as_str()))
+                        .render(log_area, buf);
+                }
+
+                (
+                    vec![
+                        "Q: Quit | s/Esc: Back to logs | ↑/↓ or c: Select character".into(),
+                        ":: Command".into(),
+                    ],
+                    100,
+                )
+            }
+            View::Achievements => {
+                let character = self.selected_character();
+                if let Ok(mut state) = self.achievements.lock() {
+                    AchievementsWidget::new(character.as_ref()).render(log_area, buf, &mut state);
+                }
+
+                (
+                    vec![
+                        "Q: Quit | a/Esc: Back to logs | ↑/↓: Select achievement | t: Show completed"
+                            .into(),
+                        "p/Enter: Switch selected character to suggestion | r: Refresh | c: Next character"
+                            .into(),
+                    ],
+                    110,
+                )
+            }
+            View::Logs => {
+                TuiLoggerSmartWidget::default()
+                    .style_error(Style::default().fg(Color::Red))
+                    .style_debug(Style::default().fg(Color::Green))
+                    .style_warn(Style::default().fg(Color::Yellow))
+                    .style_trace(Style::default().fg(Color::Magenta))
+                    .style_info(Style::default().fg(Color::Cyan))
+                    .output_level(Some(TuiLoggerLevelOutput::Long))
+                    .output_file(false)
+                    .output_line(false)
+                    .state(&self.tui_widget_state)
+                    .render(log_area, buf);
+
+                (
+                    vec![
+                        "Q: Quit | m: Map | d: Character | b: Bank | s: Stats | a: Achievements | :: Command | Tab: Switch state".into(),
+                        "↑/↓: Select target | f: Focus target | ←/→: Display level | +/-: Filter level"
+                            .into(),
+                        "Space: Toggle hidden targets | h: Hide target selector | Shift + ↑/↓: Scroll | Esc: Cancel scroll"
+                            .into(),
+                    ],
+                    140,
+                )
+            }
+        };
+
+        if let Some(dialog) = &self.dialog {
+            dialog.render(log_area, buf);
+        }
+
+        if let Some(palette) = &self.palette {
+            let character = self.selected_character();
+            let target = character
+                .as_ref()
+                .map_or("no character", |c| c.name.as_str());
+            let candidates = palette.candidates(character.as_ref()).join(" ");
+
+            Clear.render(help_area, buf);
+            Paragraph::new(vec![
+                Line::from(format!(":{}█", palette.input)),
+                Line::from(candidates).style(Color::Gray),
+            ])
+            .block(Block::default().borders(Borders::TOP).title(format!(
+                " Command for {} (Tab: Complete | Esc: Cancel) ",
+                target
+            )))
+            .render(help_area, buf);
+        } else if area.width >= min_width {
+            Text::from(help)
+                .style(Color::Gray)
+                .centered()
+                .render(help_area, buf);
+        }
+    }
+}
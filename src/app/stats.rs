@@ -0,0 +1,170 @@
+use chrono::{Duration, Utc};
+use ratatui::{
+    prelude::*,
+    symbols,
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Sparkline, Table,
+    },
+};
+
+use crate::stats::{CharacterStats, SessionStats};
+
+/// The number of one-minute buckets shown in the sparklines.
+const SPARKLINE_MINUTES: usize = 60;
+
+/// Shows the session statistics of a single character.
+#[derive(Debug)]
+pub struct StatsWidget<'a> {
+    stats: &'a SessionStats,
+    character: Option<&'a str>,
+}
+
+impl<'a> StatsWidget<'a> {
+    pub fn new(stats: &'a SessionStats, character: Option<&'a str>) -> Self {
+        Self { stats, character }
+    }
+
+    fn render_summary(stats: &CharacterStats, area: Rect, buf: &mut Buffer) {
+        let now = Utc::now();
+        let (cooldown, idle) = stats.cooldown_vs_idle(now);
+        let busy = match cooldown + idle {
+            0 => 0.0,
+            total => 100.0 * cooldown as f64 / total as f64,
+        };
+        let percent =
+            |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.0}%", 100.0 * v));
+
+        Paragraph::new(vec![
+            Line::from(format!(
+                "XP/hour: {:.0} | Gold/hour: {:.0} | Total: {} XP, {} gold",
+                stats.xp_per_hour(now),
+                stats.gold_per_hour(now),
+                stats.xp,
+                stats.gold
+            )),
+            Line::from(format!(
+                "Fights: {} | Win rate: {} | Avg. turns: {}",
+                stats.fights(),
+                percent(stats.win_rate()),
+                stats
+                    .average_turns()
+                    .map_or("-".to_string(), |t| format!("{:.1}", t)),
+            )),
+            Line::from(format!(
+                "Rests: {} ({} HP restored) | Cooldown: {}s, idle: {}s ({:.0}% busy)",
+                stats.rests, stats.hp_restored, cooldown, idle, busy
+            )),
+        ])
+        .render(area, buf);
+    }
+
+    fn render_sparklines(stats: &CharacterStats, area: Rect, buf: &mut Buffer) {
+        let now = Utc::now();
+        let [xp_area, gold_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+
+        let xp = stats.buckets(now, Duration::minutes(1), SPARKLINE_MINUTES, |s| s.xp);
+        Sparkline::default()
+            .block(Block::default().title("XP per minute (last hour)"))
+            .data(&xp)
+            .style(Style::default().fg(Color::Blue))
+            .render(xp_area, buf);
+
+        let gold = stats.buckets(now, Duration::minutes(1), SPARKLINE_MINUTES, |s| s.gold);
+        Sparkline::default()
+            .block(Block::default().title("Gold per minute (last hour)"))
+            .data(&gold)
+            .style(Style::default().fg(Color::Yellow))
+            .render(gold_area, buf);
+    }
+
+    fn render_chart(stats: &CharacterStats, area: Rect, buf: &mut Buffer) {
+        let xp = stats.cumulative(|s| s.xp);
+        let gold = stats.cumulative(|s| s.gold);
+        let max_x = xp.last().map_or(1.0, |(x, _)| x.max(1.0));
+        let max_y = (stats.xp.max(stats.gold) as f64).max(1.0);
+
+        let datasets = vec![
+            Dataset::default()
+                .name("XP")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Blue))
+                .data(&xp),
+            Dataset::default()
+                .name("Gold")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&gold),
+        ];
+
+        Chart::new(datasets)
+            .block(Block::default().title("Cumulative XP and gold"))
+            .x_axis(
+                Axis::default()
+                    .title("min")
+                    .bounds([0.0, max_x])
+                    .labels(["0".to_string(), format!("{:.0}", max_x)]),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, max_y])
+                    .labels(["0".to_string(), format!("{:.0}", max_y)]),
+            )
+            .render(area, buf);
+    }
+
+    fn render_drops(stats: &CharacterStats, area: Rect, buf: &mut Buffer) {
+        let mut drops = stats.drops.iter().collect::<Vec<_>>();
+        drops.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        let rows = drops.into_iter().map(|(code, quantity)| {
+            Row::new(vec![
+                Cell::from(code.to_string()),
+                Cell::from(quantity.to_string()),
+            ])
+        });
+
+        Widget::render(
+            Table::new(rows, [Constraint::Fill(1), Constraint::Length(8)])
+                .header(Row::new(vec!["Drop", "Quantity"]).bold())
+                .block(Block::default().borders(Borders::LEFT)),
+            area,
+            buf,
+        );
+    }
+}
+
+impl Widget for &StatsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.character {
+            Some(name) => format!(" Session stats: {} ", name),
+            None => " Session stats ".to_string(),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let Some(stats) = self.character.and_then(|name| self.stats.character(name)) else {
+            Paragraph::new("No bot actions recorded yet")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::Gray))
+                .render(inner, buf);
+            return;
+        };
+
+        let [summary_area, charts_area] =
+            Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).areas(inner);
+        StatsWidget::render_summary(stats, summary_area, buf);
+
+        let [charts_area, drops_area] =
+            Layout::horizontal([Constraint::Fill(3), Constraint::Fill(1)]).areas(charts_area);
+        let [sparkline_area, chart_area] =
+            Layout::vertical([Constraint::Length(6), Constraint::Fill(1)]).areas(charts_area);
+
+        StatsWidget::render_sparklines(stats, sparkline_area, buf);
+        StatsWidget::render_chart(stats, chart_area, buf);
+        StatsWidget::render_drops(stats, drops_area, buf);
+    }
+}
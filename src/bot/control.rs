@@ -0,0 +1,380 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::{
+    bot::{
+        BotContext,
+        strategy::{self, StepAction, StepOutcome, Strategy},
+    },
+    models::{
+        character::Character,
+        equipment::EquipmentSlot,
+        fight::FightResult,
+        item::SimpleItem,
+        types::{ItemCode, Position},
+    },
+};
+
+/// The number of events kept for clients that connect late.
+const RECENT_EVENTS: usize = 100;
+
+/// Whether a character's bot is acting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotStatus {
+    Running,
+    Paused,
+    Stopped,
+}
+
+/// A single action to perform once, ahead of the character's strategy.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OneOffAction {
+    /// Move to the given coordinates.
+    #[command(allow_negative_numbers = true)]
+    Move { x: i32, y: i32 },
+    /// Rest to recover HP.
+    Rest,
+    /// Fight the monster on the current map.
+    Fight,
+    /// Gather the resource on the current map.
+    Gather,
+    /// Craft an item at the workshop on the current map.
+    Craft {
+        code: ItemCode,
+        #[arg(default_value_t = 1)]
+        quantity: u32,
+    },
+    /// Deposit the whole inventory into the bank on the current map.
+    DepositAll,
+    /// Withdraw an item from the closest bank, moving there first.
+    Withdraw {
+        code: ItemCode,
+        #[arg(default_value_t = 1)]
+        quantity: u32,
+    },
+    /// List an item on the closest Grand Exchange, moving there first.
+    Sell {
+        code: ItemCode,
+        quantity: u32,
+        /// The price of a single item.
+        price: u32,
+    },
+    /// Equip an item from the inventory.
+    Equip { code: ItemCode, slot: EquipmentSlot },
+    /// Use a consumable from the inventory.
+    UseItem {
+        code: ItemCode,
+        #[arg(default_value_t = 1)]
+        quantity: u32,
+    },
+}
+
+impl OneOffAction {
+    pub async fn perform(
+        &self,
+        ctx: &BotContext,
+        character: &Character,
+    ) -> anyhow::Result<StepOutcome> {
+        let name = &character.name;
+
+        match self {
+            OneOffAction::Move { x, y } => {
+                strategy::move_to(ctx, name, Position::new(*x, *y)).await
+            }
+            OneOffAction::Rest => strategy::rest(ctx, name).await,
+            OneOffAction::Fight => strategy::fight(ctx, name, None).await,
+            OneOffAction::Gather => strategy::gather(ctx, name, None).await,
+            OneOffAction::Craft { code, quantity } => {
+                strategy::craft(ctx, name, code, *quantity).await
+            }
+            OneOffAction::DepositAll => strategy::deposit_all(ctx, character).await,
+            OneOffAction::Withdraw { code, quantity } => {
+                strategy::withdraw(ctx, character, code, *quantity).await
+            }
+            OneOffAction::Sell {
+                code,
+                quantity,
+                price,
+            } => strategy::sell(ctx, character, code, *quantity, *price).await,
+            OneOffAction::Equip { code, slot } => strategy::equip(ctx, name, code, *slot).await,
+            OneOffAction::UseItem { code, quantity } => {
+                strategy::use_item(ctx, name, code, *quantity).await
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for OneOffAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OneOffAction::Move { x, y } => write!(f, "move to ({}, {})", x, y),
+            OneOffAction::Rest => write!(f, "rest"),
+            OneOffAction::Fight => write!(f, "fight"),
+            OneOffAction::Gather => write!(f, "gather"),
+            OneOffAction::Craft { code, quantity } => write!(f, "craft {} x{}", code, quantity),
+            OneOffAction::DepositAll => write!(f, "deposit all"),
+            OneOffAction::Withdraw { code, quantity } => {
+                write!(f, "withdraw {} x{}", code, quantity)
+            }
+            OneOffAction::Sell {
+                code,
+                quantity,
+                price,
+            } => write!(f, "sell {} x{} at {}", code, quantity, price),
+            OneOffAction::Equip { code, slot } => write!(f, "equip {} in {}", code, slot),
+            OneOffAction::UseItem { code, quantity } => write!(f, "use {} x{}", code, quantity),
+        }
+    }
+}
+
+/// A command sent to a running character bot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotCommand {
+    /// Stops running the strategy until resumed. Queued actions are still
+    /// performed.
+    Pause,
+    Resume,
+    Stop,
+    SetStrategy(Strategy),
+    /// Performs a one-off action before the next step of the strategy.
+    Queue(OneOffAction),
+}
+
+/// The current state of a character's bot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterStatus {
+    pub name: String,
+    pub strategy: Strategy,
+    pub status: BotStatus,
+    /// One-off actions waiting to be performed.
+    pub queued: Vec<OneOffAction>,
+}
+
+/// Something that happened to a character's bot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotEventKind {
+    Started {
+        strategy: Strategy,
+    },
+    Paused,
+    Resumed,
+    Stopped,
+    StrategyChanged {
+        strategy: Strategy,
+    },
+    Queued {
+        action: OneOffAction,
+    },
+    /// The character left its strategy for an active world event.
+    EventJoined {
+        event: String,
+    },
+    /// The event ended and the character went back to its strategy.
+    EventLeft {
+        event: String,
+    },
+    Action {
+        action: StepAction,
+        xp: u32,
+        cooldown_seconds: u32,
+        fight_result: Option<FightResult>,
+        #[serde(default)]
+        gold: u32,
+        #[serde(default)]
+        drops: Vec<SimpleItem>,
+        #[serde(default)]
+        turns: u32,
+        #[serde(default)]
+        hp_restored: u32,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// An event from a character's bot, as streamed to control clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotEvent {
+    pub timestamp: DateTime<Utc>,
+    pub character: String,
+    #[serde(flatten)]
+    pub kind: BotEventKind,
+}
+
+impl std::fmt::Display for BotEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.character)?;
+
+        match &self.kind {
+            BotEventKind::Started { strategy } => write!(f, "started {}", strategy),
+            BotEventKind::Paused => write!(f, "paused"),
+            BotEventKind::Resumed => write!(f, "resumed"),
+            BotEventKind::Stopped => write!(f, "stopped"),
+            BotEventKind::StrategyChanged { strategy } => write!(f, "switched to {}", strategy),
+            BotEventKind::Queued { action } => write!(f, "queued {}", action),
+            BotEventKind::EventJoined { event } => write!(f, "joined event {}", event),
+            BotEventKind::EventLeft { event } => write!(f, "left event {}", event),
+            BotEventKind::Action {
+                action,
+                xp,
+                cooldown_seconds,
+                fight_result,
+                ..
+            } => {
+                write!(
+                    f,
+                    "{:?} (+{} XP, {}s cooldown)",
+                    action, xp, cooldown_seconds
+                )?;
+                match fight_result {
+                    Some(result) => write!(f, " {:?}", result),
+                    None => Ok(()),
+                }
+            }
+            BotEventKind::Error { message } => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// Errors that can occur while controlling a bot.
+#[derive(Debug, Error)]
+pub enum ControlError {
+    #[error("No bot is running for character {0}")]
+    UnknownCharacter(String),
+    #[error("The bot for character {0} has stopped")]
+    Stopped(String),
+}
+
+#[derive(Debug)]
+struct ControlledBot {
+    status: CharacterStatus,
+    commands: mpsc::UnboundedSender<BotCommand>,
+}
+
+/// Shared handle used to inspect and steer the running character bots.
+#[derive(Debug, Clone)]
+pub struct BotController {
+    bots: Arc<Mutex<BTreeMap<String, ControlledBot>>>,
+    recent: Arc<Mutex<VecDeque<BotEvent>>>,
+    events: broadcast::Sender<BotEvent>,
+}
+
+impl Default for BotController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BotController {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(RECENT_EVENTS);
+
+        Self {
+            bots: Arc::new(Mutex::new(BTreeMap::new())),
+            recent: Arc::new(Mutex::new(VecDeque::with_capacity(RECENT_EVENTS))),
+            events,
+        }
+    }
+
+    /// Registers a character's bot, returning the receiver for its commands.
+    pub fn register(&self, name: &str, strategy: Strategy) -> mpsc::UnboundedReceiver<BotCommand> {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let status = CharacterStatus {
+            name: name.to_string(),
+            strategy,
+            status: BotStatus::Running,
+            queued: Vec::new(),
+        };
+
+        if let Ok(mut bots) = self.bots.lock() {
+            bots.insert(name.to_string(), ControlledBot { status, commands });
+        }
+
+        receiver
+    }
+
+    /// The state of every registered bot, ordered by character name.
+    pub fn characters(&self) -> Vec<CharacterStatus> {
+        self.bots
+            .lock()
+            .map(|bots| bots.values().map(|bot| bot.status.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn character(&self, name: &str) -> Option<CharacterStatus> {
+        self.bots
+            .lock()
+            .ok()
+            .and_then(|bots| bots.get(name).map(|bot| bot.status.clone()))
+    }
+
+    /// Sends a command to a character's bot.
+    pub fn send(&self, name: &str, command: BotCommand) -> Result<(), ControlError> {
+        let bots = self
+            .bots
+            .lock()
+            .map_err(|_| ControlError::UnknownCharacter(name.to_string()))?;
+        let bot = bots
+            .get(name)
+            .ok_or_else(|| ControlError::UnknownCharacter(name.to_string()))?;
+
+        if bot.status.status == BotStatus::Stopped {
+            return Err(ControlError::Stopped(name.to_string()));
+        }
+
+        bot.commands
+            .send(command)
+            .map_err(|_| ControlError::Stopped(name.to_string()))
+    }
+
+    /// Updates the published state of a character's bot.
+    pub fn update(&self, name: &str, f: impl FnOnce(&mut CharacterStatus)) {
+        if let Ok(mut bots) = self.bots.lock()
+            && let Some(bot) = bots.get_mut(name)
+        {
+            f(&mut bot.status);
+        }
+    }
+
+    /// Publishes an event to every subscriber.
+    pub fn emit(&self, character: &str, kind: BotEventKind) {
+        let event = BotEvent {
+            timestamp: Utc::now(),
+            character: character.to_string(),
+            kind,
+        };
+
+        if let Ok(mut recent) = self.recent.lock() {
+            if recent.len() == RECENT_EVENTS {
+                recent.pop_front();
+            }
+            recent.push_back(event.clone());
+        }
+
+        // Sending only fails when nobody is subscribed.
+        _ = self.events.send(event);
+    }
+
+    /// The most recent events, oldest first.
+    pub fn recent_events(&self) -> Vec<BotEvent> {
+        self.recent
+            .lock()
+            .map(|recent| recent.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BotEvent> {
+        self.events.subscribe()
+    }
+}
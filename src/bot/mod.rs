@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tracing::warn;
+
+use crate::{
+    api::{
+        ArtifactsError, MAX_PAGE_SIZE, client::ArtifactsClient, fetch_all_pages, maps::MapQuery,
+    },
+    bot::script::ScriptHost,
+    bt::TreeCache,
+    events::EventWatcher,
+    goals::GoalRunner,
+    history::{ActionRecord, HistoryStore},
+    models::{
+        map::MapContentType,
+        skill::Skill,
+        types::{Position, ResourceCode},
+    },
+};
+
+/// Handles for inspecting and steering running bots.
+pub mod control;
+/// Runs and persists the bots for every configured character.
+pub mod orchestrator;
+/// Strategies scripted in Rhai.
+pub mod script;
+/// The local HTTP control server and its client.
+pub mod server;
+/// Strategies that drive a character one action at a time.
+pub mod strategy;
+
+/// The default fraction of max HP below which a fighting character rests.
+pub const DEFAULT_REST_THRESHOLD: f64 = 0.5;
+
+/// Every map with a given content type and, if any, content code.
+type Locations = HashMap<(MapContentType, Option<String>), Vec<Position>>;
+
+/// Shared state used by strategies while running.
+#[derive(Debug)]
+pub struct BotContext {
+    pub api: ArtifactsClient,
+    /// The fraction of max HP below which a character rests before fighting.
+    pub rest_threshold: f64,
+    /// Where every action performed by a strategy is recorded, if enabled.
+    pub history: Option<Arc<HistoryStore>>,
+    /// Runs the scripts of [`strategy::Strategy::Script`] strategies.
+    pub scripts: ScriptHost,
+    /// Loads the trees of [`strategy::Strategy::Tree`] strategies.
+    pub trees: TreeCache,
+    /// Plans the phases of [`strategy::Strategy::Goal`] strategies.
+    pub goals: GoalRunner,
+    /// The active world events, if the character follows any.
+    pub events: Option<EventWatcher>,
+    locations: Mutex<Locations>,
+    resource_skills: Mutex<HashMap<ResourceCode, Skill>>,
+}
+
+impl BotContext {
+    pub fn new(api: ArtifactsClient) -> Self {
+        Self {
+            scripts: ScriptHost::new(api.clone()),
+            trees: TreeCache::new(),
+            goals: GoalRunner::default(),
+            api,
+            rest_threshold: DEFAULT_REST_THRESHOLD,
+            history: None,
+            events: None,
+            locations: Mutex::new(HashMap::new()),
+            resource_skills: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn rest_threshold(mut self, rest_threshold: f64) -> Self {
+        self.rest_threshold = rest_threshold;
+        self
+    }
+
+    pub fn replan_margin(mut self, replan_margin: f64) -> Self {
+        self.goals = GoalRunner::new(replan_margin);
+        self
+    }
+
+    pub fn history(mut self, history: Arc<HistoryStore>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    pub fn events(mut self, events: EventWatcher) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Records the result of an action in the history store, if one is
+    /// configured. `with` fills in the record from a successful response.
+    ///
+    /// The record is written on a blocking thread, so SQLite never stalls the
+    /// async workers.
+    pub fn record<T, E: std::fmt::Display>(
+        &self,
+        record: ActionRecord,
+        result: &Result<T, ArtifactsError<E>>,
+        with: impl FnOnce(ActionRecord, &T) -> ActionRecord,
+    ) {
+        let Some(history) = &self.history else {
+            return;
+        };
+
+        let record = match result {
+            Ok(data) => with(record, data),
+            Err(e) => record.with_error(e),
+        };
+
+        let history = history.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = history.record(&record) {
+                warn!(
+                    "Failed to record {} action for {}: {}",
+                    record.action, record.character, e
+                );
+            }
+        });
+    }
+
+    /// Finds the map with the given content closest to `from`, caching every
+    /// map with that content. Without a `code`, any map of `content_type`
+    /// matches, e.g. any bank.
+    pub async fn locate(
+        &self,
+        content_type: MapContentType,
+        code: Option<&str>,
+        from: Position,
+    ) -> anyhow::Result<Position> {
+        let key = (content_type, code.map(str::to_string));
+
+        let cached = self
+            .locations
+            .lock()
+            .ok()
+            .and_then(|l| l.get(&key).cloned());
+
+        let locations = match cached {
+            Some(locations) => locations,
+            None => {
+                let maps = fetch_all_pages(|page| {
+                    let mut query = MapQuery::default()
+                        .content_type(content_type)
+                        .page_number(page)
+                        .page_size(MAX_PAGE_SIZE);
+                    if let Some(code) = code {
+                        query = query.content_code(code.to_string());
+                    }
+                    async move { self.api.get_maps(&query).await }
+                })
+                .await?;
+                let locations = maps.iter().map(|map| map.position).collect::<Vec<_>>();
+
+                if let Ok(mut cache) = self.locations.lock() {
+                    cache.insert(key, locations.clone());
+                }
+                locations
+            }
+        };
+
+        from.closest(locations).ok_or_else(|| match code {
+            Some(code) => anyhow::anyhow!("No map found for {:?} '{}'", content_type, code),
+            None => anyhow::anyhow!("No map found for {:?}", content_type),
+        })
+    }
+
+    /// Returns the skill used to gather the given resource, caching the
+    /// result.
+    pub async fn resource_skill(&self, code: &ResourceCode) -> Option<Skill> {
+        if let Some(skill) = self
+            .resource_skills
+            .lock()
+            .ok()
+            .and_then(|s| s.get(code).copied())
+        {
+            return Some(skill);
+        }
+
+        let skill = self.api.get_resource(code).await.ok()?.skill;
+
+        if let Ok(mut skills) = self.resource_skills.lock() {
+            skills.insert(code.clone(), skill);
+        }
+
+        Some(skill)
+    }
+}
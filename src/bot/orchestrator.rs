@@ -0,0 +1,610 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    api::client::ArtifactsClient,
+    bot::{
+        BotContext,
+        control::{BotCommand, BotController, BotEventKind, BotStatus, OneOffAction},
+        script::ScriptError,
+        strategy::{self, ActionError, StepOutcome, Strategy, Target},
+    },
+    bt::BtError,
+    config::{BotConfig, CharacterConfig},
+    cooldown,
+    events::{DEFAULT_POLL_INTERVAL, EventWatcher},
+    goals::GoalError,
+    history::HistoryStore,
+    models::character::Character,
+    reconcile::{RECONCILE_INTERVAL, Reconciler},
+    world::WorldState,
+};
+
+/// How long a bot waits before running a failing script or behavior tree
+/// again, and before its first retry of a failed action.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// The longest a bot waits between retries of an action that keeps failing.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// The persisted state of a single character's bot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterState {
+    /// The strategy from the config file when the state was saved. Used to
+    /// detect when the config has changed since the last run.
+    pub configured: Strategy,
+    /// The strategy the character is running.
+    pub strategy: Strategy,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The state of all bots, persisted so they can be resumed after a restart.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersistedState {
+    pub characters: BTreeMap<String, CharacterState>,
+}
+
+impl PersistedState {
+    /// The default location of the state file,
+    /// `$XDG_DATA_HOME/artifacts/state.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("artifacts").join("state.json"))
+    }
+
+    /// Loads the state from `path`, returning the default state if the file
+    /// does not exist.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the state to `path`, replacing the previous file atomically.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// The strategy to run for a configured character: the persisted one if
+    /// the config has not changed since it was saved, otherwise the one from
+    /// the config.
+    pub fn resume(&self, character: &CharacterConfig) -> Strategy {
+        match self.characters.get(character.name.as_str()) {
+            Some(state) if state.configured == character.strategy => state.strategy.clone(),
+            _ => character.strategy.clone(),
+        }
+    }
+}
+
+/// Runs the bots for every configured character until cancelled.
+///
+/// When the cancellation token is triggered, characters waiting for a
+/// cooldown stop immediately, while actions already sent to the API are
+/// allowed to finish.
+///
+/// Each bot can be steered while running through the
+/// [`BotController`] returned by [`Orchestrator::controller`].
+#[derive(Debug)]
+pub struct Orchestrator {
+    api: ArtifactsClient,
+    bot: BotConfig,
+    history: Option<Arc<HistoryStore>>,
+    state_path: Option<PathBuf>,
+    state: Arc<Mutex<PersistedState>>,
+    controller: BotController,
+    world: WorldState,
+    cancel: CancellationToken,
+}
+
+impl Orchestrator {
+    pub fn new(api: ArtifactsClient, bot: BotConfig, cancel: CancellationToken) -> Self {
+        let history = match &bot.history_path {
+            Some(path) => match HistoryStore::open(path) {
+                Ok(store) => Some(Arc::new(store)),
+                Err(e) => {
+                    error!("Failed to open history store {}: {}", path.display(), e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Self {
+            api,
+            bot,
+            history,
+            state_path: None,
+            state: Arc::new(Mutex::new(PersistedState::default())),
+            controller: BotController::new(),
+            world: WorldState::new(),
+            cancel,
+        }
+    }
+
+    /// Shares the characters' state with `world`, which every action
+    /// response is recorded into and which the bots read before acting.
+    pub fn world(mut self, world: WorldState) -> Self {
+        self.world = world;
+        self
+    }
+
+    /// Persists the bots' state at `path`, resuming from it if it exists.
+    pub fn state_path(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        match PersistedState::load(&path) {
+            Ok(state) => self.state = Arc::new(Mutex::new(state)),
+            Err(e) => warn!("Failed to load bot state from {}: {}", path.display(), e),
+        }
+
+        self.state_path = Some(path);
+        self
+    }
+
+    /// The handle used to inspect and steer the bots once they are running.
+    pub fn controller(&self) -> BotController {
+        self.controller.clone()
+    }
+
+    /// Runs every configured character's bot, returning once all of them have
+    /// stopped.
+    pub async fn run(self) {
+        // Only poll the events if a character can join them.
+        let events = self
+            .bot
+            .characters
+            .iter()
+            .any(|c| !c.events.is_empty())
+            .then(EventWatcher::new);
+        if let Some(events) = &events {
+            let (events, api, cancel) = (events.clone(), self.api.clone(), self.cancel.clone());
+            let (notices, world) = (events.subscribe(), self.world.clone());
+            tokio::spawn(async move { world.follow_events(notices).await });
+            tokio::spawn(async move { events.run(api, DEFAULT_POLL_INTERVAL, cancel).await });
+        }
+
+        // Import the actions taken while the bots were not recording, then
+        // keep importing those taken from elsewhere.
+        if let Some(history) = &self.history {
+            let reconciler = Reconciler::new(self.api.clone(), history.clone());
+            let characters = self.bot.characters.iter().map(|c| c.name.clone()).collect();
+            let cancel = self.cancel.clone();
+            tokio::spawn(
+                async move { reconciler.run(characters, RECONCILE_INTERVAL, cancel).await },
+            );
+        }
+
+        let runners = self.bot.characters.iter().map(|character| {
+            let strategy = self
+                .state
+                .lock()
+                .map(|state| state.resume(character))
+                .unwrap_or_else(|_| character.strategy.clone());
+
+            self.update_state(character, &strategy);
+            let commands = self.controller.register(&character.name, strategy.clone());
+
+            let mut ctx = BotContext::new(self.api.clone())
+                .rest_threshold(character.rest_threshold(&self.bot))
+                .replan_margin(self.bot.replan_margin);
+            ctx.history = self.history.clone();
+            if !character.events.is_empty() {
+                ctx.events = events.clone();
+            }
+
+            self.run_character(ctx, character, strategy, commands)
+        });
+
+        futures::future::join_all(runners).await;
+        self.save_state();
+    }
+
+    async fn run_character(
+        &self,
+        ctx: BotContext,
+        config: &CharacterConfig,
+        mut strategy: Strategy,
+        mut commands: mpsc::UnboundedReceiver<BotCommand>,
+    ) {
+        let name = config.name.as_str();
+        info!(target: "bot", "Starting {} for {}", strategy, name);
+        self.controller.emit(
+            name,
+            BotEventKind::Started {
+                strategy: strategy.clone(),
+            },
+        );
+
+        let mut character = tokio::select! {
+            result = ctx.api.get_character(&config.name) => match result {
+                Ok(character) => {
+                    self.world.update_character(character.clone());
+                    Some(character)
+                }
+                Err(e) => {
+                    error!(target: "bot", "Failed to fetch character {}: {}", name, e);
+                    self.controller.emit(name, BotEventKind::Error { message: e.to_string() });
+                    None
+                }
+            },
+            _ = self.cancel.cancelled() => None,
+        };
+
+        let mut runner = Runner {
+            status: BotStatus::Running,
+            queue: VecDeque::new(),
+            event: None,
+            failures: 0,
+        };
+
+        while let Some(last) = &character {
+            // The world may hold a newer state, e.g. after an action taken
+            // from elsewhere was picked up by the poller.
+            let current = &self
+                .world
+                .character(&config.name)
+                .unwrap_or_else(|| last.clone());
+
+            // A paused bot no longer runs its strategy, but still performs
+            // the actions queued on it, e.g. from the TUI.
+            if runner.status == BotStatus::Paused && runner.queue.is_empty() {
+                tokio::select! {
+                    Some(command) = commands.recv() => {
+                        if !self.apply(&mut runner, config, &mut strategy, command) {
+                            break;
+                        }
+                        continue;
+                    }
+                    _ = self.cancel.cancelled() => break,
+                }
+            }
+
+            tokio::select! {
+                _ = cooldown::sleep_until_expired(current.cooldown_expiration) => {}
+                Some(command) = commands.recv() => {
+                    if !self.apply(&mut runner, config, &mut strategy, command) {
+                        break;
+                    }
+                    continue;
+                }
+                _ = self.cancel.cancelled() => break,
+            }
+
+            // The action itself is not cancelled so it is never interrupted
+            // after being sent.
+            let queued = runner.queue.pop_front();
+            let result = match &queued {
+                Some(action) => {
+                    self.controller.update(name, |status| {
+                        status.queued = runner.queue.iter().cloned().collect()
+                    });
+                    info!(target: "bot", "{} is performing queued action: {}", name, action);
+                    action.perform(&ctx, current).await
+                }
+                None => {
+                    self.step(&ctx, &mut runner, config, &strategy, current)
+                        .await
+                }
+            };
+
+            match result {
+                Ok(outcome) => {
+                    self.world.record(&outcome);
+                    self.controller.emit(
+                        name,
+                        BotEventKind::Action {
+                            action: outcome.action,
+                            xp: outcome.xp,
+                            cooldown_seconds: outcome.cooldown_seconds,
+                            fight_result: outcome.fight_result,
+                            gold: outcome.gold,
+                            drops: outcome.drops.clone(),
+                            turns: outcome.turns,
+                            hp_restored: outcome.hp_restored,
+                        },
+                    );
+                    character = Some(outcome.character);
+                    runner.failures = 0;
+                }
+                // A goal strategy has nothing left to do once its goal is
+                // reached.
+                Err(e) if matches!(e.downcast_ref(), Some(GoalError::Reached(_))) => {
+                    info!(target: "goals", "{}: {}", name, e);
+                    break;
+                }
+                Err(e) => {
+                    self.controller.emit(
+                        name,
+                        BotEventKind::Error {
+                            message: e.to_string(),
+                        },
+                    );
+
+                    // One-off actions are often sent from the wrong map or
+                    // with a full inventory, so a failed one is dropped and
+                    // the strategy carries on.
+                    if let Some(action) = queued {
+                        warn!(
+                            target: "bot",
+                            "{} dropped queued action {}: {}",
+                            name,
+                            action,
+                            e
+                        );
+                        self.world.refresh();
+                        continue;
+                    }
+
+                    // Script and behavior tree errors happen before any action
+                    // is sent, so the bot waits for them to be fixed instead
+                    // of stopping.
+                    if is_definition_error(&e) {
+                        error!(
+                            target: "script",
+                            "{}: {}. Retrying in {}s.",
+                            name,
+                            e,
+                            RETRY_DELAY.as_secs()
+                        );
+                        tokio::select! {
+                            _ = tokio::time::sleep(RETRY_DELAY) => continue,
+                            _ = self.cancel.cancelled() => break,
+                        }
+                    }
+
+                    // The action may have changed the character before
+                    // failing, so its state is fetched again.
+                    self.world.refresh();
+
+                    // Anything but a missing character or a rejected token
+                    // may clear up, e.g. a server error or a full inventory
+                    // emptied from elsewhere, so the bot backs off and tries
+                    // again.
+                    let recoverable = e
+                        .downcast_ref::<ActionError>()
+                        .is_none_or(ActionError::is_recoverable);
+                    if recoverable {
+                        runner.failures += 1;
+                        let delay = retry_delay(runner.failures);
+                        error!(
+                            target: "bot",
+                            "{}: {}. Retrying in {}s.",
+                            name,
+                            e,
+                            delay.as_secs()
+                        );
+                        tokio::select! {
+                            _ = tokio::time::sleep(delay) => continue,
+                            _ = self.cancel.cancelled() => break,
+                        }
+                    }
+
+                    error!(target: "bot", "Bot for {} stopped: {}", name, e);
+                    break;
+                }
+            }
+
+            if self.cancel.is_cancelled() {
+                break;
+            }
+        }
+
+        self.controller
+            .update(name, |status| status.status = BotStatus::Stopped);
+        self.controller.emit(name, BotEventKind::Stopped);
+        info!(target: "bot", "Stopped {} for {}", strategy, name);
+    }
+
+    /// Performs the next action of the character's strategy, or of the
+    /// first active event it follows, announcing when it joins or leaves an
+    /// event.
+    async fn step(
+        &self,
+        ctx: &BotContext,
+        runner: &mut Runner,
+        config: &CharacterConfig,
+        strategy: &Strategy,
+        character: &Character,
+    ) -> anyhow::Result<StepOutcome> {
+        let name = config.name.as_str();
+        let event = ctx
+            .events
+            .as_ref()
+            .and_then(|events| events.find(&config.events))
+            .and_then(|event| event.content().and_then(Target::of).map(|t| (event, t)));
+
+        let code = event.as_ref().map(|(e, _)| e.code.clone());
+        if runner.event != code {
+            if let Some(left) = runner.event.take() {
+                info!(target: "bot", "{} is leaving event {} for {}", name, left, strategy);
+                self.controller
+                    .emit(name, BotEventKind::EventLeft { event: left });
+            }
+            if let Some(joined) = &code {
+                info!(target: "bot", "{} is joining event {}", name, joined);
+                self.controller.emit(
+                    name,
+                    BotEventKind::EventJoined {
+                        event: joined.clone(),
+                    },
+                );
+            }
+            runner.event = code;
+        }
+
+        match &event {
+            Some((event, target)) => {
+                cooldown::sleep_until_expired(character.cooldown_expiration).await;
+                strategy::pursue(ctx, character, target, event.location()).await
+            }
+            None => strategy.step(ctx, character).await,
+        }
+    }
+
+    /// Applies a control command to a running or paused character's bot,
+    /// returning `false` once the bot has been told to stop.
+    fn apply(
+        &self,
+        runner: &mut Runner,
+        config: &CharacterConfig,
+        strategy: &mut Strategy,
+        command: BotCommand,
+    ) -> bool {
+        let name = config.name.as_str();
+
+        let event = match command {
+            BotCommand::Pause => {
+                runner.status = BotStatus::Paused;
+                BotEventKind::Paused
+            }
+            BotCommand::Resume => {
+                runner.status = BotStatus::Running;
+                BotEventKind::Resumed
+            }
+            BotCommand::Stop => {
+                runner.status = BotStatus::Stopped;
+                self.controller
+                    .update(name, |status| status.status = BotStatus::Stopped);
+                return false;
+            }
+            BotCommand::SetStrategy(new) => {
+                info!(target: "bot", "{} switched from {} to {}", name, strategy, new);
+                *strategy = new;
+                self.update_state(config, strategy);
+                self.controller
+                    .update(name, |status| status.strategy = strategy.clone());
+                BotEventKind::StrategyChanged {
+                    strategy: strategy.clone(),
+                }
+            }
+            BotCommand::Queue(action) => {
+                runner.queue.push_back(action.clone());
+                BotEventKind::Queued { action }
+            }
+        };
+
+        self.controller.update(name, |status| {
+            status.status = runner.status;
+            status.queued = runner.queue.iter().cloned().collect();
+        });
+        self.controller.emit(name, event);
+        true
+    }
+
+    fn update_state(&self, character: &CharacterConfig, strategy: &Strategy) {
+        if let Ok(mut state) = self.state.lock() {
+            state.characters.insert(
+                character.name.to_string(),
+                CharacterState {
+                    configured: character.strategy.clone(),
+                    strategy: strategy.clone(),
+                    updated_at: Utc::now(),
+                },
+            );
+        }
+
+        self.save_state();
+    }
+
+    fn save_state(&self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+
+        let result = match self.state.lock() {
+            Ok(state) => state.save(path),
+            Err(_) => Err(anyhow::anyhow!("Bot state lock was poisoned")),
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to save bot state to {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// The control state of a single running bot.
+#[derive(Debug)]
+struct Runner {
+    status: BotStatus,
+    queue: VecDeque<OneOffAction>,
+    /// The code of the event the character is taking part in.
+    event: Option<String>,
+    /// The number of actions that failed in a row.
+    failures: u32,
+}
+
+/// How long to wait after the `failures`th failed action in a row, doubling
+/// from [`RETRY_DELAY`] up to [`MAX_RETRY_DELAY`].
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Returns true if `error` comes from a strategy script or behavior tree
+/// rather than from an action.
+fn is_definition_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ScriptError>().is_some() || error.downcast_ref::<BtError>().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character() -> CharacterConfig {
+        toml::from_str(
+            r#"
+            name = "hero"
+            strategy = { type = "fight", monster = "chicken" }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn stops_a_paused_bot() {
+        let orchestrator = Orchestrator::new(
+            ArtifactsClient::new("token"),
+            BotConfig::default(),
+            CancellationToken::new(),
+        );
+        let config = character();
+        let controller = orchestrator.controller();
+        let _commands = controller.register("hero", config.strategy.clone());
+
+        let mut strategy = config.strategy.clone();
+        let mut runner = Runner {
+            status: BotStatus::Running,
+            queue: VecDeque::new(),
+            event: None,
+            failures: 0,
+        };
+        let status = || controller.character("hero").unwrap().status;
+
+        assert!(orchestrator.apply(&mut runner, &config, &mut strategy, BotCommand::Pause));
+        assert_eq!(runner.status, BotStatus::Paused);
+        assert_eq!(status(), BotStatus::Paused);
+
+        assert!(!orchestrator.apply(&mut runner, &config, &mut strategy, BotCommand::Stop));
+        assert_eq!(runner.status, BotStatus::Stopped);
+        assert_eq!(status(), BotStatus::Stopped);
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{error, info};
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE, bank::BankItemQuery, client::ArtifactsClient, fetch_all_pages,
+        maps::MapQuery,
+    },
+    bot::control::OneOffAction,
+    models::{
+        character::Character,
+        equipment::EquipmentSlot,
+        map::MapContentType,
+        types::{CharacterName, NameError},
+    },
+};
+
+/// The function every strategy script must define. It is called with the
+/// character before each action and returns the action to perform.
+const STEP_FN: &str = "step";
+
+/// The most operations a single call of a script may run, so an endless
+/// loop fails instead of blocking a worker thread forever.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// The deepest function calls a script may nest.
+const MAX_CALL_LEVELS: usize = 64;
+
+/// The deepest expressions a script may nest, at the top level and inside
+/// functions.
+const MAX_EXPR_DEPTHS: (usize, usize) = (64, 32);
+
+/// Errors raised by strategy scripts.
+///
+/// These never perform an action, so the bot can safely retry once the
+/// script has been fixed.
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("Failed to read script {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to compile script {path}: {message}")]
+    Compile { path: PathBuf, message: String },
+    #[error("Script {path} failed: {message}")]
+    Runtime { path: PathBuf, message: String },
+}
+
+#[derive(Debug)]
+struct CompiledScript {
+    modified: SystemTime,
+    /// The last version that compiled, kept running while the file on disk
+    /// has errors.
+    ast: Option<AST>,
+    error: Option<String>,
+}
+
+impl CompiledScript {
+    fn ast(&self, path: &Path) -> Result<AST, ScriptError> {
+        match &self.ast {
+            Some(ast) => Ok(ast.clone()),
+            None => Err(ScriptError::Compile {
+                path: path.to_path_buf(),
+                message: self.error.clone().unwrap_or_default(),
+            }),
+        }
+    }
+}
+
+/// Runs strategy scripts written in [Rhai](https://rhai.rs), recompiling them
+/// whenever the file changes.
+///
+/// A script defines `fn step(character)`, which returns one of `fight()`,
+/// `gather()`, `rest()`, `move_to(x, y)`, `craft(code, quantity)`,
+/// `deposit_all()`, `withdraw(code, quantity)`, `equip(code, slot)` or
+/// `use_item(code, quantity)`. Scripts can query the world with
+/// `character(name)`, `find_map(content_type, code)`, `monster(code)`,
+/// `resource(code)`, `item(code)` and `bank_items()`, and write to the log
+/// with `log(message)` or `print(message)`.
+///
+/// Each call of `step` is limited in how many operations it runs and how
+/// deeply it nests, so a script stuck in a loop fails instead of hanging the
+/// bot.
+///
+/// # Example
+/// ```rhai
+/// fn step(character) {
+///     if character.hp < character.max_hp / 2 {
+///         return rest();
+///     }
+///
+///     let map = find_map("monster", "chicken");
+///     if character.x != map.x || character.y != map.y {
+///         return move_to(map.x, map.y);
+///     }
+///
+///     fight()
+/// }
+/// ```
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Mutex<HashMap<PathBuf, CompiledScript>>,
+}
+
+impl std::fmt::Debug for ScriptHost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptHost")
+            .field("scripts", &self.scripts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ScriptHost {
+    pub fn new(api: ArtifactsClient) -> Self {
+        Self {
+            engine: engine(api),
+            scripts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Runs the script at `path` to decide the next action of `character`.
+    ///
+    /// World queries block the current thread, so this must be called from a
+    /// multi-threaded Tokio runtime.
+    pub fn next_action(
+        &self,
+        path: &Path,
+        character: &Character,
+    ) -> Result<OneOffAction, ScriptError> {
+        let ast = self.load(path)?;
+        let runtime = |message: String| ScriptError::Runtime {
+            path: path.to_path_buf(),
+            message,
+        };
+
+        let character = rhai::serde::to_dynamic(character).map_err(|e| runtime(e.to_string()))?;
+
+        tokio::task::block_in_place(|| {
+            self.engine
+                .call_fn::<OneOffAction>(&mut Scope::new(), &ast, STEP_FN, (character,))
+        })
+        .map_err(|e| runtime(e.to_string()))
+    }
+
+    /// Returns the compiled script at `path`, recompiling it if the file has
+    /// changed since it was last loaded.
+    fn load(&self, path: &Path) -> Result<AST, ScriptError> {
+        let io = |source| ScriptError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let modified = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(io)?;
+
+        let mut scripts = self.scripts.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(script) = scripts.get(path)
+            && script.modified == modified
+        {
+            return script.ast(path);
+        }
+
+        let source = std::fs::read_to_string(path).map_err(io)?;
+        let previous = scripts.remove(path).and_then(|script| script.ast);
+
+        let script = match self.engine.compile(source) {
+            Ok(ast) => {
+                if previous.is_some() {
+                    info!(target: "script", "Reloaded script {}", path.display());
+                }
+                CompiledScript {
+                    modified,
+                    ast: Some(ast),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                if previous.is_some() {
+                    error!(
+                        target: "script",
+                        "Failed to compile {}, keeping the previous version: {}",
+                        path.display(),
+                        e
+                    );
+                }
+                CompiledScript {
+                    modified,
+                    ast: previous,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        let result = script.ast(path);
+        scripts.insert(path.to_path_buf(), script);
+        result
+    }
+}
+
+/// Blocks on an API request made from a script.
+fn block_on<T, E: Display>(
+    future: impl Future<Output = Result<T, E>>,
+) -> Result<T, Box<EvalAltResult>> {
+    tokio::runtime::Handle::current()
+        .block_on(future)
+        .map_err(|e| e.to_string().into())
+}
+
+fn to_dynamic<T: Serialize>(value: &T) -> Result<Dynamic, Box<EvalAltResult>> {
+    rhai::serde::to_dynamic(value)
+}
+
+/// Converts a quantity passed by a script, which must be at least 1.
+fn quantity(quantity: i64) -> Result<u32, Box<EvalAltResult>> {
+    u32::try_from(quantity)
+        .ok()
+        .filter(|q| *q > 0)
+        .ok_or_else(|| format!("Invalid quantity: {}", quantity).into())
+}
+
+/// Creates the script engine with the action and world query bindings.
+fn engine(api: ArtifactsClient) -> Engine {
+    let mut engine = Engine::new();
+
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTHS.0, MAX_EXPR_DEPTHS.1);
+
+    engine.on_print(|message| info!(target: "script", "{}", message));
+    engine.on_debug(|message, _, pos| info!(target: "script", "{:?}: {}", pos, message));
+
+    engine
+        .register_type_with_name::<OneOffAction>("Action")
+        .register_fn("to_string", |action: &mut OneOffAction| action.to_string())
+        .register_fn("fight", || OneOffAction::Fight)
+        .register_fn("gather", || OneOffAction::Gather)
+        .register_fn("rest", || OneOffAction::Rest)
+        .register_fn("move_to", |x: i64, y: i64| OneOffAction::Move {
+            x: x as i32,
+            y: y as i32,
+        })
+        .register_fn(
+            "craft",
+            |code: &str, n: i64| -> Result<OneOffAction, Box<EvalAltResult>> {
+                Ok(OneOffAction::Craft {
+                    code: code.into(),
+                    quantity: quantity(n)?,
+                })
+            },
+        )
+        .register_fn("deposit_all", || OneOffAction::DepositAll)
+        .register_fn(
+            "withdraw",
+            |code: &str, n: i64| -> Result<OneOffAction, Box<EvalAltResult>> {
+                Ok(OneOffAction::Withdraw {
+                    code: code.into(),
+                    quantity: quantity(n)?,
+                })
+            },
+        )
+        .register_fn(
+            "equip",
+            |code: &str, slot: &str| -> Result<OneOffAction, Box<EvalAltResult>> {
+                Ok(OneOffAction::Equip {
+                    code: code.into(),
+                    slot: slot.parse::<EquipmentSlot>()?,
+                })
+            },
+        )
+        .register_fn(
+            "use_item",
+            |code: &str, n: i64| -> Result<OneOffAction, Box<EvalAltResult>> {
+                Ok(OneOffAction::UseItem {
+                    code: code.into(),
+                    quantity: quantity(n)?,
+                })
+            },
+        )
+        .register_fn(
+            "log",
+            |message: &str| info!(target: "script", "{}", message),
+        );
+
+    let client = api.clone();
+    engine.register_fn("character", move |name: &str| {
+        let name: CharacterName = name.parse().map_err(|e: NameError| e.to_string())?;
+        to_dynamic(&block_on(client.get_character(&name))?)
+    });
+
+    let client = api.clone();
+    engine.register_fn(
+        "find_map",
+        move |content_type: &str, code: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+            let content_type: MapContentType =
+                serde_json::from_value(serde_json::Value::String(content_type.to_string()))
+                    .map_err(|_| format!("Unknown content type: {}", content_type))?;
+            let query = MapQuery::default()
+                .content_type(content_type)
+                .content_code(code.to_string());
+
+            match block_on(client.get_maps(&query))?.first() {
+                Some(map) => to_dynamic(map),
+                None => Ok(Dynamic::UNIT),
+            }
+        },
+    );
+
+    let client = api.clone();
+    engine.register_fn("monster", move |code: &str| {
+        to_dynamic(&block_on(client.get_monster(&code.into()))?)
+    });
+
+    let client = api.clone();
+    engine.register_fn("resource", move |code: &str| {
+        to_dynamic(&block_on(client.get_resource(&code.into()))?)
+    });
+
+    let client = api.clone();
+    engine.register_fn("item", move |code: &str| {
+        to_dynamic(&block_on(client.get_item(&code.into()))?)
+    });
+
+    let client = api;
+    engine.register_fn("bank_items", move || {
+        let items = block_on(fetch_all_pages(|page| {
+            let client = &client;
+            async move {
+                client
+                    .get_bank_items(
+                        &BankItemQuery::default()
+                            .page_number(page)
+                            .page_size(MAX_PAGE_SIZE),
+                    )
+                    .await
+            }
+        }))?;
+        to_dynamic(&items)
+    });
+
+    engine
+}
@@ -0,0 +1,290 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{get, post, put},
+};
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::bot::{
+    control::{BotCommand, BotController, BotEvent, CharacterStatus, ControlError, OneOffAction},
+    strategy::Strategy,
+};
+
+/// The address control clients connect to when none is configured.
+pub const DEFAULT_CONTROL_ADDR: &str = "127.0.0.1:7878";
+
+#[derive(Debug, Clone)]
+struct ServerState {
+    controller: BotController,
+    cancel: CancellationToken,
+}
+
+impl IntoResponse for ControlError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ControlError::UnknownCharacter(_) => StatusCode::NOT_FOUND,
+            ControlError::Stopped(_) => StatusCode::CONFLICT,
+        };
+
+        let body = serde_json::json!({ "error": self.to_string() });
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Builds the routes of the control API.
+///
+/// - `GET /characters` lists every bot and its strategy.
+/// - `GET /characters/{name}` shows a single bot.
+/// - `POST /characters/{name}/pause`, `/resume` and `/stop` change whether
+///   the bot acts.
+/// - `PUT /characters/{name}/strategy` swaps the bot's strategy.
+/// - `POST /characters/{name}/actions` queues a one-off action.
+/// - `GET /events` streams the recent and future bot events as server-sent
+///   events.
+pub fn router(controller: BotController, cancel: CancellationToken) -> Router {
+    Router::new()
+        .route("/characters", get(list_characters))
+        .route("/characters/{name}", get(get_character))
+        .route("/characters/{name}/pause", post(pause))
+        .route("/characters/{name}/resume", post(resume))
+        .route("/characters/{name}/stop", post(stop))
+        .route("/characters/{name}/strategy", put(set_strategy))
+        .route("/characters/{name}/actions", post(queue_action))
+        .route("/events", get(events))
+        .with_state(ServerState { controller, cancel })
+}
+
+/// Serves the control API on `addr` until `cancel` is triggered.
+pub async fn serve(
+    controller: BotController,
+    addr: SocketAddr,
+    cancel: CancellationToken,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    info!(target: "control", "Control server listening on {}", addr);
+
+    // The server has no authentication, so it should only be reachable
+    // from this machine.
+    if !addr.ip().is_loopback() {
+        warn!(
+            target: "control",
+            "Control server is bound to non-loopback address {}, anyone who can reach it can control the bots",
+            addr
+        );
+    }
+
+    axum::serve(listener, router(controller, cancel.clone()))
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+}
+
+async fn list_characters(State(state): State<ServerState>) -> Json<Vec<CharacterStatus>> {
+    Json(state.controller.characters())
+}
+
+async fn get_character(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<Json<CharacterStatus>, ControlError> {
+    state
+        .controller
+        .character(&name)
+        .map(Json)
+        .ok_or(ControlError::UnknownCharacter(name))
+}
+
+async fn pause(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ControlError> {
+    state.controller.send(&name, BotCommand::Pause)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn resume(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ControlError> {
+    state.controller.send(&name, BotCommand::Resume)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn stop(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ControlError> {
+    state.controller.send(&name, BotCommand::Stop)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn set_strategy(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+    Json(strategy): Json<Strategy>,
+) -> Result<StatusCode, ControlError> {
+    state
+        .controller
+        .send(&name, BotCommand::SetStrategy(strategy))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn queue_action(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+    Json(action): Json<OneOffAction>,
+) -> Result<StatusCode, ControlError> {
+    state.controller.send(&name, BotCommand::Queue(action))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn events(
+    State(state): State<ServerState>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    // Subscribe before reading the recent events so none are missed in
+    // between.
+    let live = BroadcastStream::new(state.controller.subscribe())
+        .filter_map(|event| async move { event.ok() });
+    let recent = futures::stream::iter(state.controller.recent_events());
+
+    let stream = recent
+        .chain(live)
+        .map(|event| Event::default().event("bot").json_data(event))
+        // End the stream on shutdown so the server does not wait for the
+        // client to disconnect.
+        .take_until(state.cancel.cancelled_owned());
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// A client for the control API of a running bot process.
+#[derive(Debug, Clone)]
+pub struct ControlClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl ControlClient {
+    /// Creates a client for the control server at `addr`, e.g.
+    /// `127.0.0.1:7878` or `http://127.0.0.1:7878`.
+    pub fn new(addr: &str) -> Self {
+        let base_url = if addr.starts_with("http://") || addr.starts_with("https://") {
+            addr.trim_end_matches('/').to_string()
+        } else {
+            format!("http://{}", addr)
+        };
+
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub async fn characters(&self) -> anyhow::Result<Vec<CharacterStatus>> {
+        let url = format!("{}/characters", self.base_url);
+        let resp = check(self.client.get(url).send().await?).await?;
+        Ok(resp.json().await?)
+    }
+
+    pub async fn character(&self, name: &str) -> anyhow::Result<CharacterStatus> {
+        let url = format!("{}/characters/{}", self.base_url, name);
+        let resp = check(self.client.get(url).send().await?).await?;
+        Ok(resp.json().await?)
+    }
+
+    pub async fn pause(&self, name: &str) -> anyhow::Result<()> {
+        self.post_command(name, "pause").await
+    }
+
+    pub async fn resume(&self, name: &str) -> anyhow::Result<()> {
+        self.post_command(name, "resume").await
+    }
+
+    pub async fn stop(&self, name: &str) -> anyhow::Result<()> {
+        self.post_command(name, "stop").await
+    }
+
+    pub async fn set_strategy(&self, name: &str, strategy: &Strategy) -> anyhow::Result<()> {
+        let url = format!("{}/characters/{}/strategy", self.base_url, name);
+        check(self.client.put(url).json(strategy).send().await?).await?;
+        Ok(())
+    }
+
+    pub async fn queue(&self, name: &str, action: &OneOffAction) -> anyhow::Result<()> {
+        let url = format!("{}/characters/{}/actions", self.base_url, name);
+        check(self.client.post(url).json(action).send().await?).await?;
+        Ok(())
+    }
+
+    async fn post_command(&self, name: &str, command: &str) -> anyhow::Result<()> {
+        let url = format!("{}/characters/{}/{}", self.base_url, name, command);
+        check(self.client.post(url).send().await?).await?;
+        Ok(())
+    }
+
+    /// Streams bot events, calling `on_event` for each, until the server
+    /// closes the connection.
+    pub async fn events(&self, mut on_event: impl FnMut(BotEvent)) -> anyhow::Result<()> {
+        let url = format!("{}/events", self.base_url);
+        let mut resp = check(self.client.get(url).send().await?).await?;
+        let mut buffer = Vec::new();
+        // The data lines of the message being received.
+        let mut data = Vec::new();
+
+        while let Some(chunk) = resp.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+
+            // Only complete lines are decoded, so characters split across
+            // chunks are kept intact.
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line = buffer.drain(..=end).collect::<Vec<_>>();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches(['\n', '\r']);
+
+                if let Some(value) = line.strip_prefix("data:") {
+                    data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+                } else if line.is_empty() && !data.is_empty() {
+                    // Keep-alive messages only contain a comment, so have no
+                    // data.
+                    on_event(serde_json::from_str(&data.join("\n"))?);
+                    data.clear();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Turns an error response from the control server into an error.
+async fn check(resp: reqwest::Response) -> anyhow::Result<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+
+    match resp.json::<ErrorBody>().await {
+        Ok(body) => Err(anyhow::anyhow!("{}: {}", status, body.error)),
+        Err(_) => Err(anyhow::anyhow!("Control server returned {}", status)),
+    }
+}
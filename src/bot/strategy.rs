@@ -0,0 +1,552 @@
+use std::path::PathBuf;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::info;
+
+use crate::{
+    api::ArtifactsError,
+    bot::BotContext,
+    cooldown,
+    goals::Goal,
+    history::{ActionRecord, error_status},
+    models::{
+        character::Character,
+        equipment::EquipmentSlot,
+        fight::FightResult,
+        item::SimpleItem,
+        map::{MapContent, MapContentType},
+        skill::Skill,
+        types::{CharacterName, ItemCode, MonsterCode, Position, ResourceCode},
+    },
+};
+
+/// A repeatable activity for a character.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Strategy {
+    /// Fight the given monster, resting when HP runs low.
+    Fight { monster: MonsterCode },
+    /// Gather the given resource.
+    Gather { resource: ResourceCode },
+    /// Let a Rhai script decide each action. See
+    /// [`ScriptHost`](crate::bot::script::ScriptHost).
+    Script { path: PathBuf },
+    /// Tick the behavior tree in the given YAML or TOML file. See
+    /// [`BehaviorTree`](crate::bt::BehaviorTree).
+    Tree { path: PathBuf },
+    /// Fight or gather whatever levels up fastest until the goal is reached,
+    /// e.g. `mining 20`. See [`GoalPlanner`](crate::goals::GoalPlanner).
+    Goal { goal: Goal },
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::Fight { monster } => write!(f, "fight {}", monster),
+            Strategy::Gather { resource } => write!(f, "gather {}", resource),
+            Strategy::Script { path } => write!(f, "script {}", path.display()),
+            Strategy::Tree { path } => write!(f, "tree {}", path.display()),
+            Strategy::Goal { goal } => write!(f, "goal {}", goal),
+        }
+    }
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    /// Parses strategies such as `fight chicken`, `gather copper_rocks`,
+    /// `script bots/miner.rhai`, `tree bots/farmer.yaml` or `goal mining 20`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            ["fight", monster] => Ok(Strategy::Fight {
+                monster: monster.into(),
+            }),
+            ["gather", resource] => Ok(Strategy::Gather {
+                resource: resource.into(),
+            }),
+            ["script", path] => Ok(Strategy::Script { path: path.into() }),
+            ["tree", path] => Ok(Strategy::Tree { path: path.into() }),
+            ["goal", ref goal @ ..] if !goal.is_empty() => Ok(Strategy::Goal {
+                goal: goal.join(" ").parse()?,
+            }),
+            _ => Err(anyhow::anyhow!("Invalid strategy: {}", s)),
+        }
+    }
+}
+
+/// The kind of action performed by a single [`Strategy::step`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    Move,
+    Rest,
+    Fight,
+    Gather,
+    Craft,
+    Deposit,
+    Withdraw,
+    UseItem,
+    Equip,
+    Sell,
+}
+
+/// The result of a single [`Strategy::step`].
+#[derive(Debug, Clone)]
+pub struct StepOutcome {
+    pub action: StepAction,
+    pub character: Character,
+    /// The XP gained, either character XP for fights or skill XP for
+    /// gathering.
+    pub xp: u32,
+    /// The skill that gained XP, or `None` for character XP.
+    pub skill: Option<Skill>,
+    pub cooldown_seconds: u32,
+    pub fight_result: Option<FightResult>,
+    /// The gold won in a fight.
+    pub gold: u32,
+    /// The items dropped by a fight or gathered.
+    pub drops: Vec<SimpleItem>,
+    /// The number of turns a fight lasted.
+    pub turns: u32,
+    /// The HP recovered by resting.
+    pub hp_restored: u32,
+}
+
+impl StepOutcome {
+    fn new(action: StepAction, character: Character, cooldown_seconds: u32) -> Self {
+        Self {
+            action,
+            character,
+            xp: 0,
+            skill: None,
+            cooldown_seconds,
+            fight_result: None,
+            gold: 0,
+            drops: Vec::new(),
+            turns: 0,
+            hp_restored: 0,
+        }
+    }
+}
+
+/// An action the API rejected or that could not be sent, with the status the
+/// API answered with, if any.
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct ActionError {
+    pub status: Option<StatusCode>,
+    message: String,
+}
+
+impl ActionError {
+    /// Returns false for failures that retrying cannot fix, i.e. the token was
+    /// rejected or the character does not exist.
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(self.status.map(|s| s.as_u16()), Some(401 | 403 | 404 | 498))
+    }
+}
+
+impl<E: std::fmt::Display> From<ArtifactsError<E>> for ActionError {
+    fn from(error: ArtifactsError<E>) -> Self {
+        Self {
+            status: error_status(&error),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Strategy {
+    /// Performs the next action of the strategy for `character`, waiting for
+    /// any active cooldown first.
+    ///
+    /// Each call performs exactly one action: resting, moving to the right
+    /// map, or the activity itself. Scripts and behavior trees decide the
+    /// action themselves.
+    pub async fn step(
+        &self,
+        ctx: &BotContext,
+        character: &Character,
+    ) -> anyhow::Result<StepOutcome> {
+        cooldown::sleep_until_expired(character.cooldown_expiration).await;
+
+        let target = match self {
+            Strategy::Fight { monster } => Target::Monster(monster.clone()),
+            Strategy::Gather { resource } => Target::Resource(resource.clone()),
+            Strategy::Script { path } => {
+                let action = ctx.scripts.next_action(path, character)?;
+                return action.perform(ctx, character).await;
+            }
+            Strategy::Tree { path } => {
+                return ctx.trees.load(path)?.tick(ctx, character).await;
+            }
+            // Boxed, as the goal's phases step through this function too.
+            Strategy::Goal { goal } => {
+                return Box::pin(ctx.goals.step(ctx, character, *goal)).await;
+            }
+        };
+        let location = ctx
+            .locate(
+                target.content_type(),
+                Some(target.code()),
+                character.position,
+            )
+            .await?;
+        pursue(ctx, character, &target, location).await
+    }
+}
+
+/// The monster or resource [`pursue`] fights or gathers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Target {
+    Monster(MonsterCode),
+    Resource(ResourceCode),
+}
+
+impl Target {
+    /// The monster or resource held by a map or event, if any.
+    pub(crate) fn of(content: &MapContent) -> Option<Self> {
+        content
+            .monster_code()
+            .map(Self::Monster)
+            .or_else(|| content.resource_code().map(Self::Resource))
+    }
+
+    fn content_type(&self) -> MapContentType {
+        match self {
+            Target::Monster(_) => MapContentType::Monster,
+            Target::Resource(_) => MapContentType::Resource,
+        }
+    }
+
+    fn code(&self) -> &str {
+        match self {
+            Target::Monster(code) => code,
+            Target::Resource(code) => code,
+        }
+    }
+}
+
+/// Performs the next action to fight or gather `target` at `location`:
+/// resting first if the monster needs it, then moving there, then the
+/// activity itself.
+pub(crate) async fn pursue(
+    ctx: &BotContext,
+    character: &Character,
+    target: &Target,
+    location: Position,
+) -> anyhow::Result<StepOutcome> {
+    let name = &character.name;
+
+    if matches!(target, Target::Monster(_))
+        && (character.hp as f64) < character.max_hp as f64 * ctx.rest_threshold
+    {
+        info!(target: "bot", "{} is resting ({}/{} HP)", name, character.hp, character.max_hp);
+        return rest(ctx, name).await;
+    }
+
+    if character.position != location {
+        return move_to(ctx, name, location).await;
+    }
+
+    match target {
+        Target::Monster(monster) => fight(ctx, name, Some(monster)).await,
+        Target::Resource(resource) => gather(ctx, name, Some(resource)).await,
+    }
+}
+
+/// Rests to recover HP.
+pub(crate) async fn rest(ctx: &BotContext, name: &CharacterName) -> anyhow::Result<StepOutcome> {
+    let record = ActionRecord::new(name, "rest", serde_json::json!({}));
+    let result = ctx.api.rest(name).await;
+    ctx.record(record, &result, ActionRecord::with_rest);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome {
+        hp_restored: data.hp_restored,
+        ..StepOutcome::new(
+            StepAction::Rest,
+            data.character,
+            data.cooldown.total_seconds,
+        )
+    })
+}
+
+/// Moves to the map at `to`.
+pub(crate) async fn move_to(
+    ctx: &BotContext,
+    name: &CharacterName,
+    to: Position,
+) -> anyhow::Result<StepOutcome> {
+    info!(target: "bot", "{} is moving to {}", name, to);
+    let record = ActionRecord::new(name, "move", serde_json::to_value(to)?);
+    let result = ctx.api.move_character(name, to).await;
+    ctx.record(record, &result, ActionRecord::with_movement);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome::new(
+        StepAction::Move,
+        data.character,
+        data.cooldown.total_seconds,
+    ))
+}
+
+/// Moves `character` to the closest map of `content_type` unless it is
+/// already on one, then waits for the move's cooldown.
+async fn move_to_closest(
+    ctx: &BotContext,
+    character: &Character,
+    content_type: MapContentType,
+) -> anyhow::Result<()> {
+    let location = ctx.locate(content_type, None, character.position).await?;
+    if character.position != location {
+        let outcome = move_to(ctx, &character.name, location).await?;
+        cooldown::sleep_until_expired(outcome.character.cooldown_expiration).await;
+    }
+    Ok(())
+}
+
+/// Fights the monster on the current map, which is recorded as `monster` if
+/// known.
+pub(crate) async fn fight(
+    ctx: &BotContext,
+    name: &CharacterName,
+    monster: Option<&MonsterCode>,
+) -> anyhow::Result<StepOutcome> {
+    let mut record = ActionRecord::new(name, "fight", serde_json::json!({}));
+    if let Some(monster) = monster {
+        record = record.target(monster);
+    }
+    let result = ctx.api.fight(name).await;
+    ctx.record(record, &result, ActionRecord::with_fight);
+
+    let data = result.map_err(ActionError::from)?;
+    info!(target: "bot", "{} fight result: {:?}", name, data.fight.result);
+    Ok(StepOutcome {
+        xp: data.fight.xp,
+        fight_result: Some(data.fight.result),
+        gold: data.fight.gold,
+        drops: data
+            .fight
+            .drops
+            .iter()
+            .map(|d| SimpleItem {
+                code: d.code.clone(),
+                quantity: d.quantity,
+            })
+            .collect(),
+        turns: data.fight.turns,
+        ..StepOutcome::new(
+            StepAction::Fight,
+            data.character,
+            data.cooldown.total_seconds,
+        )
+    })
+}
+
+/// Gathers the resource on the current map, which is recorded as `resource`
+/// if known.
+pub(crate) async fn gather(
+    ctx: &BotContext,
+    name: &CharacterName,
+    resource: Option<&ResourceCode>,
+) -> anyhow::Result<StepOutcome> {
+    let mut record = ActionRecord::new(name, "gathering", serde_json::json!({}));
+    if let Some(resource) = resource {
+        record = record.target(resource);
+    }
+    let result = ctx.api.gather(name).await;
+    ctx.record(record, &result, ActionRecord::with_skill);
+
+    let data = result.map_err(ActionError::from)?;
+    info!(target: "bot", "{} gathered {:?}", name, data.details.items);
+    let skill = match resource {
+        Some(resource) => ctx.resource_skill(resource).await,
+        None => None,
+    };
+    Ok(StepOutcome {
+        xp: data.details.xp,
+        skill,
+        drops: data.details.items,
+        ..StepOutcome::new(
+            StepAction::Gather,
+            data.character,
+            data.cooldown.total_seconds,
+        )
+    })
+}
+
+/// Crafts `quantity` of `code` at the workshop on the current map.
+pub(crate) async fn craft(
+    ctx: &BotContext,
+    name: &CharacterName,
+    code: &ItemCode,
+    quantity: u32,
+) -> anyhow::Result<StepOutcome> {
+    let record = ActionRecord::new(
+        name,
+        "crafting",
+        serde_json::json!({ "code": code, "quantity": quantity }),
+    )
+    .target(code);
+    let result = ctx.api.craft(name, code, quantity).await;
+    ctx.record(record, &result, ActionRecord::with_skill);
+
+    let data = result.map_err(ActionError::from)?;
+    info!(target: "bot", "{} crafted {} x{}", name, code, quantity);
+    Ok(StepOutcome {
+        xp: data.details.xp,
+        skill: ctx
+            .api
+            .get_item(code)
+            .await
+            .ok()
+            .and_then(|i| i.craft)
+            .map(|c| c.skill),
+        ..StepOutcome::new(
+            StepAction::Craft,
+            data.character,
+            data.cooldown.total_seconds,
+        )
+    })
+}
+
+/// Deposits every item in the character's inventory into the bank on the
+/// current map.
+pub(crate) async fn deposit_all(
+    ctx: &BotContext,
+    character: &Character,
+) -> anyhow::Result<StepOutcome> {
+    let name = &character.name;
+    let items = character
+        .inventory
+        .iter()
+        .filter(|slot| slot.quantity > 0)
+        .map(|slot| SimpleItem {
+            code: slot.code.clone(),
+            quantity: slot.quantity,
+        })
+        .collect::<Vec<_>>();
+
+    info!(target: "bot", "{} is depositing {} items", name, items.len());
+    let record = ActionRecord::new(name, "deposit", serde_json::to_value(&items)?);
+    let result = ctx.api.deposit_items(name, &items).await;
+    ctx.record(record, &result, ActionRecord::with_bank);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome::new(
+        StepAction::Deposit,
+        data.character,
+        data.cooldown.total_seconds,
+    ))
+}
+
+/// Withdraws `quantity` of `code` from the closest bank, moving there first
+/// if needed.
+pub(crate) async fn withdraw(
+    ctx: &BotContext,
+    character: &Character,
+    code: &ItemCode,
+    quantity: u32,
+) -> anyhow::Result<StepOutcome> {
+    let name = &character.name;
+    move_to_closest(ctx, character, MapContentType::Bank).await?;
+
+    let items = [SimpleItem {
+        code: code.clone(),
+        quantity,
+    }];
+
+    info!(target: "bot", "{} is withdrawing {} x{}", name, code, quantity);
+    let record = ActionRecord::new(name, "withdraw", serde_json::to_value(&items)?).target(code);
+    let result = ctx.api.withdraw_items(name, &items).await;
+    ctx.record(record, &result, ActionRecord::with_bank);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome::new(
+        StepAction::Withdraw,
+        data.character,
+        data.cooldown.total_seconds,
+    ))
+}
+
+/// Lists `quantity` of `code` on the closest Grand Exchange at `price` per
+/// item, moving there first if needed.
+pub(crate) async fn sell(
+    ctx: &BotContext,
+    character: &Character,
+    code: &ItemCode,
+    quantity: u32,
+    price: u32,
+) -> anyhow::Result<StepOutcome> {
+    let name = &character.name;
+    move_to_closest(ctx, character, MapContentType::GrandExchange).await?;
+
+    info!(target: "bot", "{} is selling {} x{} at {} each", name, code, quantity, price);
+    let record = ActionRecord::new(
+        name,
+        "ge_sell",
+        serde_json::json!({ "code": code, "quantity": quantity, "price": price }),
+    )
+    .target(code);
+    let result = ctx.api.ge_sell(name, code, quantity, price).await;
+    ctx.record(record, &result, ActionRecord::with_ge_order);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome::new(
+        StepAction::Sell,
+        data.character,
+        data.cooldown.total_seconds,
+    ))
+}
+
+/// Uses `quantity` of a consumable from the character's inventory.
+pub(crate) async fn use_item(
+    ctx: &BotContext,
+    name: &CharacterName,
+    code: &ItemCode,
+    quantity: u32,
+) -> anyhow::Result<StepOutcome> {
+    info!(target: "bot", "{} is using {} x{}", name, code, quantity);
+    let record = ActionRecord::new(
+        name,
+        "use",
+        serde_json::json!({ "code": code, "quantity": quantity }),
+    )
+    .target(code);
+    let result = ctx.api.use_item(name, code, quantity).await;
+    ctx.record(record, &result, ActionRecord::with_use_item);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome::new(
+        StepAction::UseItem,
+        data.character,
+        data.cooldown.total_seconds,
+    ))
+}
+
+/// Equips `code` from the character's inventory into `slot`.
+pub(crate) async fn equip(
+    ctx: &BotContext,
+    name: &CharacterName,
+    code: &ItemCode,
+    slot: EquipmentSlot,
+) -> anyhow::Result<StepOutcome> {
+    info!(target: "bot", "{} is equipping {} in {}", name, code, slot);
+    let record = ActionRecord::new(
+        name,
+        "equip",
+        serde_json::json!({ "code": code, "slot": slot }),
+    )
+    .target(code);
+    let result = ctx.api.equip(name, code, slot).await;
+    ctx.record(record, &result, ActionRecord::with_equip);
+
+    let data = result.map_err(ActionError::from)?;
+    Ok(StepOutcome::new(
+        StepAction::Equip,
+        data.character,
+        data.cooldown.total_seconds,
+    ))
+}
@@ -0,0 +1,573 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, error, info};
+
+use crate::{
+    bot::{
+        BotContext,
+        strategy::{self, StepOutcome},
+    },
+    models::{character::Character, map::MapContentType, skill::Skill, types::ItemCode},
+};
+
+/// Errors that can occur while loading or ticking a behavior tree.
+///
+/// None of these happen after an action has been sent, so the bot can safely
+/// retry once the tree has been fixed.
+#[derive(Debug, Error)]
+pub enum BtError {
+    #[error("Failed to read behavior tree {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse behavior tree {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("Behavior tree {0} must be a .yaml, .yml or .toml file")]
+    UnknownFormat(PathBuf),
+    #[error("Invalid behavior tree node {node}: {message}")]
+    Invalid { node: String, message: String },
+    #[error("Behavior tree finished without performing an action")]
+    Idle,
+}
+
+/// The result of ticking a node.
+#[derive(Debug)]
+pub enum Status {
+    Success,
+    Failure,
+    /// An action was performed, ending the tick.
+    Acted(Box<StepOutcome>),
+}
+
+/// A node of a behavior tree.
+///
+/// Nodes are externally tagged, e.g. `{ sequence: [...] }` or
+/// `{ condition: inventory_full }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Node {
+    /// Ticks its children in order until one fails.
+    Sequence(Vec<Node>),
+    /// Ticks its children in order until one succeeds.
+    Selector(Vec<Node>),
+    Condition(Condition),
+    Action(Action),
+}
+
+/// A check on the character's state, which never performs an action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// HP is below the given percentage of max HP.
+    HpBelow(f64),
+    InventoryFull,
+    /// The inventory holds at least `at_least` of the item.
+    ItemCount {
+        code: ItemCode,
+        at_least: u32,
+    },
+    /// The character's level in `skill` is at least `at_least`.
+    SkillLevel {
+        skill: Skill,
+        at_least: u32,
+    },
+    Not(Box<Condition>),
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// An action performed by the character.
+///
+/// Actions that have nothing to do, such as moving to the map the character
+/// is already on, succeed without acting so the tick continues.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Move to the map with the given content closest to the character.
+    MoveTo {
+        content_type: MapContentType,
+        code: String,
+    },
+    Fight,
+    Gather,
+    /// Craft at the workshop on the current map.
+    Craft {
+        code: ItemCode,
+        #[serde(default = "one")]
+        quantity: u32,
+    },
+    /// Deposit the whole inventory into the bank on the current map.
+    DepositAll,
+    Rest,
+    /// Use a consumable. Fails if the inventory does not hold enough of it.
+    UseItem {
+        code: ItemCode,
+        #[serde(default = "one")]
+        quantity: u32,
+    },
+}
+
+impl Condition {
+    pub fn check(&self, character: &Character) -> bool {
+        match self {
+            Condition::HpBelow(percent) => {
+                (character.hp as f64) < character.max_hp as f64 * percent / 100.0
+            }
+            Condition::InventoryFull => character.inventory_full(),
+            Condition::ItemCount { code, at_least } => {
+                character.inventory_quantity(code) >= *at_least
+            }
+            Condition::SkillLevel { skill, at_least } => character.skill_level(*skill) >= *at_least,
+            Condition::Not(condition) => !condition.check(character),
+        }
+    }
+
+    fn validate(&self, node: &str) -> Result<(), BtError> {
+        match self {
+            Condition::HpBelow(percent) if !(0.0..=100.0).contains(percent) => {
+                Err(BtError::Invalid {
+                    node: node.to_string(),
+                    message: format!("hp_below must be between 0 and 100, got {}", percent),
+                })
+            }
+            Condition::Not(condition) => condition.validate(&format!("{}/not", node)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Action {
+    pub async fn perform(&self, ctx: &BotContext, character: &Character) -> anyhow::Result<Status> {
+        let name = &character.name;
+
+        let outcome = match self {
+            Action::MoveTo { content_type, code } => {
+                let location = ctx
+                    .locate(*content_type, Some(code), character.position)
+                    .await?;
+                if character.position == location {
+                    return Ok(Status::Success);
+                }
+                strategy::move_to(ctx, name, location).await?
+            }
+            Action::Fight => strategy::fight(ctx, name, None).await?,
+            Action::Gather => strategy::gather(ctx, name, None).await?,
+            Action::Craft { code, quantity } => strategy::craft(ctx, name, code, *quantity).await?,
+            Action::DepositAll => {
+                if character.inventory_count() == 0 {
+                    return Ok(Status::Success);
+                }
+                strategy::deposit_all(ctx, character).await?
+            }
+            Action::Rest => {
+                if character.hp >= character.max_hp {
+                    return Ok(Status::Success);
+                }
+                strategy::rest(ctx, name).await?
+            }
+            Action::UseItem { code, quantity } => {
+                if character.inventory_quantity(code) < *quantity {
+                    return Ok(Status::Failure);
+                }
+                strategy::use_item(ctx, name, code, *quantity).await?
+            }
+        };
+
+        Ok(Status::Acted(Box::new(outcome)))
+    }
+
+    fn validate(&self, node: &str) -> Result<(), BtError> {
+        match self {
+            Action::Craft { quantity: 0, .. } | Action::UseItem { quantity: 0, .. } => {
+                Err(BtError::Invalid {
+                    node: node.to_string(),
+                    message: "quantity must be at least 1".to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Node {
+    /// Ticks the node, stopping as soon as an action is performed.
+    pub fn tick<'a>(
+        &'a self,
+        ctx: &'a BotContext,
+        character: &'a Character,
+    ) -> BoxFuture<'a, anyhow::Result<Status>> {
+        Box::pin(async move {
+            match self {
+                Node::Sequence(children) => {
+                    for child in children {
+                        match child.tick(ctx, character).await? {
+                            Status::Success => continue,
+                            status => return Ok(status),
+                        }
+                    }
+                    Ok(Status::Success)
+                }
+                Node::Selector(children) => {
+                    for child in children {
+                        match child.tick(ctx, character).await? {
+                            Status::Failure => continue,
+                            status => return Ok(status),
+                        }
+                    }
+                    Ok(Status::Failure)
+                }
+                Node::Condition(condition) => Ok(if condition.check(character) {
+                    Status::Success
+                } else {
+                    Status::Failure
+                }),
+                Node::Action(action) => action.perform(ctx, character).await,
+            }
+        })
+    }
+
+    /// Checks the node and its children, where `node` is the node's path in
+    /// the tree, used in error messages.
+    fn validate(&self, node: &str) -> Result<(), BtError> {
+        match self {
+            Node::Sequence(children) | Node::Selector(children) => {
+                let kind = match self {
+                    Node::Sequence(_) => "sequence",
+                    _ => "selector",
+                };
+
+                if children.is_empty() {
+                    return Err(BtError::Invalid {
+                        node: node.to_string(),
+                        message: format!("{} has no children", kind),
+                    });
+                }
+
+                children
+                    .iter()
+                    .enumerate()
+                    .try_for_each(|(i, child)| child.validate(&format!("{}/{}[{}]", node, kind, i)))
+            }
+            Node::Condition(condition) => condition.validate(node),
+            Node::Action(action) => action.validate(node),
+        }
+    }
+
+    fn has_action(&self) -> bool {
+        match self {
+            Node::Sequence(children) | Node::Selector(children) => {
+                children.iter().any(Node::has_action)
+            }
+            Node::Condition(_) => false,
+            Node::Action(_) => true,
+        }
+    }
+}
+
+/// A strategy defined as a behavior tree, ticked once per action.
+///
+/// # Example
+/// Fight chickens until the inventory is full, then empty it at the bank:
+/// ```yaml
+/// root:
+///   selector:
+///     - sequence:
+///         - condition: inventory_full
+///         - action: { move_to: { content_type: bank, code: bank } }
+///         - action: deposit_all
+///     - sequence:
+///         - condition: { hp_below: 50 }
+///         - action: rest
+///     - sequence:
+///         - action: { move_to: { content_type: monster, code: chicken } }
+///         - action: fight
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BehaviorTree {
+    // Enums are written as single-key maps rather than YAML tags.
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub root: Node,
+}
+
+impl BehaviorTree {
+    /// Loads and validates the tree at `path`, parsed as YAML or TOML
+    /// depending on its extension.
+    pub fn load(path: &Path) -> Result<Self, BtError> {
+        let text = std::fs::read_to_string(path).map_err(|source| BtError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let parse_error = |message: String| BtError::Parse {
+            path: path.to_path_buf(),
+            message,
+        };
+
+        let tree: Self = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => {
+                serde_yaml::from_str(&text).map_err(|e| parse_error(e.to_string()))?
+            }
+            Some("toml") => toml::from_str(&text).map_err(|e| parse_error(e.to_string()))?,
+            _ => return Err(BtError::UnknownFormat(path.to_path_buf())),
+        };
+
+        tree.validate()?;
+        Ok(tree)
+    }
+
+    /// Checks that composite nodes have children, values are in range and
+    /// the tree can perform at least one action.
+    pub fn validate(&self) -> Result<(), BtError> {
+        self.root.validate("root")?;
+
+        if !self.root.has_action() {
+            return Err(BtError::Invalid {
+                node: "root".to_string(),
+                message: "tree contains no actions".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Ticks the tree for `character`, performing exactly one action.
+    pub async fn tick(
+        &self,
+        ctx: &BotContext,
+        character: &Character,
+    ) -> anyhow::Result<StepOutcome> {
+        match self.root.tick(ctx, character).await? {
+            Status::Acted(outcome) => Ok(*outcome),
+            status => {
+                debug!(target: "bot", "{}: tree finished with {:?}", character.name, status);
+                Err(BtError::Idle.into())
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LoadedTree {
+    modified: SystemTime,
+    /// The last version that loaded, kept running while the file on disk has
+    /// errors.
+    tree: Option<Arc<BehaviorTree>>,
+    error: Option<String>,
+}
+
+/// The behavior trees used by running bots, reloaded whenever their file
+/// changes.
+#[derive(Debug, Default)]
+pub struct TreeCache {
+    trees: Mutex<HashMap<PathBuf, LoadedTree>>,
+}
+
+impl TreeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the tree at `path`, loading it again if the file has changed
+    /// since it was last loaded.
+    pub fn load(&self, path: &Path) -> Result<Arc<BehaviorTree>, BtError> {
+        let modified = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(|source| BtError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        let mut trees = self.trees.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(loaded) = trees.get(path)
+            && loaded.modified == modified
+        {
+            return loaded.result(path);
+        }
+
+        let previous = trees.remove(path).and_then(|loaded| loaded.tree);
+
+        let loaded = match BehaviorTree::load(path) {
+            Ok(tree) => {
+                if previous.is_some() {
+                    info!(target: "bot", "Reloaded behavior tree {}", path.display());
+                }
+                LoadedTree {
+                    modified,
+                    tree: Some(Arc::new(tree)),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                if previous.is_some() {
+                    error!(
+                        target: "bot",
+                        "Failed to load {}, keeping the previous version: {}",
+                        path.display(),
+                        e
+                    );
+                }
+                LoadedTree {
+                    modified,
+                    tree: previous,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        let result = loaded.result(path);
+        trees.insert(path.to_path_buf(), loaded);
+        result
+    }
+}
+
+impl LoadedTree {
+    fn result(&self, path: &Path) -> Result<Arc<BehaviorTree>, BtError> {
+        match &self.tree {
+            Some(tree) => Ok(tree.clone()),
+            None => Err(BtError::Parse {
+                path: path.to_path_buf(),
+                message: self.error.clone().unwrap_or_default(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const EXAMPLE: &str = "
+root:
+  selector:
+    - sequence:
+        - condition: inventory_full
+        - action: { move_to: { content_type: bank, code: bank } }
+        - action: deposit_all
+    - sequence:
+        - condition: { hp_below: 50 }
+        - action: rest
+    - sequence:
+        - action: { move_to: { content_type: monster, code: chicken } }
+        - action: fight
+";
+
+    /// Writes `text` to a file named `name` in a directory unique to the
+    /// test process.
+    fn write(name: &str, text: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("artifacts-bt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn parse(yaml: &str) -> BehaviorTree {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn loads_yaml_trees() {
+        let tree = BehaviorTree::load(&write("example.yaml", EXAMPLE)).unwrap();
+
+        let Node::Selector(children) = &tree.root else {
+            panic!("root should be a selector, got {:?}", tree.root);
+        };
+        assert_eq!(children.len(), 3);
+        assert_eq!(
+            children[1],
+            Node::Sequence(vec![
+                Node::Condition(Condition::HpBelow(50.0)),
+                Node::Action(Action::Rest),
+            ])
+        );
+        assert_eq!(
+            children[2],
+            Node::Sequence(vec![
+                Node::Action(Action::MoveTo {
+                    content_type: MapContentType::Monster,
+                    code: "chicken".to_string(),
+                }),
+                Node::Action(Action::Fight),
+            ])
+        );
+    }
+
+    #[test]
+    fn loads_toml_trees() {
+        let path = write(
+            "fight.toml",
+            "[root]\nsequence = [{ action = { craft = { code = \"copper\" } } }]\n",
+        );
+        let tree = BehaviorTree::load(&path).unwrap();
+        assert_eq!(
+            tree.root,
+            Node::Sequence(vec![Node::Action(Action::Craft {
+                code: ItemCode::new("copper"),
+                quantity: 1,
+            })])
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert!(matches!(
+            BehaviorTree::load(&write("tree.json", "{}")),
+            Err(BtError::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_empty_composites() {
+        let tree = parse("root:\n  selector:\n    - action: fight\n    - sequence: []\n");
+        assert!(matches!(
+            tree.validate(),
+            Err(BtError::Invalid { node, .. }) if node == "root/selector[1]"
+        ));
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        let tree = parse(
+            "root:\n  sequence:\n    - condition: { not: { hp_below: 150 } }\n    - action: rest\n",
+        );
+        assert!(matches!(
+            tree.validate(),
+            Err(BtError::Invalid { node, .. }) if node == "root/sequence[0]/not"
+        ));
+    }
+
+    #[test]
+    fn rejects_trees_without_actions() {
+        let tree = parse("root:\n  condition: inventory_full\n");
+        assert!(matches!(tree.validate(), Err(BtError::Invalid { .. })));
+    }
+
+    #[test]
+    fn keeps_the_previous_tree_when_a_reload_fails() {
+        let path = write("reload.yaml", EXAMPLE);
+        let cache = TreeCache::new();
+        let tree = cache.load(&path).unwrap();
+
+        std::fs::write(&path, "root:\n  sequence: []\n").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(cache.load(&path).unwrap(), tree);
+    }
+}
@@ -0,0 +1,566 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    achievements::{self, AchievementPlanner},
+    api::{
+        MAX_PAGE_SIZE,
+        client::ArtifactsClient,
+        events::EventQuery,
+        leaderboard::{AccountLeaderboardQuery, CharacterLeaderboardQuery},
+        maps::MapQuery,
+        monsters::MonsterQuery,
+    },
+    bot::{control::OneOffAction, server::ControlClient, strategy::Strategy},
+    config::{Overrides, TOKEN_ENV},
+    cooldown::ServerClock,
+    models::{
+        character::Character,
+        cooldown::Cooldown,
+        leaderboard::{AccountLeaderboardSort, CharacterLeaderboardSort},
+        map::MapContentType,
+        monster::Monster,
+        types::{CharacterName, ItemCode, Position},
+    },
+};
+
+/// Command line interface for the Artifacts MMO client.
+///
+/// Without a subcommand the TUI is started along with the bots.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Print output as JSON instead of human readable text.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// The config file to use instead of `$XDG_CONFIG_HOME/artifacts/config.toml`.
+    #[arg(long, global = true, env = "ARTIFACTS_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// The account profile from the config file to use.
+    #[arg(long, global = true, env = "ARTIFACTS_PROFILE")]
+    pub profile: Option<String>,
+
+    /// The API token, overriding the profile's token. Without it, the
+    /// profile's token is used, then the `ARTIFACTS_API_TOKEN` environment
+    /// variable.
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+
+    /// Run the bots without the TUI, logging to stdout and the log file.
+    #[arg(long, conflicts_with = "attach")]
+    pub headless: bool,
+
+    /// Start the TUI without bots, showing the events of the bots running
+    /// in another process through its control server.
+    #[arg(long)]
+    pub attach: bool,
+
+    /// The control server to connect to, overriding `bot.control_addr`.
+    #[arg(long, global = true, env = "ARTIFACTS_CONTROL_URL")]
+    pub control_url: Option<String>,
+
+    /// The file to write logs to.
+    #[arg(long, global = true, env = "ARTIFACTS_LOG_PATH")]
+    pub log_path: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// The settings given on the command line or in the environment.
+    pub fn overrides(&self) -> Overrides {
+        Overrides {
+            profile: self.profile.clone(),
+            token: self.token.clone(),
+            env_token: std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty()),
+            log_path: self.log_path.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Show the server status, season and announcements.
+    Status,
+    /// Commands for all of the account's characters.
+    Characters {
+        #[command(subcommand)]
+        command: CharactersCommand,
+    },
+    /// Commands for a single character.
+    Character {
+        #[command(subcommand)]
+        command: CharacterCommand,
+    },
+    /// Move a character to the given coordinates.
+    #[command(allow_negative_numbers = true)]
+    Move { name: CharacterName, x: i32, y: i32 },
+    /// Fight the monster on the character's current map.
+    Fight { name: CharacterName },
+    /// Rest to recover HP.
+    Rest { name: CharacterName },
+    /// List maps, optionally filtered by content.
+    Maps {
+        /// The type of content on the map, e.g. `monster` or `resource`.
+        #[arg(long, value_parser = parse_content_type)]
+        content_type: Option<MapContentType>,
+        /// The code of the content on the map, e.g. `chicken`.
+        #[arg(long)]
+        code: Option<String>,
+    },
+    /// Control the bots of a running process through its control server.
+    Bots {
+        #[command(subcommand)]
+        command: BotsCommand,
+    },
+    /// List monsters, optionally filtered by level.
+    Monsters {
+        #[arg(long)]
+        min_level: Option<u32>,
+        #[arg(long)]
+        max_level: Option<u32>,
+        /// Only list monsters that drop the given item.
+        #[arg(long)]
+        drop: Option<ItemCode>,
+    },
+    /// Show the actions logged by the server, newest first.
+    Logs {
+        /// Only show the logs of this character.
+        name: Option<CharacterName>,
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+    },
+    /// Show an account's achievement progress.
+    Achievements {
+        /// The account to show, defaulting to the authenticated one.
+        account: Option<String>,
+        /// Also list completed achievements.
+        #[arg(long)]
+        all: bool,
+        /// Suggest the strategies that advance the unfinished achievements
+        /// for the given character.
+        #[arg(long, value_name = "CHARACTER")]
+        plan: Option<CharacterName>,
+    },
+    /// Show the leaderboards.
+    Leaderboard {
+        #[command(subcommand)]
+        command: LeaderboardCommand,
+    },
+    /// List the active world events.
+    Events {
+        /// List every event of the season instead of the active ones.
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CharactersCommand {
+    /// List all characters.
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CharacterCommand {
+    /// Show the details of a character.
+    Show { name: CharacterName },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LeaderboardCommand {
+    /// Rank characters by combat or skill level.
+    Characters {
+        /// e.g. `combat`, `mining` or `cooking`.
+        #[arg(long, value_parser = parse_value::<CharacterLeaderboardSort>)]
+        sort: Option<CharacterLeaderboardSort>,
+    },
+    /// Rank accounts by achievement points or gold.
+    Accounts {
+        /// `achievements_points` or `gold`.
+        #[arg(long, value_parser = parse_value::<AccountLeaderboardSort>)]
+        sort: Option<AccountLeaderboardSort>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BotsCommand {
+    /// List the bots and their strategies.
+    List,
+    /// Pause a character's bot after its current action.
+    Pause { name: String },
+    /// Resume a paused character's bot.
+    Resume { name: String },
+    /// Stop a character's bot until the process is restarted.
+    Stop { name: String },
+    /// Swap a character's strategy, e.g. `fight chicken` or
+    /// `gather copper_rocks`.
+    Strategy {
+        name: String,
+        #[arg(required = true, num_args = 1..)]
+        strategy: Vec<String>,
+    },
+    /// Queue a one-off action to perform before resuming the strategy.
+    Queue {
+        name: String,
+        #[command(subcommand)]
+        action: OneOffAction,
+    },
+    /// Stream the bots' events until the server shuts down.
+    Events,
+}
+
+fn parse_content_type(s: &str) -> Result<MapContentType, String> {
+    serde_json::from_value(serde_json::Value::String(s.to_string()))
+        .map_err(|_| format!("Unknown content type: {}", s))
+}
+
+/// Parses a snake_case enum value as it is named in the API.
+fn parse_value<T: DeserializeOwned>(s: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(s.to_string()))
+        .map_err(|_| format!("Unknown value: {}", s))
+}
+
+/// Prints `value` as pretty JSON when `json` is set, otherwise using `human`.
+fn output<T: Serialize>(json: bool, value: &T, human: impl FnOnce(&T)) -> anyhow::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        human(value);
+    }
+    Ok(())
+}
+
+fn print_character(c: &Character) {
+    println!(
+        "{} (Lv. {}) at {} | HP {}/{} | XP {}/{} | {} gold",
+        c.name, c.level, c.position, c.hp, c.max_hp, c.xp, c.max_xp, c.gold
+    );
+}
+
+fn print_cooldown(cooldown: &Cooldown) {
+    println!("Cooldown: {}s", cooldown.total_seconds);
+}
+
+impl Command {
+    /// Runs a one-shot command against the API, or against the control
+    /// server for [`Command::Bots`].
+    pub async fn run(
+        self,
+        api: &ArtifactsClient,
+        control: &ControlClient,
+        json: bool,
+    ) -> anyhow::Result<()> {
+        match self {
+            Command::Status => {
+                let clock = ServerClock::global();
+                let status = clock.sync(api).await?;
+                output(json, &status, |status| {
+                    println!(
+                        "Server {} | {} characters online | max level {}",
+                        status.version, status.characters_online, status.max_level
+                    );
+                    println!(
+                        "Server time: {} (local clock skew: {}ms)",
+                        status.server_time.format("%Y-%m-%d %H:%M:%S"),
+                        clock.offset().num_milliseconds()
+                    );
+                    if let Some(season) = &status.season {
+                        println!(
+                            "Season {}: {} (since {})",
+                            season.number,
+                            season.name,
+                            season.start_date.format("%Y-%m-%d")
+                        );
+                    }
+                    for announcement in &status.announcements {
+                        println!(
+                            "[{}] {}",
+                            announcement.created_at.format("%Y-%m-%d"),
+                            announcement.message
+                        );
+                    }
+                })
+            }
+            Command::Characters {
+                command: CharactersCommand::List,
+            } => {
+                let characters = api.get_characters().await?;
+                output(json, &characters, |characters| {
+                    characters.iter().for_each(print_character)
+                })
+            }
+            Command::Character {
+                command: CharacterCommand::Show { name },
+            } => {
+                let character = api.get_character(&name).await?;
+                output(json, &character, |c| {
+                    print_character(c);
+                    println!(
+                        "Inventory ({}/{} slots used):",
+                        c.inventory.iter().filter(|s| s.quantity > 0).count(),
+                        c.inventory.len()
+                    );
+                    for slot in c.inventory.iter().filter(|s| s.quantity > 0) {
+                        println!("  {} x{}", slot.code, slot.quantity);
+                    }
+                })
+            }
+            Command::Move { name, x, y } => {
+                let data = api.move_character(&name, Position::new(x, y)).await?;
+                output(json, &data, |data| {
+                    println!(
+                        "{} moved to {} {}",
+                        name, data.destination.name, data.destination.position
+                    );
+                    print_cooldown(&data.cooldown);
+                })
+            }
+            Command::Fight { name } => {
+                let data = api.fight(&name).await?;
+                output(json, &data, |data| {
+                    let fight = &data.fight;
+                    println!(
+                        "{}: {:?} in {} turns, +{} XP, +{} gold",
+                        name, fight.result, fight.turns, fight.xp, fight.gold
+                    );
+                    for drop in &fight.drops {
+                        println!("  Dropped {} x{}", drop.code, drop.quantity);
+                    }
+                    print_character(&data.character);
+                    print_cooldown(&data.cooldown);
+                })
+            }
+            Command::Rest { name } => {
+                let data = api.rest(&name).await?;
+                output(json, &data, |data| {
+                    println!("{} restored {} HP", name, data.hp_restored);
+                    print_character(&data.character);
+                    print_cooldown(&data.cooldown);
+                })
+            }
+            Command::Maps { content_type, code } => {
+                let mut query = MapQuery::default().page_size(MAX_PAGE_SIZE);
+                query.content_type = content_type;
+                query.content_code = code;
+
+                let maps = api.get_maps(&query).await?;
+                output(json, &maps, |maps| {
+                    for map in maps {
+                        match &map.content {
+                            Some(content) => println!(
+                                "{} {}: {:?} {}",
+                                map.position, map.name, content.content_type, content.code
+                            ),
+                            None => println!("{} {}", map.position, map.name),
+                        }
+                    }
+                })
+            }
+            Command::Bots { command } => command.run(control, json).await,
+            Command::Monsters {
+                min_level,
+                max_level,
+                drop,
+            } => {
+                let mut query = MonsterQuery::default().page_size(MAX_PAGE_SIZE);
+                query.min_level = min_level;
+                query.max_level = max_level;
+                query.drop = drop;
+
+                let monsters = api.get_monsters(&query).await?;
+                output(json, &monsters, |monsters: &Vec<Monster>| {
+                    for m in monsters {
+                        println!("{} ({}) Lv. {} | HP {}", m.name, m.code, m.level, m.hp);
+                    }
+                })
+            }
+            Command::Logs { name, page } => {
+                let logs = match &name {
+                    Some(name) => api.get_character_logs(name, page).await?,
+                    None => api.get_account_logs(page).await?,
+                };
+                output(json, &logs, |logs| {
+                    for entry in logs {
+                        println!(
+                            "[{}] {}: {}",
+                            entry.created_at.format("%m-%d %H:%M:%S"),
+                            entry.character,
+                            entry.description
+                        );
+                    }
+                })
+            }
+            Command::Achievements { account, all, plan } => {
+                let account = match account {
+                    Some(account) => account,
+                    None => api.get_my_details().await?.username,
+                };
+                let mut progress = achievements::load_progress(api, &account).await?;
+
+                if let Some(name) = plan {
+                    let character = api.get_character(&name).await?;
+                    let planner = AchievementPlanner::load(api).await?;
+                    let steps = planner.plan(&character, &progress);
+                    let plan = steps
+                        .iter()
+                        .map(|step| {
+                            serde_json::json!({
+                                "achievement": step.achievement.achievement.code,
+                                "strategy": step.strategy,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+
+                    return output(json, &plan, |_| {
+                        if steps.is_empty() {
+                            println!("{} cannot advance any unfinished achievement", name);
+                        }
+                        for step in &steps {
+                            println!(
+                                "{}: {} ({}/{}, {} points)",
+                                step.strategy,
+                                step.achievement.achievement.name,
+                                step.achievement.current,
+                                step.achievement.achievement.total,
+                                step.achievement.achievement.points
+                            );
+                        }
+                    });
+                }
+
+                if !all {
+                    progress.retain(|a| !a.is_completed());
+                }
+                output(json, &progress, |progress| {
+                    for a in progress {
+                        println!(
+                            "{} ({}): {}/{} | {} points{}",
+                            a.achievement.name,
+                            a.achievement.code,
+                            a.current,
+                            a.achievement.total,
+                            a.achievement.points,
+                            if a.is_completed() { " | completed" } else { "" }
+                        );
+                    }
+                })
+            }
+            Command::Leaderboard {
+                command: LeaderboardCommand::Characters { sort },
+            } => {
+                let mut query = CharacterLeaderboardQuery::default().page_size(MAX_PAGE_SIZE);
+                query.sort = sort;
+
+                let entries = api.get_characters_leaderboard(&query).await?;
+                output(json, &entries, |entries| {
+                    for e in entries {
+                        println!(
+                            "{:>4}. {} ({}) Lv. {} | {} XP | {} gold",
+                            e.position, e.name, e.account, e.level, e.total_xp, e.gold
+                        );
+                    }
+                })
+            }
+            Command::Leaderboard {
+                command: LeaderboardCommand::Accounts { sort },
+            } => {
+                let mut query = AccountLeaderboardQuery::default().page_size(MAX_PAGE_SIZE);
+                query.sort = sort;
+
+                let entries = api.get_accounts_leaderboard(&query).await?;
+                output(json, &entries, |entries| {
+                    for e in entries {
+                        println!(
+                            "{:>4}. {} | {} points | {} gold",
+                            e.position, e.account, e.achievements_points, e.gold
+                        );
+                    }
+                })
+            }
+            Command::Events { all: true } => {
+                let events = api
+                    .get_events(&EventQuery::default().page_size(MAX_PAGE_SIZE))
+                    .await?;
+                output(json, &events, |events| {
+                    for e in events {
+                        println!(
+                            "{} ({}): {:?} {} | {} min, 1 in {} | {} maps",
+                            e.name,
+                            e.code,
+                            e.content.content_type,
+                            e.content.code,
+                            e.duration,
+                            e.rate,
+                            e.maps.len()
+                        );
+                    }
+                })
+            }
+            Command::Events { all: false } => {
+                let events = api
+                    .get_active_events(&EventQuery::default().page_size(MAX_PAGE_SIZE))
+                    .await?;
+                output(json, &events, |events| {
+                    if events.is_empty() {
+                        println!("No active events");
+                    }
+                    for e in events {
+                        println!(
+                            "{} ({}) at {} until {}",
+                            e.name,
+                            e.code,
+                            e.map.position,
+                            e.expiration.format("%H:%M:%S")
+                        );
+                    }
+                })
+            }
+        }
+    }
+}
+
+impl BotsCommand {
+    async fn run(self, control: &ControlClient, json: bool) -> anyhow::Result<()> {
+        match self {
+            BotsCommand::List => {
+                let bots = control.characters().await?;
+                output(json, &bots, |bots| {
+                    for bot in bots {
+                        println!("{}: {} ({:?})", bot.name, bot.strategy, bot.status);
+                        for action in &bot.queued {
+                            println!("  Queued: {}", action);
+                        }
+                    }
+                })
+            }
+            BotsCommand::Pause { name } => control.pause(&name).await,
+            BotsCommand::Resume { name } => control.resume(&name).await,
+            BotsCommand::Stop { name } => control.stop(&name).await,
+            BotsCommand::Strategy { name, strategy } => {
+                let strategy: Strategy = strategy.join(" ").parse()?;
+                control.set_strategy(&name, &strategy).await
+            }
+            BotsCommand::Queue { name, action } => control.queue(&name, &action).await,
+            BotsCommand::Events => {
+                control
+                    .events(|event| {
+                        if json {
+                            match serde_json::to_string(&event) {
+                                Ok(line) => println!("{}", line),
+                                Err(e) => eprintln!("Failed to serialize event: {}", e),
+                            }
+                        } else {
+                            println!("[{}] {}", event.timestamp.format("%H:%M:%S"), event);
+                        }
+                    })
+                    .await
+            }
+        }
+    }
+}
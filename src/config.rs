@@ -0,0 +1,367 @@
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    bot::{DEFAULT_REST_THRESHOLD, strategy::Strategy},
+    goals::DEFAULT_REPLAN_MARGIN,
+    models::types::CharacterName,
+};
+
+/// The name of the profile used when none is selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The environment variable holding the API token used when neither
+/// `--token` nor the profile give one.
+pub const TOKEN_ENV: &str = "ARTIFACTS_API_TOKEN";
+
+/// The default path of the log file.
+pub const DEFAULT_LOG_PATH: &str = "./artifacts.log";
+
+/// Errors that can occur while loading the configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("Profile '{0}' is not defined in the config file")]
+    UnknownProfile(String),
+    #[error(
+        "No API token configured. Set ARTIFACTS_API_TOKEN, pass --token or add a token to the '{0}' profile"
+    )]
+    MissingToken(String),
+}
+
+/// An Artifacts account.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub token: Option<String>,
+}
+
+/// The bot settings for a single character.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterConfig {
+    pub name: CharacterName,
+    pub strategy: Strategy,
+    /// Overrides [`BotConfig::rest_threshold`] for this character.
+    #[serde(default)]
+    pub rest_threshold: Option<f64>,
+    /// The codes of the world events to join while they are active, in
+    /// order of preference. The character returns to its strategy once the
+    /// event ends.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl CharacterConfig {
+    /// The rest threshold for this character, falling back to the bot-wide
+    /// default.
+    pub fn rest_threshold(&self, bot: &BotConfig) -> f64 {
+        self.rest_threshold.unwrap_or(bot.rest_threshold)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BotConfig {
+    /// The fraction of max HP below which a fighting character rests.
+    pub rest_threshold: f64,
+    /// The relative difference between the estimated and observed XP rate
+    /// that makes a goal strategy plan again.
+    pub replan_margin: f64,
+    /// The SQLite database to record actions in. History is disabled if unset.
+    pub history_path: Option<PathBuf>,
+    /// Where the bots' state is saved so they can be resumed after a restart.
+    /// Defaults to `$XDG_DATA_HOME/artifacts/state.json`.
+    pub state_path: Option<PathBuf>,
+    /// The address of the local control server. The server is disabled if
+    /// unset.
+    pub control_addr: Option<SocketAddr>,
+    /// The address of the Prometheus metrics endpoint. The endpoint is
+    /// disabled if unset.
+    pub metrics_addr: Option<SocketAddr>,
+    /// The characters to run bots for.
+    pub characters: Vec<CharacterConfig>,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            rest_threshold: DEFAULT_REST_THRESHOLD,
+            replan_margin: DEFAULT_REPLAN_MARGIN,
+            history_path: None,
+            state_path: None,
+            control_addr: None,
+            metrics_addr: None,
+            characters: Vec::new(),
+        }
+    }
+}
+
+/// The contents of the TOML config file.
+///
+/// # Example
+/// ```toml
+/// default_profile = "main"
+/// log_path = "/var/log/artifacts.log"
+///
+/// [profiles.main]
+/// token = "..."
+///
+/// [bot]
+/// rest_threshold = 0.5
+/// replan_margin = 0.25
+/// control_addr = "127.0.0.1:7878"
+/// metrics_addr = "127.0.0.1:9464"
+///
+/// [[bot.characters]]
+/// name = "Penguin"
+/// strategy = { type = "fight", monster = "chicken" }
+/// events = ["bandit_camp", "portal_demon"]
+///
+/// [[bot.characters]]
+/// name = "Miner"
+/// strategy = { type = "script", path = "scripts/miner.rhai" }
+///
+/// [[bot.characters]]
+/// name = "Lumberjack"
+/// strategy = { type = "goal", goal = "woodcutting 20" }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+    pub log_path: Option<PathBuf>,
+    pub bot: BotConfig,
+}
+
+/// Settings given on the command line or through environment variables,
+/// which take precedence over the config file, except for
+/// [`env_token`](Overrides::env_token).
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    pub profile: Option<String>,
+    /// The token passed with `--token`.
+    pub token: Option<String>,
+    /// The token in [`TOKEN_ENV`], used only if the profile has none.
+    pub env_token: Option<String>,
+    pub log_path: Option<PathBuf>,
+}
+
+/// The fully resolved settings used to run the application.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub profile: String,
+    pub token: String,
+    pub log_path: PathBuf,
+    pub bot: BotConfig,
+}
+
+impl Config {
+    /// The default location of the config file,
+    /// `$XDG_CONFIG_HOME/artifacts/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("artifacts").join("config.toml"))
+    }
+
+    /// Loads the config file at `path`.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        toml::from_str(&text).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Loads the config file at `path` if given, otherwise at the default
+    /// location. A missing file at the default location yields the default
+    /// config.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Self::load(path),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => Self::load(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// Combines the config file with `overrides`.
+    ///
+    /// The profile is taken from the overrides, then `default_profile`, then
+    /// [`DEFAULT_PROFILE`]. The token is taken from `--token`, then the
+    /// profile, then [`TOKEN_ENV`], so a token left in the environment or a
+    /// `.env` file never shadows the selected profile's.
+    pub fn resolve(self, overrides: Overrides) -> Result<Settings, ConfigError> {
+        let explicit_profile = overrides.profile.is_some() || self.default_profile.is_some();
+        let profile = overrides
+            .profile
+            .or(self.default_profile)
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        let profile_token = match self.profiles.get(&profile) {
+            Some(p) => p.token.clone(),
+            None if explicit_profile && overrides.token.is_none() => {
+                return Err(ConfigError::UnknownProfile(profile));
+            }
+            None => None,
+        };
+
+        let token = overrides
+            .token
+            .or(profile_token)
+            .or(overrides.env_token)
+            .ok_or_else(|| ConfigError::MissingToken(profile.clone()))?;
+
+        let log_path = overrides
+            .log_path
+            .or(self.log_path)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_LOG_PATH));
+
+        Ok(Settings {
+            profile,
+            token,
+            log_path,
+            bot: self.bot,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str(
+            r#"
+            default_profile = "main"
+            log_path = "/var/log/artifacts.log"
+
+            [profiles.main]
+            token = "main-token"
+
+            [profiles.alt]
+            token = "alt-token"
+
+            [profiles.empty]
+
+            [bot]
+            rest_threshold = 0.5
+            replan_margin = 0.1
+
+            [[bot.characters]]
+            name = "Penguin"
+            strategy = { type = "fight", monster = "chicken" }
+
+            [[bot.characters]]
+            name = "Lumberjack"
+            strategy = { type = "goal", goal = "woodcutting 20" }
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn overrides(profile: Option<&str>, token: Option<&str>, env_token: Option<&str>) -> Overrides {
+        Overrides {
+            profile: profile.map(str::to_string),
+            token: token.map(str::to_string),
+            env_token: env_token.map(str::to_string),
+            log_path: None,
+        }
+    }
+
+    #[test]
+    fn uses_the_default_profile() {
+        let settings = config().resolve(Overrides::default()).unwrap();
+        assert_eq!(settings.profile, "main");
+        assert_eq!(settings.token, "main-token");
+        assert_eq!(settings.log_path, PathBuf::from("/var/log/artifacts.log"));
+        assert_eq!(settings.bot.rest_threshold, 0.5);
+        assert_eq!(settings.bot.replan_margin, 0.1);
+        assert_eq!(settings.bot.characters.len(), 2);
+        assert_eq!(
+            settings.bot.characters[1].strategy.to_string(),
+            "goal woodcutting 20"
+        );
+    }
+
+    #[test]
+    fn selected_profile_overrides_the_default() {
+        let settings = config()
+            .resolve(overrides(Some("alt"), None, None))
+            .unwrap();
+        assert_eq!(settings.profile, "alt");
+        assert_eq!(settings.token, "alt-token");
+    }
+
+    #[test]
+    fn token_flag_overrides_the_profile() {
+        let settings = config()
+            .resolve(overrides(None, Some("flag-token"), Some("env-token")))
+            .unwrap();
+        assert_eq!(settings.token, "flag-token");
+    }
+
+    #[test]
+    fn environment_token_does_not_shadow_the_profile() {
+        let settings = config()
+            .resolve(overrides(None, None, Some("env-token")))
+            .unwrap();
+        assert_eq!(settings.token, "main-token");
+
+        let settings = config()
+            .resolve(overrides(Some("empty"), None, Some("env-token")))
+            .unwrap();
+        assert_eq!(settings.token, "env-token");
+    }
+
+    #[test]
+    fn rejects_unknown_profiles() {
+        assert!(matches!(
+            config().resolve(overrides(Some("missing"), None, None)),
+            Err(ConfigError::UnknownProfile(profile)) if profile == "missing"
+        ));
+    }
+
+    #[test]
+    fn requires_a_token() {
+        assert!(matches!(
+            config().resolve(overrides(Some("empty"), None, None)),
+            Err(ConfigError::MissingToken(profile)) if profile == "empty"
+        ));
+        assert!(matches!(
+            Config::default().resolve(Overrides::default()),
+            Err(ConfigError::MissingToken(profile)) if profile == DEFAULT_PROFILE
+        ));
+    }
+
+    #[test]
+    fn works_without_a_config_file() {
+        let settings = Config::default()
+            .resolve(overrides(None, None, Some("env-token")))
+            .unwrap();
+        assert_eq!(settings.profile, DEFAULT_PROFILE);
+        assert_eq!(settings.token, "env-token");
+        assert_eq!(settings.log_path, PathBuf::from(DEFAULT_LOG_PATH));
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    sync::{
+        LazyLock,
+        atomic::{AtomicI64, Ordering},
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use crate::{api::client::ArtifactsClient, models::status::ServerStatus};
+
+/// How often [`ServerClock::keep_synced`] measures the skew again.
+pub const CLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Skews larger than this are logged as warnings, as they are large enough to
+/// cause "character in cooldown" errors.
+const SKEW_WARNING_MS: i64 = 500;
+
+static SERVER_CLOCK: LazyLock<ServerClock> = LazyLock::new(ServerClock::new);
+
+/// The server's time, estimated from the local clock and the skew measured
+/// against the server status endpoint.
+///
+/// Cooldown expirations come from the server, so comparing them against a
+/// local clock that runs behind makes characters act too early.
+#[derive(Debug, Default)]
+pub struct ServerClock {
+    /// How far the server clock is ahead of the local one, in milliseconds.
+    offset_ms: AtomicI64,
+}
+
+impl ServerClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The clock shared by every cooldown calculation in the process.
+    pub fn global() -> &'static ServerClock {
+        &SERVER_CLOCK
+    }
+
+    /// The current time on the server.
+    pub fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.offset()
+    }
+
+    /// How far the server clock is ahead of the local one.
+    pub fn offset(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.offset_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_offset(&self, offset: chrono::Duration) {
+        self.offset_ms
+            .store(offset.num_milliseconds(), Ordering::Relaxed);
+    }
+
+    /// Records a server time read between `sent` and `received` local times,
+    /// assuming it was taken halfway through the request.
+    pub fn observe(
+        &self,
+        server_time: DateTime<Utc>,
+        sent: DateTime<Utc>,
+        received: DateTime<Utc>,
+    ) -> chrono::Duration {
+        let local = sent + (received - sent) / 2;
+        let offset = server_time - local;
+        self.set_offset(offset);
+        offset
+    }
+
+    /// Measures the skew against the server status endpoint, returning the
+    /// status.
+    pub async fn sync(&self, api: &ArtifactsClient) -> anyhow::Result<ServerStatus> {
+        let sent = Utc::now();
+        let status = api.server_status().await?;
+        let offset = self.observe(status.server_time, sent, Utc::now());
+
+        if offset.num_milliseconds().abs() >= SKEW_WARNING_MS {
+            warn!(
+                "Local clock is {}ms {} the server",
+                offset.num_milliseconds().abs(),
+                if offset > chrono::Duration::zero() {
+                    "behind"
+                } else {
+                    "ahead of"
+                }
+            );
+        }
+
+        Ok(status)
+    }
+
+    /// Measures the skew again every `interval`, forever.
+    pub async fn keep_synced(&self, api: ArtifactsClient, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            if let Err(e) = self.sync(&api).await {
+                warn!("Failed to sync the clock with the server: {}", e);
+            }
+        }
+    }
+}
+
+/// The current time on the server, according to [`ServerClock::global`].
+pub fn now() -> DateTime<Utc> {
+    ServerClock::global().now()
+}
+
+/// Returns how long until `expiration` on the server clock, or zero if it has
+/// passed.
+pub fn remaining(expiration: DateTime<Utc>) -> chrono::Duration {
+    (expiration - now()).max(chrono::Duration::zero())
+}
+
+/// Sleeps until the given cooldown expiration time has passed on the server.
+///
+/// Returns immediately if the cooldown has already expired.
+pub async fn sleep_until_expired(cooldown_expiration: DateTime<Utc>) {
+    let duration = remaining(cooldown_expiration);
+    if duration > chrono::Duration::zero() {
+        info!(
+            "Sleeping for {} seconds until cooldown expires",
+            duration.num_seconds()
+        );
+
+        tokio::time::sleep(Duration::from_secs_f64(duration.as_seconds_f64())).await;
+    }
+}
@@ -0,0 +1,786 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use thiserror::Error;
+use tracing::info;
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE, bank::BankItemQuery, client::ArtifactsClient, fetch_all_pages,
+        items::ItemQuery, maps::MapQuery, monsters::MonsterQuery, resources::ResourceQuery,
+    },
+    cooldown,
+    models::{
+        character::Character,
+        item::{Item, SimpleItem},
+        map::{Map, MapContentType},
+        monster::Monster,
+        resource::Resource,
+        skill::Skill,
+        types::{CharacterName, ItemCode, MonsterCode, Position, ResourceCode},
+    },
+};
+
+/// Item quantities keyed by item code.
+pub type Stock = HashMap<ItemCode, u32>;
+
+/// Errors that can occur while building a crafting plan.
+#[derive(Debug, Error)]
+pub enum PlanError {
+    #[error("Unknown item: {0}")]
+    UnknownItem(String),
+    #[error("Item {0} cannot be crafted, gathered or dropped by a monster")]
+    Unobtainable(String),
+    #[error("Recipe for {0} contains a cycle")]
+    RecipeCycle(String),
+}
+
+/// A single step of a [`CraftPlan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanStep {
+    /// Withdraw items from the bank.
+    Withdraw {
+        code: ItemCode,
+        quantity: u32,
+        location: Option<Position>,
+    },
+    /// Gather a resource until `quantity` of `item` has been collected.
+    Gather {
+        resource: ResourceCode,
+        item: ItemCode,
+        quantity: u32,
+        location: Option<Position>,
+    },
+    /// Fight a monster until `quantity` of `item` has dropped.
+    Farm {
+        monster: MonsterCode,
+        item: ItemCode,
+        quantity: u32,
+        location: Option<Position>,
+    },
+    /// Craft an item at the workshop for `skill`.
+    Craft {
+        code: ItemCode,
+        quantity: u32,
+        skill: Skill,
+        location: Option<Position>,
+    },
+}
+
+impl PlanStep {
+    /// The map the character must be on to perform the step, if known.
+    pub fn location(&self) -> Option<Position> {
+        match self {
+            PlanStep::Withdraw { location, .. }
+            | PlanStep::Gather { location, .. }
+            | PlanStep::Farm { location, .. }
+            | PlanStep::Craft { location, .. } => *location,
+        }
+    }
+
+    /// The content of the maps the step can be performed on.
+    fn site(&self) -> (MapContentType, Option<&str>) {
+        match self {
+            PlanStep::Withdraw { .. } => (MapContentType::Bank, None),
+            PlanStep::Gather { resource, .. } => (MapContentType::Resource, Some(resource)),
+            PlanStep::Farm { monster, .. } => (MapContentType::Monster, Some(monster)),
+            PlanStep::Craft { skill, .. } => (MapContentType::Workshop, Some(skill.as_str())),
+        }
+    }
+
+    fn set_location(&mut self, to: Option<Position>) {
+        match self {
+            PlanStep::Withdraw { location, .. }
+            | PlanStep::Gather { location, .. }
+            | PlanStep::Farm { location, .. }
+            | PlanStep::Craft { location, .. } => *location = to,
+        }
+    }
+}
+
+impl std::fmt::Display for PlanStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let at = |location: &Option<Position>| match location {
+            Some(location) => format!(" at {}", location),
+            None => String::new(),
+        };
+
+        match self {
+            PlanStep::Withdraw {
+                code,
+                quantity,
+                location,
+            } => write!(
+                f,
+                "Withdraw {} x{} from the bank{}",
+                code,
+                quantity,
+                at(location)
+            ),
+            PlanStep::Gather {
+                resource,
+                item,
+                quantity,
+                location,
+            } => write!(
+                f,
+                "Gather {} x{} from {}{}",
+                item,
+                quantity,
+                resource,
+                at(location)
+            ),
+            PlanStep::Farm {
+                monster,
+                item,
+                quantity,
+                location,
+            } => write!(
+                f,
+                "Farm {} x{} from {}{}",
+                item,
+                quantity,
+                monster,
+                at(location)
+            ),
+            PlanStep::Craft {
+                code,
+                quantity,
+                skill,
+                location,
+            } => write!(
+                f,
+                "Craft {} x{} ({}){}",
+                code,
+                quantity,
+                skill,
+                at(location)
+            ),
+        }
+    }
+}
+
+/// An ordered list of steps that produces the requested item.
+#[derive(Debug, Clone, Default)]
+pub struct CraftPlan {
+    /// The full bill of materials for the target item, ignoring anything
+    /// already owned. Keyed by item code.
+    pub materials: BTreeMap<ItemCode, u32>,
+    /// The steps to perform, in order.
+    pub steps: Vec<PlanStep>,
+}
+
+impl CraftPlan {
+    /// Executes the plan with the given character, moving between maps as
+    /// needed and waiting for cooldowns between actions.
+    pub async fn execute(
+        &self,
+        api: &ArtifactsClient,
+        name: &CharacterName,
+    ) -> anyhow::Result<Character> {
+        let mut character = api.get_character(name).await?;
+        cooldown::sleep_until_expired(character.cooldown_expiration).await;
+
+        for step in &self.steps {
+            info!(target: "craft", "{}: {}", name, step);
+
+            if let Some(location) = step.location()
+                && character.position != location
+            {
+                let data = api.move_character(name, location).await?;
+                cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                character = data.character;
+            }
+
+            match step {
+                PlanStep::Withdraw { code, quantity, .. } => {
+                    let items = [SimpleItem {
+                        code: code.clone(),
+                        quantity: *quantity,
+                    }];
+                    let data = api.withdraw_items(name, &items).await?;
+                    cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                    character = data.character;
+                }
+                PlanStep::Gather { item, quantity, .. } => {
+                    let target = character.inventory_quantity(item) + quantity;
+                    while character.inventory_quantity(item) < target {
+                        let data = api.gather(name).await?;
+                        cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                        character = data.character;
+                    }
+                }
+                PlanStep::Farm { item, quantity, .. } => {
+                    let target = character.inventory_quantity(item) + quantity;
+                    while character.inventory_quantity(item) < target {
+                        if character.hp * 2 < character.max_hp {
+                            let data = api.rest(name).await?;
+                            cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                            character = data.character;
+                            continue;
+                        }
+
+                        let data = api.fight(name).await?;
+                        cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                        character = data.character;
+                    }
+                }
+                PlanStep::Craft { code, quantity, .. } => {
+                    let data = api.craft(name, code, *quantity).await?;
+                    cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                    character = data.character;
+                }
+            }
+        }
+
+        Ok(character)
+    }
+}
+
+/// Resolves crafting recipes into a [`CraftPlan`].
+#[derive(Debug, Clone, Default)]
+pub struct Planner {
+    items: HashMap<ItemCode, Item>,
+    resources: Vec<Resource>,
+    monsters: Vec<Monster>,
+    maps: Vec<Map>,
+}
+
+impl Planner {
+    pub fn new(
+        items: Vec<Item>,
+        resources: Vec<Resource>,
+        monsters: Vec<Monster>,
+        maps: Vec<Map>,
+    ) -> Self {
+        Self {
+            items: items.into_iter().map(|i| (i.code.clone(), i)).collect(),
+            resources,
+            monsters,
+            maps,
+        }
+    }
+
+    /// Creates a planner from the full item, resource, monster and map
+    /// databases.
+    pub async fn load(api: &ArtifactsClient) -> anyhow::Result<Self> {
+        let items = fetch_all_pages(|page| async move {
+            api.get_items(
+                &ItemQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let resources = fetch_all_pages(|page| async move {
+            api.get_resources(
+                &ResourceQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let monsters = fetch_all_pages(|page| async move {
+            api.get_monsters(
+                &MonsterQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let maps = fetch_all_pages(|page| async move {
+            api.get_maps(
+                &MapQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        Ok(Self::new(items, resources, monsters, maps))
+    }
+
+    pub fn item(&self, code: &ItemCode) -> Option<&Item> {
+        self.items.get(code)
+    }
+
+    /// Builds a plan for the given character, taking its position, its
+    /// inventory and the account's bank into account.
+    pub async fn plan_for(
+        &self,
+        api: &ArtifactsClient,
+        character: &Character,
+        code: &ItemCode,
+        quantity: u32,
+    ) -> anyhow::Result<CraftPlan> {
+        let inventory = character
+            .inventory
+            .iter()
+            .filter(|slot| slot.quantity > 0)
+            .fold(Stock::new(), |mut stock, slot| {
+                *stock.entry(slot.code.clone()).or_default() += slot.quantity;
+                stock
+            });
+
+        let bank = fetch_all_pages(|page| async move {
+            api.get_bank_items(
+                &BankItemQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?
+        .into_iter()
+        .map(|item| (item.code, item.quantity))
+        .collect();
+
+        Ok(self.plan(code, quantity, &inventory, &bank, character.position)?)
+    }
+
+    /// Computes the full bill of materials for `quantity` of the item `code`,
+    /// ignoring anything already owned.
+    pub fn bill_of_materials(
+        &self,
+        code: &ItemCode,
+        quantity: u32,
+    ) -> Result<BTreeMap<ItemCode, u32>, PlanError> {
+        Ok(self
+            .plan(
+                code,
+                quantity,
+                &Stock::new(),
+                &Stock::new(),
+                Position::default(),
+            )?
+            .materials)
+    }
+
+    /// Builds an ordered plan to obtain `quantity` of the item `code`.
+    ///
+    /// Items in `inventory` are used first, then items in `bank`. Anything
+    /// still missing is crafted if there is a recipe, otherwise gathered from
+    /// a resource or farmed from a monster.
+    ///
+    /// The steps are ordered as: bank withdrawals, gathering and farming, and
+    /// finally crafting with every ingredient crafted before the items that
+    /// use it. Each step is performed on the suitable map closest to where
+    /// the previous one left the character, starting `from`.
+    pub fn plan(
+        &self,
+        code: &ItemCode,
+        quantity: u32,
+        inventory: &Stock,
+        bank: &Stock,
+        from: Position,
+    ) -> Result<CraftPlan, PlanError> {
+        let order = self.topological_order(code)?;
+
+        let mut inventory = inventory.clone();
+        let mut bank = bank.clone();
+
+        let mut demand = Stock::from([(code.clone(), quantity)]);
+        let mut raw_demand = Stock::from([(code.clone(), quantity)]);
+
+        let mut withdrawals = Vec::new();
+        let mut collection = Vec::new();
+        let mut crafts = Vec::new();
+        let mut materials = BTreeMap::new();
+
+        // `order` lists every item before its ingredients, so by the time an
+        // item is processed its total demand is known.
+        for code in &order {
+            let item = &self.items[code];
+
+            let raw = raw_demand.get(code).copied().unwrap_or(0);
+            let mut needed = demand.get(code).copied().unwrap_or(0);
+
+            if let Some(craft) = &item.craft {
+                let crafts = raw.div_ceil(craft.quantity);
+                for ingredient in &craft.items {
+                    *raw_demand.entry(ingredient.code.clone()).or_default() +=
+                        crafts * ingredient.quantity;
+                }
+            } else {
+                materials.insert(code.clone(), raw);
+            }
+
+            needed -= take(&mut inventory, code, needed);
+
+            let withdrawn = take(&mut bank, code, needed);
+            if withdrawn > 0 {
+                needed -= withdrawn;
+                withdrawals.push(PlanStep::Withdraw {
+                    code: code.clone(),
+                    quantity: withdrawn,
+                    location: None,
+                });
+            }
+
+            if needed == 0 {
+                continue;
+            }
+
+            if let Some(craft) = &item.craft {
+                let count = needed.div_ceil(craft.quantity);
+                for ingredient in &craft.items {
+                    *demand.entry(ingredient.code.clone()).or_default() +=
+                        count * ingredient.quantity;
+                }
+
+                crafts.push(PlanStep::Craft {
+                    code: code.clone(),
+                    quantity: count,
+                    skill: craft.skill,
+                    location: None,
+                });
+            } else {
+                collection.push(self.collect_step(code, needed)?);
+            }
+        }
+
+        // Crafts were collected parents-first; ingredients must be crafted first.
+        crafts.reverse();
+
+        let mut steps = withdrawals
+            .into_iter()
+            .chain(collection)
+            .chain(crafts)
+            .collect::<Vec<_>>();
+
+        let mut position = from;
+        for step in &mut steps {
+            let (content_type, code) = step.site();
+            let location = self.find_map(content_type, code, position);
+            step.set_location(location);
+            position = location.unwrap_or(position);
+        }
+
+        Ok(CraftPlan { materials, steps })
+    }
+
+    /// Returns the codes of `code` and all of its transitive ingredients such
+    /// that every item appears before any of its ingredients.
+    fn topological_order(&self, code: &ItemCode) -> Result<Vec<ItemCode>, PlanError> {
+        fn visit(
+            planner: &Planner,
+            code: &ItemCode,
+            visiting: &mut HashSet<ItemCode>,
+            visited: &mut HashSet<ItemCode>,
+            order: &mut Vec<ItemCode>,
+        ) -> Result<(), PlanError> {
+            if visited.contains(code) {
+                return Ok(());
+            }
+            if !visiting.insert(code.clone()) {
+                return Err(PlanError::RecipeCycle(code.to_string()));
+            }
+
+            let item = planner
+                .items
+                .get(code)
+                .ok_or_else(|| PlanError::UnknownItem(code.to_string()))?;
+
+            if let Some(craft) = &item.craft {
+                for ingredient in &craft.items {
+                    visit(planner, &ingredient.code, visiting, visited, order)?;
+                }
+            }
+
+            visiting.remove(code);
+            visited.insert(code.clone());
+            order.push(code.clone());
+            Ok(())
+        }
+
+        let mut order = Vec::new();
+        visit(
+            self,
+            code,
+            &mut HashSet::new(),
+            &mut HashSet::new(),
+            &mut order,
+        )?;
+
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Picks a resource or monster to obtain a raw material from, preferring
+    /// resources and, among those, the one with the best drop rate.
+    fn collect_step(&self, code: &ItemCode, quantity: u32) -> Result<PlanStep, PlanError> {
+        // Drop rates are expressed as "1 in `rate`", so lower is better.
+        let resource = self
+            .resources
+            .iter()
+            .filter_map(|r| {
+                r.drops
+                    .iter()
+                    .find(|d| d.code == *code)
+                    .map(|d| (r, d.rate))
+            })
+            .min_by_key(|(r, rate)| (*rate, r.level));
+
+        if let Some((resource, _)) = resource {
+            return Ok(PlanStep::Gather {
+                resource: resource.code.clone(),
+                item: code.clone(),
+                quantity,
+                location: None,
+            });
+        }
+
+        let monster = self
+            .monsters
+            .iter()
+            .filter_map(|m| {
+                m.drops
+                    .iter()
+                    .find(|d| d.code == *code)
+                    .map(|d| (m, d.rate))
+            })
+            .min_by_key(|(m, rate)| (m.level, *rate));
+
+        if let Some((monster, _)) = monster {
+            return Ok(PlanStep::Farm {
+                monster: monster.code.clone(),
+                item: code.clone(),
+                quantity,
+                location: None,
+            });
+        }
+
+        Err(PlanError::Unobtainable(code.to_string()))
+    }
+
+    /// Finds the map with the given content closest to `from`.
+    fn find_map(
+        &self,
+        content_type: MapContentType,
+        code: Option<&str>,
+        from: Position,
+    ) -> Option<Position> {
+        from.closest(
+            self.maps
+                .iter()
+                .filter(|map| {
+                    map.content.as_ref().is_some_and(|content| {
+                        content.content_type == content_type
+                            && code.is_none_or(|code| content.code == code)
+                    })
+                })
+                .map(|map| map.position),
+        )
+    }
+}
+
+/// Removes up to `quantity` of `code` from `stock`, returning the amount taken.
+fn take(stock: &mut Stock, code: &str, quantity: u32) -> u32 {
+    let Some(available) = stock.get_mut(code) else {
+        return 0;
+    };
+
+    let taken = (*available).min(quantity);
+    *available -= taken;
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn item(code: &str) -> Item {
+        serde_json::from_value(json!({
+            "name": code,
+            "code": code,
+            "level": 1,
+            "type": "resource",
+            "subtype": "",
+        }))
+        .unwrap()
+    }
+
+    /// An item crafted one at a time by `skill` from `items`.
+    fn crafted(code: &str, skill: &str, items: &[(&str, u32)]) -> Item {
+        let mut item = item(code);
+        item.craft = Some(
+            serde_json::from_value(json!({
+                "skill": skill,
+                "level": 1,
+                "quantity": 1,
+                "items": items
+                    .iter()
+                    .map(|(code, quantity)| json!({ "code": code, "quantity": quantity }))
+                    .collect::<Vec<_>>(),
+            }))
+            .unwrap(),
+        );
+        item
+    }
+
+    fn map(x: i32, y: i32, content: Option<(&str, &str)>) -> Map {
+        let mut map = json!({ "name": "", "skin": "", "x": x, "y": y });
+        if let Some((content_type, code)) = content {
+            map["content_type"] = json!(content_type);
+            map["code"] = json!(code);
+        }
+        serde_json::from_value(map).unwrap()
+    }
+
+    /// A copper dagger is crafted from copper bars, which are smelted from
+    /// copper ore mined at copper rocks.
+    fn planner() -> Planner {
+        let items = vec![
+            item("copper_ore"),
+            crafted("copper", "mining", &[("copper_ore", 10)]),
+            crafted("copper_dagger", "weaponcrafting", &[("copper", 6)]),
+        ];
+        let resources = vec![
+            serde_json::from_value(json!({
+                "name": "Copper Rocks",
+                "code": "copper_rocks",
+                "skill": "mining",
+                "level": 1,
+                "drops": [{ "code": "copper_ore", "rate": 1, "min_quantity": 1, "max_quantity": 1 }],
+            }))
+            .unwrap(),
+        ];
+        let maps = vec![
+            map(0, 0, None),
+            map(2, 0, Some(("resource", "copper_rocks"))),
+            map(10, 10, Some(("resource", "copper_rocks"))),
+            map(1, 5, Some(("workshop", "mining"))),
+            map(2, 1, Some(("workshop", "weaponcrafting"))),
+            map(4, 1, Some(("bank", "bank"))),
+        ];
+        Planner::new(items, resources, Vec::new(), maps)
+    }
+
+    #[test]
+    fn orders_items_before_their_ingredients() {
+        let order = planner()
+            .topological_order(&ItemCode::new("copper_dagger"))
+            .unwrap();
+        assert_eq!(order, ["copper_dagger", "copper", "copper_ore"]);
+    }
+
+    #[test]
+    fn detects_recipe_cycles() {
+        let planner = Planner::new(
+            vec![
+                crafted("a", "mining", &[("b", 1)]),
+                crafted("b", "mining", &[("a", 1)]),
+            ],
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        );
+        assert!(matches!(
+            planner.topological_order(&ItemCode::new("a")),
+            Err(PlanError::RecipeCycle(_))
+        ));
+    }
+
+    #[test]
+    fn computes_the_bill_of_materials() {
+        let materials = planner()
+            .bill_of_materials(&ItemCode::new("copper_dagger"), 2)
+            .unwrap();
+        assert_eq!(
+            materials,
+            BTreeMap::from([(ItemCode::new("copper_ore"), 120)])
+        );
+    }
+
+    #[test]
+    fn plans_from_scratch_at_the_closest_maps() {
+        let plan = planner()
+            .plan(
+                &ItemCode::new("copper_dagger"),
+                1,
+                &Stock::new(),
+                &Stock::new(),
+                Position::new(0, 0),
+            )
+            .unwrap();
+
+        assert_eq!(
+            plan.steps,
+            vec![
+                PlanStep::Gather {
+                    resource: ResourceCode::new("copper_rocks"),
+                    item: ItemCode::new("copper_ore"),
+                    quantity: 60,
+                    location: Some(Position::new(2, 0)),
+                },
+                PlanStep::Craft {
+                    code: ItemCode::new("copper"),
+                    quantity: 6,
+                    skill: Skill::Mining,
+                    location: Some(Position::new(1, 5)),
+                },
+                PlanStep::Craft {
+                    code: ItemCode::new("copper_dagger"),
+                    quantity: 1,
+                    skill: Skill::Weaponcrafting,
+                    location: Some(Position::new(2, 1)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_the_inventory_then_the_bank() {
+        let inventory = Stock::from([(ItemCode::new("copper"), 2)]);
+        let bank = Stock::from([(ItemCode::new("copper"), 3)]);
+        let plan = planner()
+            .plan(
+                &ItemCode::new("copper_dagger"),
+                1,
+                &inventory,
+                &bank,
+                Position::new(0, 0),
+            )
+            .unwrap();
+
+        assert_eq!(
+            plan.steps[..2],
+            [
+                PlanStep::Withdraw {
+                    code: ItemCode::new("copper"),
+                    quantity: 3,
+                    location: Some(Position::new(4, 1)),
+                },
+                PlanStep::Gather {
+                    resource: ResourceCode::new("copper_rocks"),
+                    item: ItemCode::new("copper_ore"),
+                    quantity: 10,
+                    location: Some(Position::new(2, 0)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unobtainable_items() {
+        let planner = Planner::new(vec![item("gem")], Vec::new(), Vec::new(), Vec::new());
+        assert!(matches!(
+            planner.plan(
+                &ItemCode::new("gem"),
+                1,
+                &Stock::new(),
+                &Stock::new(),
+                Position::default()
+            ),
+            Err(PlanError::Unobtainable(_))
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    api::{MAX_PAGE_SIZE, client::ArtifactsClient, events::EventQuery, fetch_all_pages},
+    cooldown,
+    models::event::ActiveEvent,
+};
+
+/// How often the active events are fetched by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The number of notices buffered for slow subscribers.
+const NOTICE_CAPACITY: usize = 32;
+
+/// A change in the active events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "event", rename_all = "snake_case")]
+pub enum EventNotice {
+    Started(ActiveEvent),
+    Ended(ActiveEvent),
+}
+
+impl std::fmt::Display for EventNotice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (verb, event) = match self {
+            EventNotice::Started(event) => ("started", event),
+            EventNotice::Ended(event) => ("ended", event),
+        };
+        write!(f, "Event {} {} at {}", event.name, verb, event.map.position)
+    }
+}
+
+/// Shared handle to the active events, kept up to date by
+/// [`EventWatcher::run`].
+#[derive(Debug, Clone)]
+pub struct EventWatcher {
+    active: Arc<Mutex<Vec<ActiveEvent>>>,
+    notices: broadcast::Sender<EventNotice>,
+}
+
+impl Default for EventWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventWatcher {
+    pub fn new() -> Self {
+        let (notices, _) = broadcast::channel(NOTICE_CAPACITY);
+
+        Self {
+            active: Arc::new(Mutex::new(Vec::new())),
+            notices,
+        }
+    }
+
+    /// The events active at the last poll that have not expired since.
+    pub fn active(&self) -> Vec<ActiveEvent> {
+        let now = cooldown::now();
+        self.active
+            .lock()
+            .map(|active| {
+                active
+                    .iter()
+                    .filter(|e| e.expiration > now)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the first active event whose code is in `codes`, in the order
+    /// of `codes`.
+    pub fn find(&self, codes: &[String]) -> Option<ActiveEvent> {
+        let active = self.active();
+        codes
+            .iter()
+            .find_map(|code| active.iter().find(|e| &e.code == code).cloned())
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EventNotice> {
+        self.notices.subscribe()
+    }
+
+    /// Replaces the active events, publishing and returning a notice for
+    /// every event that started or ended since the last update.
+    pub fn update(&self, events: Vec<ActiveEvent>) -> Vec<EventNotice> {
+        let Ok(mut active) = self.active.lock() else {
+            return Vec::new();
+        };
+
+        let same =
+            |a: &ActiveEvent, b: &ActiveEvent| a.code == b.code && a.location() == b.location();
+        let mut notices = active
+            .iter()
+            .filter(|old| !events.iter().any(|new| same(old, new)))
+            .cloned()
+            .map(EventNotice::Ended)
+            .collect::<Vec<_>>();
+        notices.extend(
+            events
+                .iter()
+                .filter(|new| !active.iter().any(|old| same(old, new)))
+                .cloned()
+                .map(EventNotice::Started),
+        );
+        *active = events;
+        drop(active);
+
+        for notice in &notices {
+            info!(target: "events", "{}", notice);
+            // Sending only fails when nobody is subscribed.
+            _ = self.notices.send(notice.clone());
+        }
+
+        notices
+    }
+
+    /// Fetches the active events every `interval` until cancelled.
+    pub async fn run(&self, api: ArtifactsClient, interval: Duration, cancel: CancellationToken) {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = cancel.cancelled() => break,
+            }
+
+            let api = &api;
+            let result = fetch_all_pages(|page| async move {
+                api.get_active_events(
+                    &EventQuery::default()
+                        .page_number(page)
+                        .page_size(MAX_PAGE_SIZE),
+                )
+                .await
+            })
+            .await;
+
+            match result {
+                Ok(events) => {
+                    self.update(events);
+                }
+                Err(e) => warn!(target: "events", "Failed to fetch active events: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn event(code: &str, x: i32, y: i32) -> ActiveEvent {
+        serde_json::from_value(json!({
+            "name": code,
+            "code": code,
+            "map": { "name": "Forest", "skin": "forest_1", "x": x, "y": y },
+            "previous_map": null,
+            "duration": 60,
+            "expiration": "2099-01-01T00:00:00Z",
+            "created_at": "2024-01-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn notices_started_and_ended_events() {
+        let watcher = EventWatcher::new();
+        let mut notices = watcher.subscribe();
+
+        assert_eq!(
+            watcher.update(vec![event("bandit_camp", 1, 2)]),
+            vec![EventNotice::Started(event("bandit_camp", 1, 2))]
+        );
+        assert_eq!(
+            notices.try_recv().unwrap(),
+            EventNotice::Started(event("bandit_camp", 1, 2))
+        );
+
+        // Still active events are not noticed again.
+        assert_eq!(
+            watcher.update(vec![event("bandit_camp", 1, 2), event("portal", 3, 4)]),
+            vec![EventNotice::Started(event("portal", 3, 4))]
+        );
+
+        // An event moving to another map ends and starts again.
+        assert_eq!(
+            watcher.update(vec![event("portal", 5, 6)]),
+            vec![
+                EventNotice::Ended(event("bandit_camp", 1, 2)),
+                EventNotice::Ended(event("portal", 3, 4)),
+                EventNotice::Started(event("portal", 5, 6)),
+            ]
+        );
+        assert_eq!(watcher.active(), vec![event("portal", 5, 6)]);
+        assert_eq!(
+            watcher.find(&["bandit_camp".to_string(), "portal".to_string()]),
+            Some(event("portal", 5, 6))
+        );
+    }
+}
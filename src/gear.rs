@@ -0,0 +1,369 @@
+use std::collections::{BTreeMap, HashMap};
+
+use tracing::info;
+
+use crate::{
+    api::client::ArtifactsClient,
+    cooldown,
+    models::{
+        character::Character,
+        equipment::EquipmentSlot,
+        item::{Item, SimpleItem},
+        monster::Monster,
+        types::{CharacterName, ItemCode, Position},
+    },
+    simulator::{self, CombatStats, SimulationResult},
+};
+
+/// The number of fights simulated to evaluate a loadout.
+const SIMULATION_ITERATIONS: u32 = 200;
+
+/// The maximum number of improvement passes over all slots.
+const MAX_PASSES: usize = 5;
+
+/// A change to a single equipment slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadoutChange {
+    pub slot: EquipmentSlot,
+    /// The item currently in the slot, which has to be unequipped first.
+    pub unequip: Option<ItemCode>,
+    /// The item to equip in the slot.
+    pub equip: Option<ItemCode>,
+}
+
+/// The best loadout found by [`optimize_loadout`].
+#[derive(Debug, Clone)]
+pub struct Loadout {
+    /// The item in each slot after applying the changes.
+    pub equipment: BTreeMap<EquipmentSlot, Option<ItemCode>>,
+    /// The simulated outcome with the current equipment.
+    pub current: SimulationResult,
+    /// The simulated outcome with the optimized equipment.
+    pub optimized: SimulationResult,
+    /// The changes required to go from the current to the optimized
+    /// equipment, in slot order.
+    pub changes: Vec<LoadoutChange>,
+}
+
+impl Loadout {
+    /// Applies the changes with the given character, unequipping every
+    /// changed slot before equipping the new items.
+    ///
+    /// Items that are not in the character's inventory are withdrawn from the
+    /// bank, moving to `bank_location` first if it is given.
+    pub async fn execute(
+        &self,
+        api: &ArtifactsClient,
+        name: &CharacterName,
+        bank_location: Option<Position>,
+    ) -> anyhow::Result<Character> {
+        let mut character = api.get_character(name).await?;
+        cooldown::sleep_until_expired(character.cooldown_expiration).await;
+
+        let mut withdraw: HashMap<&ItemCode, u32> = HashMap::new();
+        for code in self.changes.iter().filter_map(|c| c.equip.as_ref()) {
+            *withdraw.entry(code).or_default() += 1;
+        }
+        // Items that are unequipped during the swap are available to equip too.
+        withdraw.retain(|code, quantity| {
+            let freed = self
+                .changes
+                .iter()
+                .filter(|c| c.unequip.as_ref() == Some(*code))
+                .count() as u32;
+
+            *quantity = quantity.saturating_sub(character.inventory_quantity(code) + freed);
+            *quantity > 0
+        });
+
+        if !withdraw.is_empty() {
+            if let Some(location) = bank_location
+                && character.position != location
+            {
+                let data = api.move_character(name, location).await?;
+                cooldown::sleep_until_expired(data.cooldown.expiration).await;
+            }
+
+            let items = withdraw
+                .into_iter()
+                .map(|(code, quantity)| SimpleItem {
+                    code: code.clone(),
+                    quantity,
+                })
+                .collect::<Vec<_>>();
+
+            let data = api.withdraw_items(name, &items).await?;
+            cooldown::sleep_until_expired(data.cooldown.expiration).await;
+            character = data.character;
+        }
+
+        // Everything is unequipped before anything is equipped, so an item
+        // moving between slots, e.g. a ring, is free when it is equipped.
+        for change in self.changes.iter().filter(|c| c.unequip.is_some()) {
+            info!(target: "gear", "{}: unequipping {}", name, change.slot);
+            let data = api.unequip(name, change.slot).await?;
+            cooldown::sleep_until_expired(data.cooldown.expiration).await;
+            character = data.character;
+        }
+
+        for change in &self.changes {
+            if let Some(code) = &change.equip {
+                info!(target: "gear", "{}: equipping {} in {}", name, code, change.slot);
+                let data = api.equip(name, code, change.slot).await?;
+                cooldown::sleep_until_expired(data.cooldown.expiration).await;
+                character = data.character;
+            }
+        }
+
+        Ok(character)
+    }
+}
+
+/// Searches equipment combinations for the loadout that maximizes the win
+/// rate against `monster`, then minimizes the HP lost.
+///
+/// `available_items` lists the equippable items the character has access to
+/// (inventory and bank) along with the quantity owned, not counting items that
+/// are already equipped. Items that are currently equipped should also be
+/// included (with a quantity of `0` if none are spare), otherwise their slot
+/// is left unchanged.
+///
+/// Slots are optimized one at a time, repeating until no slot can be improved.
+pub fn optimize_loadout(
+    character: &Character,
+    monster: &Monster,
+    available_items: &[(Item, u32)],
+) -> Loadout {
+    let items: HashMap<&str, &Item> = available_items
+        .iter()
+        .map(|(item, _)| (item.code.as_str(), item))
+        .collect();
+
+    let mut owned: HashMap<&str, u32> = available_items
+        .iter()
+        .map(|(item, quantity)| (item.code.as_str(), *quantity))
+        .collect();
+
+    let current: BTreeMap<EquipmentSlot, Option<ItemCode>> = EquipmentSlot::ALL
+        .into_iter()
+        .map(|slot| (slot, character.equipped(slot).cloned()))
+        .collect();
+
+    // Stats without any of the equipment we are able to change. Fights are
+    // simulated at full health.
+    let mut base = CombatStats::from(character);
+    base.hp = base.max_hp;
+
+    let mut locked = Vec::new();
+    for (slot, code) in &current {
+        let Some(code) = code else { continue };
+
+        match items.get(code.as_str()) {
+            Some(item) => {
+                base.apply_effects(&item.effects, -1);
+                *owned.entry(code.as_str()).or_default() += 1;
+            }
+            None => locked.push(*slot),
+        }
+    }
+
+    let monster_stats = CombatStats::from(monster);
+    let evaluate = |equipment: &BTreeMap<EquipmentSlot, Option<ItemCode>>| {
+        let mut stats = base.clone();
+        for (slot, code) in equipment {
+            if !locked.contains(slot)
+                && let Some(item) = code.as_deref().and_then(|c| items.get(c))
+            {
+                stats.apply_effects(&item.effects, 1);
+            }
+        }
+        simulator::simulate(&stats, &monster_stats, SIMULATION_ITERATIONS)
+    };
+
+    let current_result = evaluate(&current);
+    let mut best = current.clone();
+    let mut best_result = current_result;
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for slot in EquipmentSlot::ALL {
+            if locked.contains(&slot) {
+                continue;
+            }
+
+            let candidates = available_items
+                .iter()
+                .map(|(item, _)| item)
+                .filter(|item| item.item_type == slot.item_type() && item.level <= character.level)
+                .map(|item| Some(item.code.clone()))
+                .chain(std::iter::once(None));
+
+            for candidate in candidates {
+                if best[&slot] == candidate {
+                    continue;
+                }
+
+                let mut equipment = best.clone();
+                equipment.insert(slot, candidate);
+
+                if !is_affordable(&equipment, &locked, &owned) {
+                    continue;
+                }
+
+                let result = evaluate(&equipment);
+                if is_better(&result, &best_result) {
+                    best = equipment;
+                    best_result = result;
+                    improved = true;
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    let changes = EquipmentSlot::ALL
+        .into_iter()
+        .filter(|slot| current[slot] != best[slot])
+        .map(|slot| LoadoutChange {
+            slot,
+            unequip: current[&slot].clone(),
+            equip: best[&slot].clone(),
+        })
+        .collect();
+
+    Loadout {
+        equipment: best,
+        current: current_result,
+        optimized: best_result,
+        changes,
+    }
+}
+
+/// Whether enough copies are owned to equip every item in the unlocked slots
+/// of `equipment`.
+fn is_affordable(
+    equipment: &BTreeMap<EquipmentSlot, Option<ItemCode>>,
+    locked: &[EquipmentSlot],
+    owned: &HashMap<&str, u32>,
+) -> bool {
+    let mut needed: HashMap<&str, u32> = HashMap::new();
+    for (slot, code) in equipment {
+        if let Some(code) = code
+            && !locked.contains(slot)
+        {
+            *needed.entry(code.as_str()).or_default() += 1;
+        }
+    }
+
+    needed
+        .into_iter()
+        .all(|(code, quantity)| owned.get(code).copied().unwrap_or(0) >= quantity)
+}
+
+/// Compares results by win rate, then by HP lost.
+fn is_better(a: &SimulationResult, b: &SimulationResult) -> bool {
+    const EPSILON: f64 = 1e-9;
+
+    if (a.win_rate - b.win_rate).abs() > EPSILON {
+        return a.win_rate > b.win_rate;
+    }
+
+    a.average_hp_lost + EPSILON < b.average_hp_lost
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::character::fixtures;
+
+    fn item(code: &str, item_type: &str, attack_fire: i32) -> Item {
+        serde_json::from_value(json!({
+            "name": code,
+            "code": code,
+            "level": 1,
+            "type": item_type,
+            "subtype": "",
+            "effects": [{ "code": "attack_fire", "value": attack_fire }],
+        }))
+        .unwrap()
+    }
+
+    /// A level 1 character with 6 fire attack of its own, wielding a
+    /// wooden stick.
+    fn character() -> Character {
+        fixtures::character(json!({
+            "attack_fire": 10,
+            "weapon_slot": "wooden_stick",
+        }))
+    }
+
+    /// A monster that kills the character in 5 hits.
+    fn monster() -> Monster {
+        serde_json::from_value(json!({
+            "name": "Chicken",
+            "code": "chicken",
+            "level": 1,
+            "hp": 60,
+            "attack_water": 20,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn equips_the_items_that_win_the_fight() {
+        let available = [
+            (item("wooden_stick", "weapon", 4), 0),
+            (item("copper_dagger", "weapon", 20), 1),
+            (item("fire_ring", "ring", 5), 1),
+        ];
+        let loadout = optimize_loadout(&character(), &monster(), &available);
+
+        assert_eq!(loadout.current.win_rate, 0.0);
+        assert_eq!(loadout.optimized.win_rate, 1.0);
+        assert_eq!(
+            loadout.changes,
+            vec![
+                LoadoutChange {
+                    slot: EquipmentSlot::Weapon,
+                    unequip: Some(ItemCode::new("wooden_stick")),
+                    equip: Some(ItemCode::new("copper_dagger")),
+                },
+                LoadoutChange {
+                    slot: EquipmentSlot::Ring1,
+                    unequip: None,
+                    equip: Some(ItemCode::new("fire_ring")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_items_it_does_not_know() {
+        let available = [(item("copper_dagger", "weapon", 20), 1)];
+        let loadout = optimize_loadout(&character(), &monster(), &available);
+
+        assert!(loadout.changes.is_empty());
+        assert_eq!(
+            loadout.equipment[&EquipmentSlot::Weapon],
+            Some(ItemCode::new("wooden_stick"))
+        );
+    }
+
+    #[test]
+    fn does_not_equip_more_copies_than_owned() {
+        let equipment = BTreeMap::from([
+            (EquipmentSlot::Ring1, Some(ItemCode::new("fire_ring"))),
+            (EquipmentSlot::Ring2, Some(ItemCode::new("fire_ring"))),
+        ]);
+        let owned = HashMap::from([("fire_ring", 1)]);
+
+        assert!(!is_affordable(&equipment, &[], &owned));
+        assert!(is_affordable(&equipment, &[EquipmentSlot::Ring2], &owned));
+    }
+}
@@ -0,0 +1,679 @@
+use std::{collections::HashMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::info;
+
+use crate::{
+    api::{
+        MAX_PAGE_SIZE, client::ArtifactsClient, fetch_all_pages, monsters::MonsterQuery,
+        resources::ResourceQuery,
+    },
+    bot::{
+        BotContext,
+        strategy::{StepAction, StepOutcome, Strategy},
+    },
+    models::{
+        character::Character, monster::Monster, resource::Resource, skill::Skill,
+        types::CharacterName,
+    },
+    simulator::{self, CombatStats},
+};
+
+/// The default relative difference between estimated and observed XP rates
+/// that triggers a replan.
+pub const DEFAULT_REPLAN_MARGIN: f64 = 0.25;
+
+/// The minimum win rate for a monster to be considered for levelling.
+pub(crate) const MIN_WIN_RATE: f64 = 0.9;
+
+/// The cooldown assumed for an activity before any fight or gathering has
+/// been observed.
+const DEFAULT_ACTION_SECONDS: f64 = 30.0;
+
+/// The number of seconds of cooldown that must be observed in a phase before
+/// its XP rate is compared with the estimate.
+const MIN_SAMPLE_SECONDS: u32 = 300;
+
+/// A level to reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Goal {
+    CharacterLevel(u32),
+    SkillLevel { skill: Skill, level: u32 },
+}
+
+impl Goal {
+    pub fn target_level(&self) -> u32 {
+        match self {
+            Goal::CharacterLevel(level) | Goal::SkillLevel { level, .. } => *level,
+        }
+    }
+
+    /// The skill the goal is about, or `None` for the character level.
+    pub fn skill(&self) -> Option<Skill> {
+        match self {
+            Goal::CharacterLevel(_) => None,
+            Goal::SkillLevel { skill, .. } => Some(*skill),
+        }
+    }
+
+    pub fn current_level(&self, character: &Character) -> u32 {
+        match self {
+            Goal::CharacterLevel(_) => character.level,
+            Goal::SkillLevel { skill, .. } => character.skill_level(*skill),
+        }
+    }
+
+    pub fn is_reached(&self, character: &Character) -> bool {
+        self.current_level(character) >= self.target_level()
+    }
+
+    /// The XP an action gained towards the goal, i.e. character XP for a
+    /// character level and the skill's XP for a skill level.
+    pub fn xp(&self, outcome: &StepOutcome) -> u32 {
+        if outcome.skill == self.skill() {
+            outcome.xp
+        } else {
+            0
+        }
+    }
+
+    fn with_level(&self, level: u32) -> Goal {
+        match self {
+            Goal::CharacterLevel(_) => Goal::CharacterLevel(level),
+            Goal::SkillLevel { skill, .. } => Goal::SkillLevel {
+                skill: *skill,
+                level,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Goal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Goal::CharacterLevel(level) => write!(f, "character level {}", level),
+            Goal::SkillLevel { skill, level } => write!(f, "{} {}", skill, level),
+        }
+    }
+}
+
+impl FromStr for Goal {
+    type Err = GoalError;
+
+    /// Parses goals such as `mining 20`, `level 15` or `character level 15`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let invalid = || GoalError::InvalidGoal(s.to_string());
+
+        let (level, subject) = words.split_last().ok_or_else(invalid)?;
+        let level = level.parse().map_err(|_| invalid())?;
+
+        match subject {
+            ["level"] | ["character", "level"] | ["character"] => Ok(Goal::CharacterLevel(level)),
+            [skill] | [skill, "level"] => Ok(Goal::SkillLevel {
+                skill: skill.parse().map_err(|_| invalid())?,
+                level,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for Goal {
+    type Error = GoalError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Goal> for String {
+    fn from(goal: Goal) -> Self {
+        goal.to_string()
+    }
+}
+
+/// Errors that can occur while planning towards a [`Goal`].
+#[derive(Debug, Error)]
+pub enum GoalError {
+    #[error("Invalid goal: {0}")]
+    InvalidGoal(String),
+    /// Crafting skills, which cannot be levelled by gathering or fighting.
+    #[error("Goal {0} is not supported")]
+    Unsupported(Goal),
+    #[error("No activity available to progress towards {0}")]
+    NoActivity(Goal),
+    /// Returned instead of an action once the goal is reached.
+    #[error("Goal {0} is reached")]
+    Reached(Goal),
+}
+
+/// A section of a goal plan: run `strategy` until `until` is reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub strategy: Strategy,
+    pub until: Goal,
+    pub estimated_xp_per_hour: f64,
+}
+
+/// The phase being run towards a goal and what was observed since it
+/// started.
+#[derive(Debug, Clone)]
+struct Progress {
+    goal: Goal,
+    phase: Phase,
+    xp: u64,
+    seconds: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Observation {
+    xp: u64,
+    seconds: u64,
+}
+
+/// Estimates the XP per hour of strategies, refined by observed actions.
+#[derive(Debug, Clone, Default)]
+pub struct XpEstimator {
+    observations: HashMap<Strategy, Observation>,
+    /// The number and total cooldown of every fight and gathering observed,
+    /// whatever the strategy.
+    activities: (u64, u64),
+}
+
+impl XpEstimator {
+    /// Records the XP gained towards `goal` and the cooldown of an action
+    /// performed for `strategy`.
+    ///
+    /// Moves and rests are recorded too, as they are part of the time cost of
+    /// the strategy.
+    pub fn record(&mut self, strategy: &Strategy, goal: Goal, outcome: &StepOutcome) {
+        let observation = self.observations.entry(strategy.clone()).or_default();
+        observation.xp += goal.xp(outcome) as u64;
+        observation.seconds += outcome.cooldown_seconds as u64;
+
+        if matches!(outcome.action, StepAction::Fight | StepAction::Gather) {
+            self.activities.0 += 1;
+            self.activities.1 += outcome.cooldown_seconds as u64;
+        }
+    }
+
+    /// The average cooldown of the fights and gatherings observed so far.
+    fn action_seconds(&self) -> f64 {
+        match self.activities {
+            (0, _) | (_, 0) => DEFAULT_ACTION_SECONDS,
+            (actions, seconds) => seconds as f64 / actions as f64,
+        }
+    }
+
+    /// Estimates the XP per hour of running `strategy`, where the activity has
+    /// level `activity_level` and the character's relevant level is `level`.
+    ///
+    /// Until an action of the strategy with a cooldown has been observed, the
+    /// XP per action is a heuristic that only ranks activities against each
+    /// other, over the average cooldown observed for other activities.
+    pub fn xp_per_hour(&self, strategy: &Strategy, activity_level: u32, level: u32) -> f64 {
+        if let Some(observation) = self.observations.get(strategy)
+            && observation.seconds > 0
+        {
+            return observation.xp as f64 * 3600.0 / observation.seconds as f64;
+        }
+
+        // Activities give more XP the higher their level, and stop giving XP
+        // once they are more than 10 levels below the character.
+        if level > activity_level + 10 {
+            return 0.0;
+        }
+
+        let xp_per_action = 10.0 + activity_level as f64 * 4.0;
+        xp_per_action * 3600.0 / self.action_seconds()
+    }
+}
+
+/// Plans which activities to run to reach a [`Goal`].
+#[derive(Debug, Clone)]
+pub struct GoalPlanner {
+    resources: Vec<Resource>,
+    monsters: Vec<Monster>,
+    estimator: XpEstimator,
+    progress: Option<Progress>,
+    /// The relative difference between the estimated and observed XP rate
+    /// that triggers a replan.
+    pub replan_margin: f64,
+}
+
+impl GoalPlanner {
+    pub fn new(resources: Vec<Resource>, monsters: Vec<Monster>) -> Self {
+        Self {
+            resources,
+            monsters,
+            estimator: XpEstimator::default(),
+            progress: None,
+            replan_margin: DEFAULT_REPLAN_MARGIN,
+        }
+    }
+
+    pub fn replan_margin(mut self, replan_margin: f64) -> Self {
+        self.replan_margin = replan_margin;
+        self
+    }
+
+    /// Creates a planner from the full resource and monster databases.
+    pub async fn load(api: &ArtifactsClient) -> anyhow::Result<Self> {
+        let resources = fetch_all_pages(|page| async move {
+            api.get_resources(
+                &ResourceQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        let monsters = fetch_all_pages(|page| async move {
+            api.get_monsters(
+                &MonsterQuery::default()
+                    .page_number(page)
+                    .page_size(MAX_PAGE_SIZE),
+            )
+            .await
+        })
+        .await?;
+
+        Ok(Self::new(resources, monsters))
+    }
+
+    pub fn estimator(&self) -> &XpEstimator {
+        &self.estimator
+    }
+
+    /// Plans the phases needed for `character` to reach `goal`.
+    ///
+    /// A new phase starts whenever a better activity is unlocked by levelling
+    /// up. Returns an empty plan if the goal is already reached.
+    ///
+    /// Only the character level and gathering skills can be planned for.
+    /// Crafting skills need materials gathered with other skills, so their
+    /// goals fail with [`GoalError::Unsupported`].
+    pub fn plan(&self, character: &Character, goal: Goal) -> Result<Vec<Phase>, GoalError> {
+        if goal.skill().is_some_and(|skill| !skill.is_gathering()) {
+            return Err(GoalError::Unsupported(goal));
+        }
+
+        let activities = self.activities(character, goal);
+        let target = goal.target_level();
+        let mut level = goal.current_level(character);
+
+        let mut unlocks = activities
+            .iter()
+            .map(|(_, activity_level)| *activity_level)
+            .filter(|l| *l > level && *l < target)
+            .collect::<Vec<_>>();
+        unlocks.sort_unstable();
+        unlocks.dedup();
+        unlocks.push(target);
+
+        let mut phases: Vec<Phase> = Vec::new();
+        for until in unlocks {
+            if level >= target {
+                break;
+            }
+
+            let best = activities
+                .iter()
+                .filter(|(_, activity_level)| *activity_level <= level)
+                .map(|(strategy, activity_level)| {
+                    let rate = self.estimator.xp_per_hour(strategy, *activity_level, level);
+                    (strategy, rate)
+                })
+                .filter(|(_, rate)| *rate > 0.0)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            let Some((strategy, rate)) = best else {
+                return Err(GoalError::NoActivity(goal.with_level(level)));
+            };
+
+            match phases.last_mut() {
+                Some(last) if last.strategy == *strategy => last.until = goal.with_level(until),
+                _ => phases.push(Phase {
+                    strategy: strategy.clone(),
+                    until: goal.with_level(until),
+                    estimated_xp_per_hour: rate,
+                }),
+            }
+
+            level = until;
+        }
+
+        Ok(phases)
+    }
+
+    /// Returns the candidate strategies for `goal` along with the level
+    /// required to use them.
+    fn activities(&self, character: &Character, goal: Goal) -> Vec<(Strategy, u32)> {
+        match goal.skill() {
+            Some(skill) => self
+                .resources
+                .iter()
+                .filter(|r| r.skill == skill)
+                .map(|r| {
+                    let strategy = Strategy::Gather {
+                        resource: r.code.clone(),
+                    };
+                    (strategy, r.level)
+                })
+                .collect(),
+            None => {
+                let mut stats = CombatStats::from(character);
+                stats.hp = stats.max_hp;
+
+                self.monsters
+                    .iter()
+                    .filter(|m| {
+                        let result = simulator::simulate(&stats, &CombatStats::from(*m), 100);
+                        result.win_rate >= MIN_WIN_RATE
+                    })
+                    .map(|m| {
+                        let strategy = Strategy::Fight {
+                            monster: m.code.clone(),
+                        };
+                        (strategy, m.level)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Performs the next action towards `goal` for `character`, failing with
+    /// [`GoalError::Reached`] once it is reached.
+    ///
+    /// The plan is recomputed whenever a phase is finished, or when the XP
+    /// rate observed during a phase differs from its estimate by more than
+    /// [`GoalPlanner::replan_margin`].
+    pub async fn step(
+        &mut self,
+        ctx: &BotContext,
+        character: &Character,
+        goal: Goal,
+    ) -> anyhow::Result<StepOutcome> {
+        if goal.is_reached(character) {
+            return Err(GoalError::Reached(goal).into());
+        }
+
+        let mut progress = match self.progress.take() {
+            Some(progress)
+                if progress.goal == goal
+                    && !progress.phase.until.is_reached(character)
+                    && !self.is_off_estimate(&character.name, &progress) =>
+            {
+                progress
+            }
+            _ => self.next_phase(character, goal)?,
+        };
+
+        let result = progress.phase.strategy.step(ctx, character).await;
+        if let Ok(outcome) = &result {
+            self.estimator
+                .record(&progress.phase.strategy, goal, outcome);
+            progress.xp += goal.xp(outcome) as u64;
+            progress.seconds += outcome.cooldown_seconds;
+        }
+        self.progress = Some(progress);
+
+        result
+    }
+
+    /// Plans the next phase towards `goal`.
+    fn next_phase(&self, character: &Character, goal: Goal) -> Result<Progress, GoalError> {
+        let phase = self
+            .plan(character, goal)?
+            .into_iter()
+            .next()
+            .ok_or(GoalError::Reached(goal))?;
+
+        info!(
+            target: "goals",
+            "{}: {} until {} (estimated {:.0} XP/h)",
+            character.name, phase.strategy, phase.until, phase.estimated_xp_per_hour
+        );
+
+        Ok(Progress {
+            goal,
+            phase,
+            xp: 0,
+            seconds: 0,
+        })
+    }
+
+    /// Returns true if the XP rate observed during a phase differs from its
+    /// estimate by more than the replan margin.
+    fn is_off_estimate(&self, name: &CharacterName, progress: &Progress) -> bool {
+        let estimate = progress.phase.estimated_xp_per_hour;
+        if progress.seconds < MIN_SAMPLE_SECONDS || estimate <= 0.0 {
+            return false;
+        }
+
+        let actual = progress.xp as f64 * 3600.0 / progress.seconds as f64;
+        if (actual - estimate).abs() / estimate <= self.replan_margin {
+            return false;
+        }
+
+        info!(
+            target: "goals",
+            "{}: observed {:.0} XP/h for {}, estimated {:.0} XP/h. Replanning.",
+            name, actual, progress.phase.strategy, estimate
+        );
+        true
+    }
+}
+
+/// The planner of a character's [`Strategy::Goal`] strategy, loaded on its
+/// first step.
+#[derive(Debug)]
+pub struct GoalRunner {
+    replan_margin: f64,
+    planner: tokio::sync::Mutex<Option<GoalPlanner>>,
+}
+
+impl Default for GoalRunner {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLAN_MARGIN)
+    }
+}
+
+impl GoalRunner {
+    pub fn new(replan_margin: f64) -> Self {
+        Self {
+            replan_margin,
+            planner: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Performs the next action towards `goal`. See [`GoalPlanner::step`].
+    pub async fn step(
+        &self,
+        ctx: &BotContext,
+        character: &Character,
+        goal: Goal,
+    ) -> anyhow::Result<StepOutcome> {
+        let mut planner = self.planner.lock().await;
+        let planner = match &mut *planner {
+            Some(planner) => planner,
+            None => planner.insert(
+                GoalPlanner::load(&ctx.api)
+                    .await?
+                    .replan_margin(self.replan_margin),
+            ),
+        };
+
+        planner.step(ctx, character, goal).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::{
+        character::fixtures,
+        types::{MonsterCode, ResourceCode},
+    };
+
+    fn character(level: u32, mining_level: u32) -> Character {
+        fixtures::character(json!({
+            "level": level,
+            "mining_level": mining_level,
+            "attack_fire": 20,
+        }))
+    }
+
+    fn resource(code: &str, level: u32) -> Resource {
+        serde_json::from_value(json!({
+            "name": code,
+            "code": code,
+            "skill": "mining",
+            "level": level,
+            "drops": [],
+        }))
+        .unwrap()
+    }
+
+    fn monster(code: &str, level: u32, hp: u32) -> Monster {
+        serde_json::from_value(json!({
+            "name": code,
+            "code": code,
+            "level": level,
+            "hp": hp,
+            "attack_water": 5,
+        }))
+        .unwrap()
+    }
+
+    fn gather(resource: &str) -> Strategy {
+        Strategy::Gather {
+            resource: ResourceCode::new(resource),
+        }
+    }
+
+    #[test]
+    fn parses_goals() {
+        let mining = Goal::SkillLevel {
+            skill: Skill::Mining,
+            level: 20,
+        };
+        assert_eq!("mining 20".parse::<Goal>().unwrap(), mining);
+        assert_eq!("mining level 20".parse::<Goal>().unwrap(), mining);
+        assert_eq!(
+            "level 15".parse::<Goal>().unwrap(),
+            Goal::CharacterLevel(15)
+        );
+        assert_eq!(
+            "character level 15".parse::<Goal>().unwrap(),
+            Goal::CharacterLevel(15)
+        );
+    }
+
+    #[test]
+    fn parses_goal_strategies() {
+        let strategy = "goal mining 20".parse::<Strategy>().unwrap();
+        assert_eq!(
+            strategy,
+            Strategy::Goal {
+                goal: Goal::SkillLevel {
+                    skill: Skill::Mining,
+                    level: 20,
+                },
+            }
+        );
+        assert_eq!(strategy.to_string().parse::<Strategy>().unwrap(), strategy);
+
+        let value = serde_json::to_value(&strategy).unwrap();
+        assert_eq!(value, json!({ "type": "goal", "goal": "mining 20" }));
+        assert_eq!(serde_json::from_value::<Strategy>(value).unwrap(), strategy);
+
+        assert!("goal".parse::<Strategy>().is_err());
+        assert!("goal swimming 10".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_goals() {
+        for goal in ["", "mining", "mining twenty", "swimming 10", "a b c 10"] {
+            assert!(
+                matches!(goal.parse::<Goal>(), Err(GoalError::InvalidGoal(_))),
+                "{:?} should be invalid",
+                goal
+            );
+        }
+    }
+
+    #[test]
+    fn switches_activity_when_a_better_one_unlocks() {
+        let planner = GoalPlanner::new(
+            vec![resource("copper_rocks", 1), resource("iron_rocks", 10)],
+            Vec::new(),
+        );
+        let goal = "mining 15".parse().unwrap();
+        let phases = planner.plan(&character(1, 1), goal).unwrap();
+
+        let phases = phases
+            .into_iter()
+            .map(|p| (p.strategy, p.until))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            vec![
+                (gather("copper_rocks"), goal.with_level(10)),
+                (gather("iron_rocks"), goal),
+            ]
+        );
+    }
+
+    #[test]
+    fn fights_monsters_it_can_beat() {
+        let planner = GoalPlanner::new(
+            Vec::new(),
+            vec![monster("chicken", 1, 40), monster("dragon", 1, 100_000)],
+        );
+        let phases = planner
+            .plan(&character(1, 1), Goal::CharacterLevel(5))
+            .unwrap();
+
+        assert_eq!(phases.len(), 1);
+        assert_eq!(
+            phases[0].strategy,
+            Strategy::Fight {
+                monster: MonsterCode::new("chicken")
+            }
+        );
+    }
+
+    #[test]
+    fn plans_nothing_once_reached() {
+        let planner = GoalPlanner::new(vec![resource("copper_rocks", 1)], Vec::new());
+        let phases = planner
+            .plan(&character(1, 20), "mining 15".parse().unwrap())
+            .unwrap();
+        assert!(phases.is_empty());
+    }
+
+    #[test]
+    fn rejects_crafting_skills() {
+        let planner = GoalPlanner::new(Vec::new(), Vec::new());
+        assert!(matches!(
+            planner.plan(&character(1, 1), "weaponcrafting 5".parse().unwrap()),
+            Err(GoalError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn fails_without_an_activity() {
+        let planner = GoalPlanner::new(vec![resource("iron_rocks", 10)], Vec::new());
+        assert!(matches!(
+            planner.plan(&character(1, 1), "mining 15".parse().unwrap()),
+            Err(GoalError::NoActivity(_))
+        ));
+    }
+}
@@ -0,0 +1,633 @@
+use std::{path::Path, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    api::ArtifactsError,
+    models::{
+        bank::BankItemTransaction,
+        equipment::EquipRequestData,
+        fight::{CharacterFightData, FightResult},
+        grand_exchange::GeOrderTransaction,
+        item::{SimpleItem, UseItemData},
+        log::LogEntry,
+        movement::CharacterMovementData,
+        rest::CharacterRestData,
+        skill::SkillData,
+        types::ItemCode,
+    },
+};
+
+/// Schema migrations, applied in order. The index of the last applied
+/// migration is stored in the database's `user_version`.
+const MIGRATIONS: &[&str] = &[
+    // 1: Initial schema
+    "CREATE TABLE actions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        character TEXT NOT NULL,
+        action TEXT NOT NULL,
+        target TEXT,
+        request TEXT NOT NULL,
+        response TEXT,
+        xp INTEGER NOT NULL DEFAULT 0,
+        gold INTEGER NOT NULL DEFAULT 0,
+        result TEXT,
+        cooldown_seconds INTEGER NOT NULL DEFAULT 0,
+        error TEXT
+    );
+    CREATE TABLE drops (
+        action_id INTEGER NOT NULL REFERENCES actions(id) ON DELETE CASCADE,
+        code TEXT NOT NULL,
+        quantity INTEGER NOT NULL
+    );",
+    // 2: Indices for the query helpers
+    "CREATE INDEX actions_character_timestamp ON actions (character, timestamp);
+    CREATE INDEX drops_action_id ON drops (action_id);",
+    // 3: Actions imported from the server logs, and how far each character's
+    // logs have been reconciled
+    "ALTER TABLE actions ADD COLUMN imported INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE reconciled (
+        character TEXT PRIMARY KEY,
+        until TEXT NOT NULL
+    );",
+];
+
+/// Errors that can occur while reading or writing the history store.
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Serialize error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("History store lock was poisoned")]
+    Poisoned,
+}
+
+/// A single action performed by a character, as stored in the history.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionRecord {
+    pub timestamp: DateTime<Utc>,
+    pub character: String,
+    /// The kind of action, e.g. `fight` or `gathering`.
+    pub action: String,
+    /// The monster, resource or item the action targeted, if any.
+    pub target: Option<String>,
+    pub request: serde_json::Value,
+    pub response: Option<serde_json::Value>,
+    pub xp: u32,
+    pub gold: u32,
+    pub result: Option<FightResult>,
+    pub drops: Vec<SimpleItem>,
+    pub cooldown_seconds: u32,
+    pub error: Option<String>,
+    /// Whether the action was imported from the server logs rather than
+    /// recorded by a bot.
+    pub imported: bool,
+}
+
+impl ActionRecord {
+    /// Creates a record for an action, timestamped now, so it is created
+    /// right before the request is sent. The response fields are filled in
+    /// with one of the `with_*` methods.
+    pub fn new(character: &str, action: &str, request: serde_json::Value) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            character: character.to_string(),
+            action: action.to_string(),
+            target: None,
+            request,
+            response: None,
+            xp: 0,
+            gold: 0,
+            result: None,
+            drops: Vec::new(),
+            cooldown_seconds: 0,
+            error: None,
+            imported: false,
+        }
+    }
+
+    /// Creates a record for an action found in the server logs but not in
+    /// the history.
+    pub fn from_log(entry: &LogEntry) -> Self {
+        let action = entry
+            .log_type
+            .action()
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                serde_json::to_value(entry.log_type)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default()
+            });
+        let details = entry.details();
+
+        let mut record = Self {
+            timestamp: entry.created_at,
+            response: Some(entry.content.clone()),
+            cooldown_seconds: entry.cooldown,
+            imported: true,
+            ..Self::new(&entry.character, &action, serde_json::json!({}))
+        };
+
+        if let Some(fight) = details.fight {
+            record.xp = fight.xp;
+            record.gold = fight.gold;
+            record.result = Some(fight.result);
+            record.drops = fight
+                .drops
+                .iter()
+                .map(|d| SimpleItem {
+                    code: d.code.clone(),
+                    quantity: d.quantity,
+                })
+                .collect();
+        }
+        if let Some(details) = details.details {
+            record.xp = details.xp;
+            record.drops = details.items;
+        }
+
+        record
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Records the error of a failed action.
+    pub fn with_error<E: std::fmt::Display>(mut self, error: &ArtifactsError<E>) -> Self {
+        self.error = Some(match error_status(error) {
+            Some(status) => format!("{}: {}", status.as_u16(), error),
+            None => error.to_string(),
+        });
+        self
+    }
+
+    pub fn with_fight(mut self, data: &CharacterFightData) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.xp = data.fight.xp;
+        self.gold = data.fight.gold;
+        self.result = Some(data.fight.result);
+        self.drops = data
+            .fight
+            .drops
+            .iter()
+            .map(|d| SimpleItem {
+                code: d.code.clone(),
+                quantity: d.quantity,
+            })
+            .collect();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    pub fn with_rest(mut self, data: &CharacterRestData) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    pub fn with_movement(mut self, data: &CharacterMovementData) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    /// Records the result of a bank deposit or withdrawal.
+    pub fn with_bank(mut self, data: &BankItemTransaction) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    pub fn with_equip(mut self, data: &EquipRequestData) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    pub fn with_ge_order(mut self, data: &GeOrderTransaction) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    pub fn with_use_item(mut self, data: &UseItemData) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+
+    /// Records the result of a gathering or crafting action.
+    pub fn with_skill(mut self, data: &SkillData) -> Self {
+        self.response = serde_json::to_value(data).ok();
+        self.xp = data.details.xp;
+        self.drops = data.details.items.clone();
+        self.cooldown_seconds = data.cooldown.total_seconds;
+        self
+    }
+}
+
+/// Drops collected from a monster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MonsterDrops {
+    pub monster: String,
+    pub code: String,
+    pub quantity: u64,
+    /// The number of fights won against the monster.
+    pub fights: u64,
+}
+
+/// A persistent record of every action performed by the bots, stored in
+/// SQLite.
+#[derive(Debug)]
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryStore {
+    /// Opens (or creates) the history database at `path` and applies any
+    /// pending migrations.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, HistoryError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a history store that only lives in memory.
+    pub fn open_in_memory() -> Result<Self, HistoryError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, HistoryError> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>, HistoryError> {
+        self.conn.lock().map_err(|_| HistoryError::Poisoned)
+    }
+
+    /// Stores a record, returning its ID.
+    pub fn record(&self, record: &ActionRecord) -> Result<i64, HistoryError> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO actions (timestamp, character, action, target, request, response,
+                xp, gold, result, cooldown_seconds, error, imported)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.timestamp,
+                record.character,
+                record.action,
+                record.target,
+                record.request,
+                record.response,
+                record.xp,
+                record.gold,
+                record.result.map(|r| match r {
+                    FightResult::Win => "win",
+                    FightResult::Loss => "loss",
+                }),
+                record.cooldown_seconds,
+                record.error,
+                record.imported,
+            ],
+        )?;
+
+        let id = tx.last_insert_rowid();
+        for drop in &record.drops {
+            tx.execute(
+                "INSERT INTO drops (action_id, code, quantity) VALUES (?1, ?2, ?3)",
+                params![id, drop.code.as_str(), drop.quantity],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// The average XP gained per hour by `character` since `since`, measured
+    /// from the first recorded action in that period.
+    pub fn xp_per_hour(&self, character: &str, since: DateTime<Utc>) -> Result<f64, HistoryError> {
+        let conn = self.conn()?;
+        let row: Option<(Option<i64>, Option<DateTime<Utc>>)> = conn
+            .query_row(
+                "SELECT SUM(xp), MIN(timestamp) FROM actions
+                 WHERE character = ?1 AND timestamp >= ?2",
+                params![character, since],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let Some((Some(xp), Some(first))) = row else {
+            return Ok(0.0);
+        };
+
+        let hours = (Utc::now() - first).as_seconds_f64() / 3600.0;
+        Ok(if hours > 0.0 { xp as f64 / hours } else { 0.0 })
+    }
+
+    /// The total drops per monster, optionally restricted to a character.
+    pub fn drops_per_monster(
+        &self,
+        character: Option<&str>,
+    ) -> Result<Vec<MonsterDrops>, HistoryError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT a.target, d.code, SUM(d.quantity),
+                (SELECT COUNT(*) FROM actions f
+                 WHERE f.action = 'fight' AND f.target = a.target AND f.result = 'win'
+                   AND (?1 IS NULL OR f.character = ?1))
+             FROM drops d JOIN actions a ON a.id = d.action_id
+             WHERE a.action = 'fight' AND a.target IS NOT NULL
+               AND (?1 IS NULL OR a.character = ?1)
+             GROUP BY a.target, d.code
+             ORDER BY a.target, d.code",
+        )?;
+
+        let drops = stmt
+            .query_map(params![character], |row| {
+                Ok(MonsterDrops {
+                    monster: row.get(0)?,
+                    code: row.get(1)?,
+                    quantity: row.get::<_, i64>(2)? as u64,
+                    fights: row.get::<_, i64>(3)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(drops)
+    }
+
+    /// The number of fights lost by `character` since `since`.
+    pub fn loss_count(&self, character: &str, since: DateTime<Utc>) -> Result<u64, HistoryError> {
+        let conn = self.conn()?;
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM actions
+             WHERE character = ?1 AND timestamp >= ?2 AND action = 'fight' AND result = 'loss'",
+            params![character, since],
+            |row| row.get::<_, i64>(0),
+        )?;
+
+        Ok(count as u64)
+    }
+
+    /// The most recent records for `character`, newest first.
+    pub fn recent(&self, character: &str, limit: u32) -> Result<Vec<ActionRecord>, HistoryError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, character, action, target, request, response, xp, gold,
+                result, cooldown_seconds, error, imported
+             FROM actions WHERE character = ?1 ORDER BY timestamp DESC, id DESC LIMIT ?2",
+        )?;
+
+        let rows = stmt
+            .query_map(params![character, limit], |row| {
+                let result: Option<String> = row.get(9)?;
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    ActionRecord {
+                        timestamp: row.get(1)?,
+                        character: row.get(2)?,
+                        action: row.get(3)?,
+                        target: row.get(4)?,
+                        request: row.get(5)?,
+                        response: row.get(6)?,
+                        xp: row.get(7)?,
+                        gold: row.get(8)?,
+                        result: result.as_deref().and_then(|r| match r {
+                            "win" => Some(FightResult::Win),
+                            "loss" => Some(FightResult::Loss),
+                            _ => None,
+                        }),
+                        drops: Vec::new(),
+                        cooldown_seconds: row.get(10)?,
+                        error: row.get(11)?,
+                        imported: row.get(12)?,
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut drops = conn.prepare("SELECT code, quantity FROM drops WHERE action_id = ?1")?;
+        rows.into_iter()
+            .map(|(id, mut record)| {
+                record.drops = drops
+                    .query_map(params![id], |row| {
+                        Ok(SimpleItem {
+                            code: ItemCode::new(row.get::<_, String>(0)?),
+                            quantity: row.get(1)?,
+                        })
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(record)
+            })
+            .collect()
+    }
+
+    /// The time and kind of every successful action of `character` between
+    /// `from` and `to`, oldest first.
+    pub fn actions_between(
+        &self,
+        character: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, String)>, HistoryError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, action FROM actions
+             WHERE character = ?1 AND timestamp >= ?2 AND timestamp <= ?3 AND error IS NULL
+             ORDER BY timestamp",
+        )?;
+
+        let actions = stmt
+            .query_map(params![character, from, to], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(actions)
+    }
+
+    /// The time of the newest log entry of `character` that has been
+    /// reconciled, if any.
+    pub fn reconciled_until(&self, character: &str) -> Result<Option<DateTime<Utc>>, HistoryError> {
+        let conn = self.conn()?;
+        let until = conn
+            .query_row(
+                "SELECT until FROM reconciled WHERE character = ?1",
+                params![character],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(until)
+    }
+
+    pub fn set_reconciled_until(
+        &self,
+        character: &str,
+        until: DateTime<Utc>,
+    ) -> Result<(), HistoryError> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO reconciled (character, until) VALUES (?1, ?2)
+             ON CONFLICT (character) DO UPDATE SET until = excluded.until",
+            params![character, until],
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Applies every migration that has not yet been applied.
+fn migrate(conn: &mut Connection) -> Result<(), HistoryError> {
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i as i64 + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Returns the HTTP status of a failed action, if the API returned one.
+pub fn error_status<E>(error: &ArtifactsError<E>) -> Option<StatusCode> {
+    match error {
+        ArtifactsError::ApiError { status, .. }
+        | ArtifactsError::UnknownStatusError { status, .. } => Some(*status),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn fight(
+        character: &str,
+        monster: &str,
+        result: FightResult,
+        drops: &[(&str, u32)],
+    ) -> ActionRecord {
+        ActionRecord {
+            xp: 50,
+            gold: 5,
+            result: Some(result),
+            drops: drops
+                .iter()
+                .map(|(code, quantity)| SimpleItem {
+                    code: ItemCode::new(*code),
+                    quantity: *quantity,
+                })
+                .collect(),
+            cooldown_seconds: 20,
+            ..ActionRecord::new(character, "fight", serde_json::json!({})).target(monster)
+        }
+    }
+
+    #[test]
+    fn migrates_to_the_latest_version() {
+        let store = HistoryStore::open_in_memory().unwrap();
+        let mut conn = store.conn().unwrap();
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len() as i64);
+
+        // Migrating an up to date database is a no-op.
+        migrate(&mut conn).unwrap();
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let store = HistoryStore::open_in_memory().unwrap();
+        let record = fight(
+            "hero",
+            "chicken",
+            FightResult::Win,
+            &[("feather", 2), ("egg", 1)],
+        );
+        store.record(&record).unwrap();
+        store
+            .record(&ActionRecord::new("other", "rest", serde_json::json!({})))
+            .unwrap();
+
+        let recent = store.recent("hero", 10).unwrap();
+        assert_eq!(recent.len(), 1);
+
+        let stored = &recent[0];
+        assert_eq!(stored.timestamp, record.timestamp);
+        assert_eq!(stored.action, "fight");
+        assert_eq!(stored.target.as_deref(), Some("chicken"));
+        assert_eq!(stored.result, Some(FightResult::Win));
+        assert_eq!(stored.drops, record.drops);
+        assert_eq!(stored.cooldown_seconds, 20);
+        assert!(!stored.imported);
+    }
+
+    #[test]
+    fn aggregates_drops_and_losses() {
+        let store = HistoryStore::open_in_memory().unwrap();
+        let since = Utc::now() - Duration::minutes(1);
+        store
+            .record(&fight(
+                "hero",
+                "chicken",
+                FightResult::Win,
+                &[("feather", 2)],
+            ))
+            .unwrap();
+        store
+            .record(&fight(
+                "hero",
+                "chicken",
+                FightResult::Win,
+                &[("feather", 1)],
+            ))
+            .unwrap();
+        store
+            .record(&fight("hero", "chicken", FightResult::Loss, &[]))
+            .unwrap();
+
+        assert_eq!(
+            store.drops_per_monster(Some("hero")).unwrap(),
+            vec![MonsterDrops {
+                monster: "chicken".to_string(),
+                code: "feather".to_string(),
+                quantity: 3,
+                fights: 2,
+            }]
+        );
+        assert!(store.drops_per_monster(Some("other")).unwrap().is_empty());
+        assert_eq!(store.loss_count("hero", since).unwrap(), 1);
+    }
+
+    #[test]
+    fn tracks_reconciliation() {
+        let store = HistoryStore::open_in_memory().unwrap();
+        assert_eq!(store.reconciled_until("hero").unwrap(), None);
+
+        let first = Utc::now() - Duration::hours(1);
+        let second = Utc::now();
+        store.set_reconciled_until("hero", first).unwrap();
+        store.set_reconciled_until("hero", second).unwrap();
+        assert_eq!(store.reconciled_until("hero").unwrap(), Some(second));
+    }
+}
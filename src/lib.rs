@@ -1,9 +1,41 @@
+/// Module containing the planner that advances unfinished achievements.
+pub mod achievements;
 /// Module containing actions for the Artifacts API.
 pub mod actions;
 /// Module containing the Artifacts API client and code for making requests.
 pub mod api;
 /// Module containing the main application logic and UI components.
 pub mod app;
+/// Module containing the bot strategies that drive characters.
+pub mod bot;
+/// Module containing behavior-tree strategies loaded from YAML or TOML.
+pub mod bt;
+/// Module containing the command line interface and its one-shot commands.
+pub mod cli;
+/// Module containing runtime configuration loading.
+pub mod config;
+/// Module containing helpers for waiting on character cooldowns.
+pub mod cooldown;
+/// Module containing the crafting recipe resolver and materials planner.
+pub mod craft;
+/// Module containing the poller that tracks active world events.
+pub mod events;
+/// Module containing the equipment loadout optimizer.
+pub mod gear;
+/// Module containing the goal-oriented planner for levelling skills.
+pub mod goals;
+/// Module containing the persistent SQLite action history.
+pub mod history;
 pub mod macros;
+/// Module containing the Prometheus metrics of the client and the bots.
+pub mod metrics;
 /// Module containing models for the Artifacts API.
 pub mod models;
+/// Module containing the reconciler between the server logs and the action history.
+pub mod reconcile;
+/// Module containing a local fight simulator.
+pub mod simulator;
+/// Module containing rolling session statistics aggregated from bot events.
+pub mod stats;
+/// Module containing the shared character state and the events it broadcasts.
+pub mod world;
@@ -3,6 +3,7 @@
 ///
 /// # Example
 /// ```
+/// # use artifacts::make_error;
 /// make_error!(CharacterRestError,
 ///    486 => ActionAlreadyInProgressForCharacter
 ///        => "Action already in progress for character",
@@ -1,196 +1,203 @@
 #![allow(dead_code)]
 
-use dotenvy_macro::dotenv;
+use clap::Parser;
 use std::{
+    path::Path,
     sync::{Arc, Mutex},
-    time::Duration,
 };
+use tokio::sync::broadcast::error::RecvError;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info, level_filters::LevelFilter};
+use tracing::{error, info, level_filters::LevelFilter, warn};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
 use tui_logger::TuiTracingSubscriberLayer;
 
 use artifacts::{
-    actions::{FightAction, GetCharactersAction, RestAction},
-    api::{Action, ParamatarisedAction, client::ArtifactsClient, maps::MapQuery},
-    app::{self, App},
-    models,
+    api::client::ArtifactsClient,
+    app::{self, App, command::BotHandle},
+    bot::{
+        control::BotController,
+        orchestrator::{Orchestrator, PersistedState},
+        server::{self, ControlClient, DEFAULT_CONTROL_ADDR},
+    },
+    cli::Cli,
+    config::{BotConfig, Config},
+    cooldown::{CLOCK_SYNC_INTERVAL, ServerClock},
+    metrics,
+    stats::SessionStats,
+    world::{FALLBACK_POLL_INTERVAL, WorldState},
 };
 
-async fn periodically_update_characters(
-    api: ArtifactsClient,
+/// Shows the characters of `world` in the character widget whenever they
+/// change.
+async fn follow_characters(
+    world: WorldState,
     character_widget: Arc<Mutex<app::CharacterWidgetState>>,
 ) {
-    let mut interval = tokio::time::interval(Duration::from_secs(3));
+    let mut characters = world.watch();
 
     loop {
-        match GetCharactersAction.execute(&api).await {
-            Ok(chars) => {
-                if let Ok(mut state) = character_widget.lock() {
-                    state.characters = chars;
-                } else {
-                    error!("Failed to lock character widget state");
-                    return;
-                }
-            }
-            Err(e) => {
-                error!("Failed to fetch characters: {}", e);
-            }
+        let latest = characters.borrow_and_update().clone();
+        if let Ok(mut state) = character_widget.lock() {
+            state.characters = latest;
+        } else {
+            error!("Failed to lock character widget state");
+            return;
         }
 
-        interval.tick().await;
+        if characters.changed().await.is_err() {
+            return;
+        }
     }
 }
 
-async fn sleep_until_cooldown_expired(cooldown_expiration: chrono::DateTime<chrono::Utc>) {
-    let now = chrono::Utc::now();
-    if cooldown_expiration > now {
-        let duration = cooldown_expiration - now;
-        info!(
-            target: "fight-loop", "Sleeping for {} seconds until cooldown expires",
-            duration.num_seconds()
-        );
-
-        tokio::time::sleep(Duration::from_secs_f64(duration.as_seconds_f64())).await;
+async fn load_map(api: ArtifactsClient, map_widget: Arc<Mutex<app::map::MapWidgetState>>) {
+    match app::map::MapWidgetState::load(&api).await {
+        Ok(map) => {
+            if let Ok(mut state) = map_widget.lock() {
+                *state = map;
+            }
+        }
+        Err(e) => error!("Failed to load the world map: {}", e),
     }
 }
 
-async fn move_to_chicken(
-    api: ArtifactsClient,
-    character: &models::character::Character,
-) -> Option<models::character::Character> {
-    let Ok(chicken) = api.get_monster("chicken").await else {
-        error!(target: "fight-loop", "Failed to fetch monster 'chicken'");
-        return None;
-    };
-
-    let Ok(maps) = api
-        .get_maps(
-            &MapQuery::default()
-                .content_type(models::map::MapContentType::Monster)
-                .content_code(chicken.code.clone()),
-        )
-        .await
-    else {
-        error!(target: "fight-loop", "Failed to fetch maps for monster 'chicken'");
-        return None;
-    };
-
-    let Some(map) = maps.first() else {
-        error!(target: "fight-loop", "No maps found for monster 'chicken'");
-        return None;
+/// Resolves once SIGINT or SIGTERM is received.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
     };
 
-    let (x, y) = (map.x, map.y);
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
 
-    if (character.x, character.y) != (x, y) {
-        info!(target: "fight-loop", "Character {} is not at the correct location. Moving to ({}, {}).", character.name, x, y);
-        match api.move_character(&character.name, x, y).await {
-            Ok(data) => {
-                info!(target: "fight-loop", "Character {} moved to ({}, {}).", character.name, x, y);
-                return Some(data.character);
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
             }
             Err(e) => {
-                error!(target: "fight-loop", "Failed to move character {}: {}", character.name, e);
-                return None;
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
             }
         }
-    }
-
-    Some(character.clone())
-}
-
-async fn character_fight_loop(api: ArtifactsClient, name: String) {
-    info!(target: "fight-loop", "Starting fight loop for character: {}", name);
-
-    let mut character = match api.get_character(&name).await {
-        Ok(c) => c,
-        Err(e) => {
-            error!(target: "fight-loop", "failed to fetch character {}: {}", name, e);
-            return;
-        }
     };
 
-    if character.cooldown_expiration > chrono::Utc::now() {
-        info!(target: "fight-loop", "Character {} is on cooldown. Waiting for cooldown to expire.", name);
-        sleep_until_cooldown_expired(character.cooldown_expiration).await;
-    }
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    if character.hp < character.max_hp {
-        info!(target: "fight-loop", "Character {} needs to rest before starting the fight loop.", name);
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
 
-        match RestAction.execute(&api, &character).await {
-            Ok(rest_data) => {
-                info!(
-                    "Rest result for {}: Recovered {} HP",
-                    name, rest_data.hp_restored
-                );
-                character = rest_data.character;
+/// Spawns the bots, along with the control server and the metrics endpoint
+/// if they are configured, and returns the controller used to steer them.
+fn spawn_bots(
+    client: ArtifactsClient,
+    bot: BotConfig,
+    world: WorldState,
+    token: CancellationToken,
+) -> (tokio::task::JoinHandle<()>, BotController) {
+    let state_path = bot.state_path.clone().or_else(PersistedState::default_path);
+    let control_addr = bot.control_addr;
+    let metrics_addr = bot.metrics_addr;
+
+    let mut orchestrator = Orchestrator::new(client, bot, token.clone()).world(world);
+    if let Some(path) = state_path {
+        orchestrator = orchestrator.state_path(path);
+    }
 
-                sleep_until_cooldown_expired(rest_data.cooldown.expiration).await;
-            }
-            Err(e) => {
-                error!("Failed to rest character {}: {}", name, e);
-                return;
+    let controller = orchestrator.controller();
+    if let Some(addr) = control_addr {
+        let (controller, token) = (controller.clone(), token.clone());
+        tokio::spawn(async move {
+            if let Err(e) = server::serve(controller, addr, token).await {
+                error!("Control server on {} failed: {}", addr, e);
             }
-        }
+        });
     }
 
-    #[allow(unused_assignments)]
-    let mut lost_hp = 0;
-
-    if let Some(updated_character) = move_to_chicken(api.clone(), &character).await {
-        character = updated_character;
-    } else {
-        error!(target: "fight-loop", "Failed to move character {} to chicken location.", name);
-        return;
+    if let Some(addr) = metrics_addr {
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(addr, token).await {
+                error!("Metrics endpoint on {} failed: {}", addr, e);
+            }
+        });
     }
 
-    loop {
-        if character.cooldown_expiration > chrono::Utc::now() {
-            info!(target: "fight-loop", "Character {} is on cooldown. Waiting for cooldown to expire.", name);
-            sleep_until_cooldown_expired(character.cooldown_expiration).await;
-        }
+    (tokio::spawn(orchestrator.run()), controller)
+}
 
-        info!(target: "fight-loop", "Preparing to fight with character: {}", name);
-        match FightAction.execute(&api, &character).await {
-            Ok(fight_data) => {
-                info!("Fight result for {}: {:?}", name, fight_data.fight.result);
-                lost_hp = character.hp.saturating_sub(fight_data.character.hp);
-                character = fight_data.character;
+/// Feeds the events of the bots running in this process into `stats`.
+async fn collect_stats(controller: BotController, stats: Arc<Mutex<SessionStats>>) {
+    let mut events = controller.subscribe();
 
-                sleep_until_cooldown_expired(fight_data.cooldown.expiration).await;
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Ok(mut stats) = stats.lock() {
+                    stats.record(&event);
+                }
             }
-            Err(e) => {
-                error!("Failed to fight with character {}: {}", name, e);
-                return;
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Session stats skipped {} bot events", skipped);
             }
+            Err(RecvError::Closed) => return,
         }
+    }
+}
 
-        if lost_hp < character.hp {
-            info!(target: "fight-loop", "Character {} can continue fighting. Skipping rest.", name);
-            continue;
-        }
+/// Logs the events of bots running in another process, and feeds them into
+/// `stats`, until cancelled.
+async fn follow_remote_bots(control: ControlClient, stats: Arc<Mutex<SessionStats>>) {
+    info!("Attaching to control server at {}", control.base_url());
 
-        info!(target: "fight-loop", "Character {} needs to rest. Lost HP: {} ({}/{})",
-            name, lost_hp, character.hp, character.max_hp);
+    let result = control
+        .events(|event| {
+            info!(target: "bot", "{}", event);
+            if let Ok(mut stats) = stats.lock() {
+                stats.record(&event);
+            }
+        })
+        .await;
 
-        match RestAction.execute(&api, &character).await {
-            Ok(rest_data) => {
-                info!("Rest result for {}: {:?}", name, rest_data);
-                character = rest_data.character;
+    match result {
+        Ok(()) => info!("Control server closed the event stream"),
+        Err(e) => error!("Lost connection to control server: {}", e),
+    }
+}
 
-                sleep_until_cooldown_expired(rest_data.cooldown.expiration).await;
-            }
-            Err(e) => {
-                error!("Failed to rest character {}: {}", name, e);
-                return;
-            }
+async fn run_headless(client: ArtifactsClient, bot: BotConfig) -> anyhow::Result<()> {
+    let token = CancellationToken::new();
+    let world = WorldState::new();
+    let (mut bots, _) = spawn_bots(client.clone(), bot, world.clone(), token.clone());
+
+    // Picks up changes made from elsewhere, and the state of characters
+    // whose action failed.
+    let poll_token = token.clone();
+    let poller =
+        tokio::spawn(async move { world.poll(client, FALLBACK_POLL_INTERVAL, poll_token).await });
+
+    tokio::select! {
+        _ = shutdown_signal() => {
+            info!("Shutting down. Waiting for in-flight actions to finish.");
+            token.cancel();
+            bots.await?;
+        }
+        result = &mut bots => {
+            result?;
+            info!("All bots have stopped");
+            token.cancel();
         }
-
-        info!(target: "fight-loop", "Character {} rested and is ready for the next fight.", name);
     }
+
+    poller.await?;
+    Ok(())
 }
 
 fn set_panic_hook() {
@@ -201,9 +208,21 @@ fn set_panic_hook() {
     }));
 }
 
-fn configure_logging() {
-    let log_path = "./artifacts.log";
-    let log_file = std::fs::File::create(log_path).expect("Failed to create log file");
+/// Where logs are written in addition to the log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogOutput {
+    Tui,
+    Stdout,
+    FileOnly,
+}
+
+fn configure_logging(log_path: &Path, output: LogOutput) {
+    // Appended to, so one-shot subcommands keep the log of a running daemon.
+    let log_file = std::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(log_path)
+        .expect("Failed to open log file");
 
     let file_layer = tracing_subscriber::fmt::layer()
         .with_file(true)
@@ -213,62 +232,137 @@ fn configure_logging() {
         .with_ansi(false)
         .with_filter(EnvFilter::from_default_env().add_directive(LevelFilter::DEBUG.into()));
 
-    tui_logger::init_logger(tui_logger::LevelFilter::Trace).unwrap();
-    tui_logger::set_default_level(tui_logger::LevelFilter::Trace);
-
-    tracing_subscriber::registry()
+    let registry = tracing_subscriber::registry()
         .with(file_layer)
-        .with(ErrorLayer::default())
-        .with(TuiTracingSubscriberLayer)
-        .init();
+        .with(ErrorLayer::default());
+
+    match output {
+        LogOutput::Tui => {
+            tui_logger::init_logger(tui_logger::LevelFilter::Trace).unwrap();
+            tui_logger::set_default_level(tui_logger::LevelFilter::Trace);
+
+            registry.with(TuiTracingSubscriberLayer).init();
+        }
+        LogOutput::Stdout => {
+            let stdout_layer = tracing_subscriber::fmt::layer()
+                .with_target(true)
+                .with_filter(EnvFilter::from_default_env().add_directive(LevelFilter::INFO.into()));
+
+            registry.with(stdout_layer).init();
+        }
+        LogOutput::FileOnly => registry.init(),
+    }
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     color_eyre::install().unwrap();
-    configure_logging();
+    _ = dotenvy::dotenv();
+
+    let cli = Cli::parse();
+    let settings = Config::load_or_default(cli.config.as_deref())?.resolve(cli.overrides())?;
+    let output = match (&cli.command, cli.headless) {
+        (Some(_), _) => LogOutput::FileOnly,
+        (None, true) => LogOutput::Stdout,
+        (None, false) => LogOutput::Tui,
+    };
+    configure_logging(&settings.log_path, output);
 
-    let api_token = dotenv!("ARTIFACTS_API_TOKEN");
-    let client = ArtifactsClient::new(api_token);
+    let client = ArtifactsClient::new(settings.token);
+    let control = match (&cli.control_url, settings.bot.control_addr) {
+        (Some(url), _) => ControlClient::new(url),
+        (None, Some(addr)) => ControlClient::new(&addr.to_string()),
+        (None, None) => ControlClient::new(DEFAULT_CONTROL_ADDR),
+    };
+
+    if let Some(command) = cli.command {
+        return command.run(&client, &control, cli.json).await;
+    }
+
+    // Cooldown expirations are compared against the server clock, so measure
+    // the skew before any bot acts.
+    let clock = ServerClock::global();
+    match clock.sync(&client).await {
+        Ok(status) => {
+            if let Some(season) = &status.season {
+                info!(
+                    "Server {} | Season {}: {}",
+                    status.version, season.number, season.name
+                );
+            }
+            for announcement in &status.announcements {
+                info!("Announcement: {}", announcement.message);
+            }
+        }
+        Err(e) => warn!("Failed to sync the clock with the server: {}", e),
+    }
+    let sync_client = client.clone();
+    tokio::spawn(async move { clock.keep_synced(sync_client, CLOCK_SYNC_INTERVAL).await });
+
+    if cli.headless {
+        return run_headless(client, settings.bot).await;
+    }
 
     let mut terminal = ratatui::init();
     terminal.clear()?;
     terminal.hide_cursor()?;
 
     let token = CancellationToken::new();
-    let app = App::new(client.clone());
+    let world = WorldState::new();
+    let mut app = App::new(client.clone()).world(world.clone());
+    let stats = app.session_stats();
+
+    let bots_handle = if cli.attach {
+        let tok = token.clone();
+        let remote = control.clone();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = follow_remote_bots(remote, stats) => {}
+                _ = tok.cancelled() => {}
+            }
+        });
+        app = app.bots(BotHandle::Remote(control));
+        handle
+    } else {
+        let (handle, controller) =
+            spawn_bots(client.clone(), settings.bot, world.clone(), token.clone());
+        // Stops by itself once the bots have stopped and dropped the
+        // controller's event sender.
+        tokio::spawn(collect_stats(controller.clone(), stats));
+        app = app.bots(BotHandle::Local(controller));
+        handle
+    };
+
+    let poller = world.clone();
+    let (client_clone, tok) = (client.clone(), token.clone());
+    let poll_handle =
+        tokio::spawn(async move { poller.poll(client_clone, FALLBACK_POLL_INTERVAL, tok).await });
 
     let tok = token.clone();
-    let client_clone = client.clone();
-    let char_fight_handle = tokio::spawn(async move {
+    let character_widget_state = app.character_widget_state();
+    let update_char_handle = tokio::spawn(async move {
         tokio::select! {
-            _ = character_fight_loop(client_clone, "Penguin".to_string()) => {
-                info!("Character fight loop completed");
-            }
+            _ = follow_characters(world, character_widget_state) => {}
             _ = tok.cancelled() => {
-                info!("Character fight loop cancelled");
+                info!("Character update loop cancelled");
             }
         }
     });
 
     let tok = token.clone();
     let client_clone = client.clone();
-    let character_widget_state = app.character_widget_state();
-    let update_char_handle = tokio::spawn(async move {
+    let map_widget_state = app.map_widget_state();
+    let map_handle = tokio::spawn(async move {
         tokio::select! {
-            _ = periodically_update_characters(client_clone, character_widget_state) => {
-                info!("Character update loop completed");
-            }
-            _ = tok.cancelled() => {
-                info!("Character update loop cancelled");
-            }
+            _ = load_map(client_clone, map_widget_state) => {}
+            _ = tok.cancelled() => {}
         }
     });
 
     _ = app.run(terminal).await;
 
     token.cancel();
-    _ = tokio::join!(char_fight_handle, update_char_handle);
+    _ = tokio::join!(bots_handle, poll_handle, update_char_handle, map_handle);
 
     ratatui::restore();
     Ok(())
@@ -0,0 +1,502 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{Router, http::header, response::IntoResponse, routing::get};
+use http::Extensions;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next, Result};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use crate::models::fight::{Fight, FightResult};
+
+/// The upper bounds of the request latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// The content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// The latencies of the requests to a single endpoint that ended with a
+/// single status.
+#[derive(Debug, Clone, Default)]
+struct Latencies {
+    /// The number of requests at or below each of [`LATENCY_BUCKETS`].
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Latencies {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// What a single action gained, added to the character's totals by
+/// [`Metrics::record_action`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ActionGains {
+    pub xp: u32,
+    pub gold: u32,
+    pub fight_result: Option<FightResult>,
+}
+
+impl ActionGains {
+    pub fn xp(xp: u32) -> Self {
+        Self {
+            xp,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&Fight> for ActionGains {
+    fn from(fight: &Fight) -> Self {
+        Self {
+            xp: fight.xp,
+            gold: fight.gold,
+            fight_result: Some(fight.result),
+        }
+    }
+}
+
+/// The totals of a single character.
+#[derive(Debug, Clone, Default)]
+struct CharacterTotals {
+    actions: BTreeMap<String, u64>,
+    xp: u64,
+    gold: u64,
+    wins: u64,
+    losses: u64,
+    cooldown_seconds: u64,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    /// Keyed by method, endpoint and status.
+    requests: BTreeMap<(String, String, String), Latencies>,
+    rate_limit_waits: u64,
+    rate_limit_wait_seconds: f64,
+    characters: BTreeMap<String, CharacterTotals>,
+}
+
+/// Counters of the requests sent to the Artifacts API and of the actions
+/// performed through it, by the bots, the TUI or a plan alike, rendered in
+/// the Prometheus text format by [`Metrics::render`].
+///
+/// Cooldown utilization is the rate of `artifacts_cooldown_seconds_total`,
+/// i.e. the fraction of the time a character spent in cooldown.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    registry: Mutex<Registry>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The metrics shared by the client and the bots of the process.
+    pub fn global() -> &'static Metrics {
+        &METRICS
+    }
+
+    /// Records a request to `endpoint` that ended with `status`, or with
+    /// `error` if no response was received.
+    pub fn record_request(&self, method: &str, endpoint: &str, status: &str, latency: Duration) {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry
+                .requests
+                .entry((method.to_string(), endpoint.to_string(), status.to_string()))
+                .or_default()
+                .observe(latency.as_secs_f64());
+        }
+    }
+
+    /// Records a wait before retrying a request that was rate limited.
+    pub fn record_rate_limit_wait(&self, wait: Duration) {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry.rate_limit_waits += 1;
+            registry.rate_limit_wait_seconds += wait.as_secs_f64();
+        }
+    }
+
+    /// Adds an action performed by `character` to its totals. `action` is the
+    /// label of the action, e.g. `fight`.
+    pub fn record_action(
+        &self,
+        character: &str,
+        action: &str,
+        cooldown_seconds: u32,
+        gains: ActionGains,
+    ) {
+        let Ok(mut registry) = self.registry.lock() else {
+            return;
+        };
+        let totals = registry
+            .characters
+            .entry(character.to_string())
+            .or_default();
+
+        *totals.actions.entry(action.to_string()).or_default() += 1;
+        totals.xp += gains.xp as u64;
+        totals.gold += gains.gold as u64;
+        totals.cooldown_seconds += cooldown_seconds as u64;
+
+        match gains.fight_result {
+            Some(FightResult::Win) => totals.wins += 1,
+            Some(FightResult::Loss) => totals.losses += 1,
+            None => {}
+        }
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let Ok(registry) = self.registry.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "artifacts_http_request_duration_seconds",
+            "histogram",
+            "Latency of the requests to the Artifacts API, by endpoint and status.",
+        );
+        for ((method, endpoint, status), latencies) in &registry.requests {
+            let labels = format!(
+                "method=\"{}\",endpoint=\"{}\",status=\"{}\"",
+                escape(method),
+                escape(endpoint),
+                escape(status)
+            );
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(latencies.buckets) {
+                _ = writeln!(
+                    out,
+                    "artifacts_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, count
+                );
+            }
+            _ = writeln!(
+                out,
+                "artifacts_http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, latencies.count
+            );
+            _ = writeln!(
+                out,
+                "artifacts_http_request_duration_seconds_sum{{{}}} {}",
+                labels, latencies.sum
+            );
+            _ = writeln!(
+                out,
+                "artifacts_http_request_duration_seconds_count{{{}}} {}",
+                labels, latencies.count
+            );
+        }
+
+        header(
+            &mut out,
+            "artifacts_rate_limit_waits_total",
+            "counter",
+            "Requests retried after being rate limited.",
+        );
+        _ = writeln!(
+            out,
+            "artifacts_rate_limit_waits_total {}",
+            registry.rate_limit_waits
+        );
+        header(
+            &mut out,
+            "artifacts_rate_limit_wait_seconds_total",
+            "counter",
+            "Time spent waiting to retry rate limited requests.",
+        );
+        _ = writeln!(
+            out,
+            "artifacts_rate_limit_wait_seconds_total {}",
+            registry.rate_limit_wait_seconds
+        );
+
+        header(
+            &mut out,
+            "artifacts_actions_total",
+            "counter",
+            "Actions performed, by character and type.",
+        );
+        for (character, totals) in &registry.characters {
+            for (action, count) in &totals.actions {
+                _ = writeln!(
+                    out,
+                    "artifacts_actions_total{{character=\"{}\",action=\"{}\"}} {}",
+                    escape(character),
+                    escape(action),
+                    count
+                );
+            }
+        }
+
+        let characters = &registry.characters;
+        per_character(
+            &mut out,
+            characters,
+            "artifacts_xp_total",
+            "XP gained by the characters.",
+            |t| t.xp,
+        );
+        per_character(
+            &mut out,
+            characters,
+            "artifacts_gold_total",
+            "Gold gained by the characters.",
+            |t| t.gold,
+        );
+        per_character(
+            &mut out,
+            characters,
+            "artifacts_cooldown_seconds_total",
+            "Time spent in cooldown by the characters.",
+            |t| t.cooldown_seconds,
+        );
+
+        header(
+            &mut out,
+            "artifacts_fights_total",
+            "counter",
+            "Fights fought, by character and result.",
+        );
+        for (character, totals) in &registry.characters {
+            for (result, count) in [("win", totals.wins), ("loss", totals.losses)] {
+                _ = writeln!(
+                    out,
+                    "artifacts_fights_total{{character=\"{}\",result=\"{}\"}} {}",
+                    escape(character),
+                    result,
+                    count
+                );
+            }
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    _ = writeln!(out, "# HELP {} {}", name, help);
+    _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Writes a counter with a single `character` label.
+fn per_character(
+    out: &mut String,
+    characters: &BTreeMap<String, CharacterTotals>,
+    name: &str,
+    help: &str,
+    value: impl Fn(&CharacterTotals) -> u64,
+) {
+    header(out, name, "counter", help);
+    for (character, totals) in characters {
+        _ = writeln!(
+            out,
+            "{}{{character=\"{}\"}} {}",
+            name,
+            escape(character),
+            value(totals)
+        );
+    }
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Replaces the names, codes and coordinates in an API path with
+/// placeholders, so every request to the same endpoint shares its labels.
+pub fn endpoint(path: &str) -> String {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    let templated = match segments.as_slice() {
+        ["my", "logs", _] => vec!["my", "logs", "{name}"],
+        ["my", name, rest @ ..] if !["bank", "characters", "details", "logs"].contains(name) => {
+            [&["my", "{name}"][..], rest].concat()
+        }
+        ["characters", name] if *name != "delete" => vec!["characters", "{name}"],
+        ["accounts", _, rest @ ..] => [&["accounts", "{account}"][..], rest].concat(),
+        ["maps", _, _] => vec!["maps", "{x}", "{y}"],
+        ["grandexchange", "history", _] => vec!["grandexchange", "history", "{code}"],
+        [
+            kind @ ("items" | "monsters" | "resources" | "achievements"),
+            _,
+        ] => {
+            vec![*kind, "{code}"]
+        }
+        _ => segments,
+    };
+
+    format!("/{}", templated.join("/"))
+}
+
+/// Records the latency and status of every request sent to the Artifacts
+/// API, including each retry, into [`Metrics::global`].
+#[derive(Debug, Default)]
+pub struct MetricsMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let method = req.method().to_string();
+        let endpoint = endpoint(req.url().path());
+        let start = Instant::now();
+
+        let result = next.run(req, extensions).await;
+
+        let status = match &result {
+            Ok(resp) => resp.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        Metrics::global().record_request(&method, &endpoint, &status, start.elapsed());
+
+        result
+    }
+}
+
+async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        Metrics::global().render(),
+    )
+}
+
+/// Builds the routes of the metrics endpoint.
+///
+/// - `GET /metrics` renders [`Metrics::global`] for Prometheus to scrape.
+pub fn router() -> Router {
+    Router::new().route("/metrics", get(metrics))
+}
+
+/// Serves the metrics endpoint on `addr` until `cancel` is triggered.
+pub async fn serve(addr: SocketAddr, cancel: CancellationToken) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("Metrics endpoint listening on http://{}/metrics", addr);
+
+    axum::serve(listener, router())
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_endpoints() {
+        for (path, expected) in [
+            ("/my/hero/action/fight", "/my/{name}/action/fight"),
+            ("/my/bank/items", "/my/bank/items"),
+            ("/my/characters", "/my/characters"),
+            ("/my/logs/hero", "/my/logs/{name}"),
+            ("/characters/hero", "/characters/{name}"),
+            ("/characters/delete", "/characters/delete"),
+            (
+                "/accounts/someone/characters",
+                "/accounts/{account}/characters",
+            ),
+            ("/maps/1/-2", "/maps/{x}/{y}"),
+            ("/items/copper", "/items/{code}"),
+            ("/items", "/items"),
+            (
+                "/grandexchange/history/copper",
+                "/grandexchange/history/{code}",
+            ),
+        ] {
+            assert_eq!(endpoint(path), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn renders_request_latencies() {
+        let metrics = Metrics::new();
+        metrics.record_request("GET", "/items", "200", Duration::from_millis(200));
+        metrics.record_request("GET", "/items", "200", Duration::from_secs(3));
+        let out = metrics.render();
+
+        let labels = "method=\"GET\",endpoint=\"/items\",status=\"200\"";
+        for (le, count) in [("0.1", 0), ("0.25", 1), ("2.5", 1), ("5", 2), ("+Inf", 2)] {
+            let line = format!(
+                "artifacts_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                labels, le, count
+            );
+            assert!(out.contains(&line), "missing {:?} in\n{}", line, out);
+        }
+        assert!(out.contains(&format!(
+            "artifacts_http_request_duration_seconds_sum{{{}}} 3.2",
+            labels
+        )));
+        assert!(out.contains(&format!(
+            "artifacts_http_request_duration_seconds_count{{{}}} 2",
+            labels
+        )));
+    }
+
+    #[test]
+    fn renders_action_totals() {
+        let metrics = Metrics::new();
+        let win = ActionGains {
+            xp: 50,
+            gold: 5,
+            fight_result: Some(FightResult::Win),
+        };
+        metrics.record_action("hero", "fight", 20, win);
+        metrics.record_action("hero", "fight", 20, win);
+        metrics.record_action("hero", "gather", 25, ActionGains::xp(10));
+        metrics.record_rate_limit_wait(Duration::from_millis(1500));
+        let out = metrics.render();
+
+        for line in [
+            "# TYPE artifacts_actions_total counter",
+            "artifacts_actions_total{character=\"hero\",action=\"fight\"} 2",
+            "artifacts_actions_total{character=\"hero\",action=\"gather\"} 1",
+            "artifacts_xp_total{character=\"hero\"} 110",
+            "artifacts_gold_total{character=\"hero\"} 10",
+            "artifacts_cooldown_seconds_total{character=\"hero\"} 65",
+            "artifacts_fights_total{character=\"hero\",result=\"win\"} 2",
+            "artifacts_fights_total{character=\"hero\",result=\"loss\"} 0",
+            "artifacts_rate_limit_waits_total 1",
+            "artifacts_rate_limit_wait_seconds_total 1.5",
+        ] {
+            assert!(
+                out.lines().any(|l| l == line),
+                "missing {:?} in\n{}",
+                line,
+                out
+            );
+        }
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
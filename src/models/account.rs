@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// The membership status of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    Standard,
+    Founder,
+    GoldFounder,
+    VipFounder,
+    #[serde(other)]
+    Other,
+}
+
+/// The public details of an account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub username: String,
+    pub member: bool,
+    pub status: AccountStatus,
+    #[serde(default)]
+    pub badges: Vec<String>,
+    #[serde(default)]
+    pub skins: Vec<String>,
+    pub achievements_points: u32,
+    #[serde(default)]
+    pub banned: bool,
+    pub ban_reason: Option<String>,
+}
+
+/// The size and gold of the account's bank.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankDetails {
+    /// The number of different items the bank can hold.
+    pub slots: u32,
+    pub expansions: u32,
+    pub next_expansion_cost: u32,
+    pub gold: u32,
+}
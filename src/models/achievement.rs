@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What an achievement counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AchievementType {
+    /// Monsters of the target killed.
+    CombatKill,
+    /// Items of the target dropped by monsters.
+    CombatDrop,
+    /// Character levels reached.
+    CombatLevel,
+    /// Items of the target gathered.
+    Gathering,
+    /// Items of the target crafted.
+    Crafting,
+    /// Items of the target recycled.
+    Recycling,
+    /// Tasks completed.
+    Task,
+    /// Items of the target used.
+    Use,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AchievementRewards {
+    #[serde(default)]
+    pub gold: u32,
+}
+
+/// An achievement that accounts can complete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Achievement {
+    pub name: String,
+    pub code: String,
+    pub description: String,
+    pub points: u32,
+    #[serde(rename = "type")]
+    pub achievement_type: AchievementType,
+    /// The monster, resource or item code counted, if any.
+    pub target: Option<String>,
+    /// The count needed to complete the achievement.
+    pub total: u32,
+    #[serde(default)]
+    pub rewards: AchievementRewards,
+}
+
+/// An account's progress on an achievement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountAchievement {
+    #[serde(flatten)]
+    pub achievement: Achievement,
+    pub current: u32,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl AccountAchievement {
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some() || self.current >= self.achievement.total
+    }
+
+    /// The count still needed to complete the achievement.
+    pub fn remaining(&self) -> u32 {
+        self.achievement.total.saturating_sub(self.current)
+    }
+
+    /// The fraction of the achievement completed, between 0 and 1.
+    pub fn progress(&self) -> f64 {
+        match self.achievement.total {
+            0 => 1.0,
+            total => (self.current as f64 / total as f64).min(1.0),
+        }
+    }
+}
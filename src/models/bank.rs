@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{character::Character, cooldown::Cooldown, item::SimpleItem};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BankItemTransaction {
+    pub cooldown: Cooldown,
+    pub items: Vec<SimpleItem>,
+    pub bank: Vec<SimpleItem>,
+    pub character: Character,
+}
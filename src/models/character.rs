@@ -1,18 +1,247 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::{
+    equipment::EquipmentSlot,
+    skill::Skill,
+    types::{CharacterName, ItemCode, Position},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InventorySlot {
+    pub slot: u32,
+    pub code: ItemCode,
+    pub quantity: u32,
+}
+
 // TODO: Finish adding model fields
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Character {
-    pub name: String,
+    pub name: CharacterName,
     pub gold: u32,
     pub level: u32,
     pub hp: u32,
     pub max_hp: u32,
     pub xp: u32,
     pub max_xp: u32,
-    pub x: i32,
-    pub y: i32,
+    #[serde(flatten)]
+    pub position: Position,
     pub cooldown: u32,
     pub cooldown_expiration: DateTime<Utc>,
+    pub inventory_max_items: u32,
+    pub inventory: Vec<InventorySlot>,
+    #[serde(default)]
+    pub mining_level: u32,
+    #[serde(default)]
+    pub mining_xp: u32,
+    #[serde(default)]
+    pub mining_max_xp: u32,
+    #[serde(default)]
+    pub woodcutting_level: u32,
+    #[serde(default)]
+    pub woodcutting_xp: u32,
+    #[serde(default)]
+    pub woodcutting_max_xp: u32,
+    #[serde(default)]
+    pub fishing_level: u32,
+    #[serde(default)]
+    pub fishing_xp: u32,
+    #[serde(default)]
+    pub fishing_max_xp: u32,
+    #[serde(default)]
+    pub weaponcrafting_level: u32,
+    #[serde(default)]
+    pub weaponcrafting_xp: u32,
+    #[serde(default)]
+    pub weaponcrafting_max_xp: u32,
+    #[serde(default)]
+    pub gearcrafting_level: u32,
+    #[serde(default)]
+    pub gearcrafting_xp: u32,
+    #[serde(default)]
+    pub gearcrafting_max_xp: u32,
+    #[serde(default)]
+    pub jewelrycrafting_level: u32,
+    #[serde(default)]
+    pub jewelrycrafting_xp: u32,
+    #[serde(default)]
+    pub jewelrycrafting_max_xp: u32,
+    #[serde(default)]
+    pub cooking_level: u32,
+    #[serde(default)]
+    pub cooking_xp: u32,
+    #[serde(default)]
+    pub cooking_max_xp: u32,
+    #[serde(default)]
+    pub alchemy_level: u32,
+    #[serde(default)]
+    pub alchemy_xp: u32,
+    #[serde(default)]
+    pub alchemy_max_xp: u32,
+    #[serde(default)]
+    pub attack_fire: i32,
+    #[serde(default)]
+    pub attack_earth: i32,
+    #[serde(default)]
+    pub attack_water: i32,
+    #[serde(default)]
+    pub attack_air: i32,
+    #[serde(default)]
+    pub dmg: i32,
+    #[serde(default)]
+    pub dmg_fire: i32,
+    #[serde(default)]
+    pub dmg_earth: i32,
+    #[serde(default)]
+    pub dmg_water: i32,
+    #[serde(default)]
+    pub dmg_air: i32,
+    #[serde(default)]
+    pub res_fire: i32,
+    #[serde(default)]
+    pub res_earth: i32,
+    #[serde(default)]
+    pub res_water: i32,
+    #[serde(default)]
+    pub res_air: i32,
+    #[serde(default)]
+    pub critical_strike: i32,
+    #[serde(default)]
+    pub weapon_slot: ItemCode,
+    #[serde(default)]
+    pub shield_slot: ItemCode,
+    #[serde(default)]
+    pub helmet_slot: ItemCode,
+    #[serde(default)]
+    pub body_armor_slot: ItemCode,
+    #[serde(default)]
+    pub leg_armor_slot: ItemCode,
+    #[serde(default)]
+    pub boots_slot: ItemCode,
+    #[serde(default)]
+    pub ring1_slot: ItemCode,
+    #[serde(default)]
+    pub ring2_slot: ItemCode,
+    #[serde(default)]
+    pub amulet_slot: ItemCode,
+    #[serde(default)]
+    pub artifact1_slot: ItemCode,
+    #[serde(default)]
+    pub artifact2_slot: ItemCode,
+    #[serde(default)]
+    pub artifact3_slot: ItemCode,
+    /// The code of the current task's target, or empty without a task.
+    #[serde(default)]
+    pub task: String,
+    #[serde(default)]
+    pub task_type: String,
+    #[serde(default)]
+    pub task_progress: u32,
+    #[serde(default)]
+    pub task_total: u32,
+}
+
+impl Character {
+    /// Returns the quantity of the given item in the character's inventory.
+    pub fn inventory_quantity(&self, code: &ItemCode) -> u32 {
+        self.inventory
+            .iter()
+            .filter(|slot| slot.code == *code)
+            .map(|slot| slot.quantity)
+            .sum()
+    }
+
+    /// Returns the total quantity of items in the character's inventory.
+    pub fn inventory_count(&self) -> u32 {
+        self.inventory.iter().map(|slot| slot.quantity).sum()
+    }
+
+    /// Returns true if the character cannot carry any more items.
+    pub fn inventory_full(&self) -> bool {
+        self.inventory_count() >= self.inventory_max_items
+            || self.inventory.iter().all(|slot| slot.quantity > 0)
+    }
+
+    /// Returns the code of the item equipped in `slot`, if any.
+    pub fn equipped(&self, slot: EquipmentSlot) -> Option<&ItemCode> {
+        let code = match slot {
+            EquipmentSlot::Weapon => &self.weapon_slot,
+            EquipmentSlot::Shield => &self.shield_slot,
+            EquipmentSlot::Helmet => &self.helmet_slot,
+            EquipmentSlot::BodyArmor => &self.body_armor_slot,
+            EquipmentSlot::LegArmor => &self.leg_armor_slot,
+            EquipmentSlot::Boots => &self.boots_slot,
+            EquipmentSlot::Ring1 => &self.ring1_slot,
+            EquipmentSlot::Ring2 => &self.ring2_slot,
+            EquipmentSlot::Amulet => &self.amulet_slot,
+            EquipmentSlot::Artifact1 => &self.artifact1_slot,
+            EquipmentSlot::Artifact2 => &self.artifact2_slot,
+            EquipmentSlot::Artifact3 => &self.artifact3_slot,
+        };
+
+        (!code.is_empty()).then_some(code)
+    }
+
+    /// Returns the character's level in the given skill.
+    pub fn skill_level(&self, skill: Skill) -> u32 {
+        match skill {
+            Skill::Mining => self.mining_level,
+            Skill::Woodcutting => self.woodcutting_level,
+            Skill::Fishing => self.fishing_level,
+            Skill::Weaponcrafting => self.weaponcrafting_level,
+            Skill::Gearcrafting => self.gearcrafting_level,
+            Skill::Jewelrycrafting => self.jewelrycrafting_level,
+            Skill::Cooking => self.cooking_level,
+            Skill::Alchemy => self.alchemy_level,
+        }
+    }
+
+    /// Returns the character's current and required XP for the next level in
+    /// the given skill.
+    pub fn skill_xp(&self, skill: Skill) -> (u32, u32) {
+        match skill {
+            Skill::Mining => (self.mining_xp, self.mining_max_xp),
+            Skill::Woodcutting => (self.woodcutting_xp, self.woodcutting_max_xp),
+            Skill::Fishing => (self.fishing_xp, self.fishing_max_xp),
+            Skill::Weaponcrafting => (self.weaponcrafting_xp, self.weaponcrafting_max_xp),
+            Skill::Gearcrafting => (self.gearcrafting_xp, self.gearcrafting_max_xp),
+            Skill::Jewelrycrafting => (self.jewelrycrafting_xp, self.jewelrycrafting_max_xp),
+            Skill::Cooking => (self.cooking_xp, self.cooking_max_xp),
+            Skill::Alchemy => (self.alchemy_xp, self.alchemy_max_xp),
+        }
+    }
+}
+
+/// Characters built from a few fields, for tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use serde_json::{Value, json};
+
+    use super::Character;
+
+    /// A level 1 character named `hero` at (0, 0), with 100 HP, an empty
+    /// inventory and no cooldown, with `fields` set on top, e.g.
+    /// `json!({ "mining_level": 5 })`.
+    pub(crate) fn character(fields: Value) -> Character {
+        let mut character = json!({
+            "name": "hero",
+            "gold": 0,
+            "level": 1,
+            "hp": 100,
+            "max_hp": 100,
+            "xp": 0,
+            "max_xp": 150,
+            "x": 0,
+            "y": 0,
+            "cooldown": 0,
+            "cooldown_expiration": "2024-01-01T00:00:00Z",
+            "inventory_max_items": 100,
+            "inventory": [],
+        });
+
+        if let (Some(character), Value::Object(fields)) = (character.as_object_mut(), fields) {
+            character.extend(fields);
+        }
+        serde_json::from_value(character).unwrap()
+    }
 }
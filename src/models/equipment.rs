@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{character::Character, cooldown::Cooldown, item::Item};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipmentSlot {
+    Weapon,
+    Shield,
+    Helmet,
+    BodyArmor,
+    LegArmor,
+    Boots,
+    Ring1,
+    Ring2,
+    Amulet,
+    Artifact1,
+    Artifact2,
+    Artifact3,
+}
+
+impl EquipmentSlot {
+    pub const ALL: [EquipmentSlot; 12] = [
+        EquipmentSlot::Weapon,
+        EquipmentSlot::Shield,
+        EquipmentSlot::Helmet,
+        EquipmentSlot::BodyArmor,
+        EquipmentSlot::LegArmor,
+        EquipmentSlot::Boots,
+        EquipmentSlot::Ring1,
+        EquipmentSlot::Ring2,
+        EquipmentSlot::Amulet,
+        EquipmentSlot::Artifact1,
+        EquipmentSlot::Artifact2,
+        EquipmentSlot::Artifact3,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EquipmentSlot::Weapon => "weapon",
+            EquipmentSlot::Shield => "shield",
+            EquipmentSlot::Helmet => "helmet",
+            EquipmentSlot::BodyArmor => "body_armor",
+            EquipmentSlot::LegArmor => "leg_armor",
+            EquipmentSlot::Boots => "boots",
+            EquipmentSlot::Ring1 => "ring1",
+            EquipmentSlot::Ring2 => "ring2",
+            EquipmentSlot::Amulet => "amulet",
+            EquipmentSlot::Artifact1 => "artifact1",
+            EquipmentSlot::Artifact2 => "artifact2",
+            EquipmentSlot::Artifact3 => "artifact3",
+        }
+    }
+
+    /// The item type (e.g. `ring`) that can be equipped in this slot.
+    pub fn item_type(&self) -> &'static str {
+        match self {
+            EquipmentSlot::Ring1 | EquipmentSlot::Ring2 => "ring",
+            EquipmentSlot::Artifact1 | EquipmentSlot::Artifact2 | EquipmentSlot::Artifact3 => {
+                "artifact"
+            }
+            slot => slot.as_str(),
+        }
+    }
+}
+
+impl std::fmt::Display for EquipmentSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EquipmentSlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EquipmentSlot::ALL
+            .into_iter()
+            .find(|slot| slot.as_str() == s)
+            .ok_or_else(|| format!("Unknown equipment slot: {}", s))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EquipRequestData {
+    pub cooldown: Cooldown,
+    pub slot: EquipmentSlot,
+    pub item: Item,
+    pub character: Character,
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    map::{Map, MapContent, MapContentType},
+    types::{ContentCode, Position},
+};
+
+/// The monster or resource spawned by an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventContent {
+    #[serde(rename = "type")]
+    pub content_type: MapContentType,
+    pub code: ContentCode,
+}
+
+/// A map an event can spawn on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventMap {
+    #[serde(flatten)]
+    pub position: Position,
+    #[serde(default)]
+    pub skin: String,
+}
+
+/// A world event that can spawn during the season.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub name: String,
+    pub code: String,
+    pub content: EventContent,
+    pub maps: Vec<EventMap>,
+    /// How long the event lasts once spawned, in minutes.
+    pub duration: u32,
+    /// The chance of the event spawning, as 1 in `rate`.
+    pub rate: u32,
+}
+
+/// An event that is currently spawned on a map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveEvent {
+    pub name: String,
+    pub code: String,
+    /// The map the event spawned on, including its content.
+    pub map: Map,
+    /// The map as it was before the event spawned.
+    pub previous_map: Option<Map>,
+    /// How long the event lasts, in minutes.
+    pub duration: u32,
+    pub expiration: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ActiveEvent {
+    /// The monster or resource spawned by the event.
+    pub fn content(&self) -> Option<&MapContent> {
+        self.map.content.as_ref()
+    }
+
+    pub fn location(&self) -> Position {
+        self.map.position
+    }
+}
@@ -1,14 +1,14 @@
 use serde::{Deserialize, Serialize};
 
-use crate::models::{character::Character, cooldown::Cooldown};
+use crate::models::{character::Character, cooldown::Cooldown, types::ItemCode};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Drop {
-    pub code: String,
+    pub code: ItemCode,
     pub quantity: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum FightResult {
     Win,
@@ -25,9 +25,343 @@ pub struct Fight {
     pub result: FightResult,
 }
 
+impl Fight {
+    /// Parses the raw fight logs into structured [`FightEvent`]s, in the
+    /// order they occurred.
+    pub fn events(&self) -> Vec<FightEvent> {
+        self.logs
+            .iter()
+            .flat_map(|line| FightEvent::parse(line))
+            .collect()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CharacterFightData {
     pub cooldown: Cooldown,
     pub fight: Fight,
     pub character: Character,
 }
+
+/// The element of an attack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+    Fire,
+    Earth,
+    Water,
+    Air,
+}
+
+impl Element {
+    pub const ALL: [Element; 4] = [Element::Fire, Element::Earth, Element::Water, Element::Air];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Element::Fire => "fire",
+            Element::Earth => "earth",
+            Element::Water => "water",
+            Element::Air => "air",
+        }
+    }
+}
+
+/// The participant of a fight that an event refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Combatant {
+    Character,
+    Monster,
+}
+
+/// A single event parsed from a fight log line.
+///
+/// A log line may produce more than one event, e.g. a line starting with
+/// `Turn 3:` produces a [`FightEvent::TurnStart`] followed by the events
+/// described by the rest of the line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FightEvent {
+    /// The start of a fight, with the starting HP of both sides.
+    Start { character_hp: u32, monster_hp: u32 },
+    /// The start of a new turn.
+    TurnStart { turn: u32 },
+    /// A regular elemental attack.
+    Attack {
+        attacker: Combatant,
+        element: Element,
+        damage: u32,
+    },
+    /// An elemental attack that landed a critical strike.
+    CriticalStrike {
+        attacker: Combatant,
+        element: Element,
+        damage: u32,
+    },
+    /// An attack that was blocked by the defender.
+    Block {
+        defender: Combatant,
+        element: Option<Element>,
+    },
+    /// HP restored by a healing effect.
+    Heal { target: Combatant, amount: u32 },
+    /// Damage taken from poison.
+    Poison { target: Combatant, damage: u32 },
+    /// HP restored by lifesteal.
+    Lifesteal { target: Combatant, amount: u32 },
+    /// The final result of the fight.
+    Result { result: FightResult },
+    /// A log line that could not be recognised.
+    Unknown { line: String },
+}
+
+impl FightEvent {
+    /// Parses a single fight log line into one or more events.
+    pub fn parse(line: &str) -> Vec<FightEvent> {
+        let line = line.trim();
+        let lower = line.to_lowercase();
+
+        if let Some(rest) = lower.strip_prefix("fight start:") {
+            return match (
+                hp_after(rest, "character hp:"),
+                hp_after(rest, "monster hp:"),
+            ) {
+                (Some(character_hp), Some(monster_hp)) => vec![FightEvent::Start {
+                    character_hp,
+                    monster_hp,
+                }],
+                _ => vec![FightEvent::Unknown {
+                    line: line.to_string(),
+                }],
+            };
+        }
+
+        if let Some(rest) = lower.strip_prefix("fight result:") {
+            let result = if rest.contains("win") {
+                FightResult::Win
+            } else if rest.contains("loss") || rest.contains("lose") {
+                FightResult::Loss
+            } else {
+                return vec![FightEvent::Unknown {
+                    line: line.to_string(),
+                }];
+            };
+
+            return vec![FightEvent::Result { result }];
+        }
+
+        let mut events = Vec::new();
+        let mut body = lower.as_str();
+
+        if let Some((turn, rest)) = body
+            .strip_prefix("turn ")
+            .and_then(|rest| rest.split_once(':'))
+            && let Ok(turn) = turn.trim().parse()
+        {
+            events.push(FightEvent::TurnStart { turn });
+            body = rest.trim();
+        }
+
+        match parse_action(body) {
+            Some(event) => events.push(event),
+            None if events.is_empty() || !body.is_empty() => events.push(FightEvent::Unknown {
+                line: line.to_string(),
+            }),
+            None => {}
+        }
+
+        events
+    }
+
+    /// Returns the attacker and damage dealt if this event is an attack.
+    pub fn damage(&self) -> Option<(Combatant, u32)> {
+        match self {
+            FightEvent::Attack {
+                attacker, damage, ..
+            }
+            | FightEvent::CriticalStrike {
+                attacker, damage, ..
+            } => Some((*attacker, *damage)),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the description of an action, i.e. the part of a log line after
+/// the `Turn N:` prefix.
+fn parse_action(body: &str) -> Option<FightEvent> {
+    let actor = if body.starts_with("the monster") {
+        Combatant::Monster
+    } else {
+        Combatant::Character
+    };
+
+    if body.contains("lifesteal") {
+        let amount = number_before(body, " hp")?;
+        return Some(FightEvent::Lifesteal {
+            target: actor,
+            amount,
+        });
+    }
+
+    if body.contains("poison") {
+        let damage = number_before(body, " damage").or_else(|| number_before(body, " hp"))?;
+        return Some(FightEvent::Poison {
+            target: actor,
+            damage,
+        });
+    }
+
+    if body.contains("blocked") {
+        return Some(FightEvent::Block {
+            defender: actor,
+            element: element_before(body, " attack"),
+        });
+    }
+
+    if body.contains("heal") || body.contains("restored") {
+        let amount = number_before(body, " hp")?;
+        return Some(FightEvent::Heal {
+            target: actor,
+            amount,
+        });
+    }
+
+    if body.contains(" attack") {
+        let element = element_before(body, " attack")?;
+        let damage = number_before(body, " damage")?;
+
+        return Some(if body.contains("critical") {
+            FightEvent::CriticalStrike {
+                attacker: actor,
+                element,
+                damage,
+            }
+        } else {
+            FightEvent::Attack {
+                attacker: actor,
+                element,
+                damage,
+            }
+        });
+    }
+
+    None
+}
+
+/// Returns the last word before `marker`.
+fn word_before<'a>(haystack: &'a str, marker: &str) -> Option<&'a str> {
+    let idx = haystack.find(marker)?;
+    haystack[..idx].split_whitespace().next_back()
+}
+
+fn number_before(haystack: &str, marker: &str) -> Option<u32> {
+    word_before(haystack, marker)?
+        .trim_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()
+}
+
+fn element_before(haystack: &str, marker: &str) -> Option<Element> {
+    let word = word_before(haystack, marker)?;
+    Element::ALL.into_iter().find(|e| e.as_str() == word)
+}
+
+/// Parses the current HP following `label`, e.g. `character hp: 120/150`.
+fn hp_after(haystack: &str, label: &str) -> Option<u32> {
+    let idx = haystack.find(label)? + label.len();
+    let value = haystack[idx..].trim_start();
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fight_start() {
+        assert_eq!(
+            FightEvent::parse("Fight start: Character HP: 120/150, Monster HP: 60/60"),
+            vec![FightEvent::Start {
+                character_hp: 120,
+                monster_hp: 60,
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_turn_and_attack() {
+        assert_eq!(
+            FightEvent::parse(
+                "Turn 1: The character used fire attack and dealt 23 damage. (Monster HP: 37/60)"
+            ),
+            vec![
+                FightEvent::TurnStart { turn: 1 },
+                FightEvent::Attack {
+                    attacker: Combatant::Character,
+                    element: Element::Fire,
+                    damage: 23,
+                },
+            ]
+        );
+        assert_eq!(
+            FightEvent::parse(
+                "Turn 2: The monster used water attack and dealt 10 damage. (Character HP: 110/150)"
+            ),
+            vec![
+                FightEvent::TurnStart { turn: 2 },
+                FightEvent::Attack {
+                    attacker: Combatant::Monster,
+                    element: Element::Water,
+                    damage: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_critical_strike() {
+        let events = FightEvent::parse(
+            "Turn 3: The character used earth attack and dealt 30 damage (Critical strike). (Monster HP: 7/60)",
+        );
+        assert_eq!(
+            events[1],
+            FightEvent::CriticalStrike {
+                attacker: Combatant::Character,
+                element: Element::Earth,
+                damage: 30,
+            }
+        );
+        assert_eq!(events[1].damage(), Some((Combatant::Character, 30)));
+    }
+
+    #[test]
+    fn parses_result() {
+        assert_eq!(
+            FightEvent::parse("Fight result: win."),
+            vec![FightEvent::Result {
+                result: FightResult::Win
+            }]
+        );
+        assert_eq!(
+            FightEvent::parse("Fight result: loss."),
+            vec![FightEvent::Result {
+                result: FightResult::Loss
+            }]
+        );
+    }
+
+    #[test]
+    fn keeps_unrecognised_lines() {
+        let line = "Something unexpected happened";
+        assert_eq!(
+            FightEvent::parse(line),
+            vec![FightEvent::Unknown {
+                line: line.to_string()
+            }]
+        );
+    }
+}
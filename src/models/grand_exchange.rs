@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{character::Character, cooldown::Cooldown, types::ItemCode};
+
+/// A sell order listed on the Grand Exchange.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeOrder {
+    pub id: String,
+    pub seller: String,
+    pub code: ItemCode,
+    pub quantity: u32,
+    /// The price of a single item.
+    pub price: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A completed Grand Exchange sale.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeOrderHistory {
+    pub order_id: String,
+    pub seller: String,
+    pub buyer: String,
+    pub code: ItemCode,
+    pub quantity: u32,
+    /// The price of a single item.
+    pub price: u32,
+    pub sold_at: DateTime<Utc>,
+}
+
+/// A sell order created by one of the user's characters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeOrderCreated {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub code: ItemCode,
+    pub quantity: u32,
+    pub price: u32,
+    #[serde(default)]
+    pub total_price: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeOrderTransaction {
+    pub cooldown: Cooldown,
+    pub order: GeOrderCreated,
+    pub character: Character,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    character::Character,
+    cooldown::Cooldown,
+    skill::Skill,
+    types::{EffectCode, ItemCode},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleItem {
+    pub code: ItemCode,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Craft {
+    pub skill: Skill,
+    pub level: u32,
+    pub items: Vec<SimpleItem>,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemEffect {
+    pub code: EffectCode,
+    pub value: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub code: ItemCode,
+    pub level: u32,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub subtype: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+    pub craft: Option<Craft>,
+    #[serde(default)]
+    pub tradeable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UseItemData {
+    pub cooldown: Cooldown,
+    pub item: Item,
+    pub character: Character,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{account::AccountStatus, types::CharacterName};
+
+/// The ranking used by the characters leaderboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterLeaderboardSort {
+    Combat,
+    Woodcutting,
+    Mining,
+    Fishing,
+    Weaponcrafting,
+    Gearcrafting,
+    Jewelrycrafting,
+    Cooking,
+    Alchemy,
+}
+
+/// The ranking used by the accounts leaderboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountLeaderboardSort {
+    AchievementsPoints,
+    Gold,
+}
+
+/// A character's rank on the characters leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterLeaderboardEntry {
+    pub position: u32,
+    pub name: CharacterName,
+    pub account: String,
+    pub status: AccountStatus,
+    pub level: u32,
+    pub total_xp: u64,
+    pub gold: u32,
+}
+
+/// An account's rank on the accounts leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountLeaderboardEntry {
+    pub position: u32,
+    pub account: String,
+    pub status: AccountStatus,
+    pub achievements_points: u32,
+    pub gold: u32,
+}
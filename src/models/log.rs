@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{fight::Fight, item::SimpleItem, types::CharacterName};
+
+/// The kind of action a [`LogEntry`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogType {
+    Spawn,
+    Movement,
+    Fight,
+    Crafting,
+    Gathering,
+    Rest,
+    Use,
+    Equip,
+    Unequip,
+    DepositItem,
+    WithdrawItem,
+    DepositGold,
+    WithdrawGold,
+    BuyGe,
+    SellGe,
+    CancelGe,
+    Recycling,
+    Task,
+    #[serde(other)]
+    Other,
+}
+
+impl LogType {
+    /// The name of the matching action in the
+    /// [`HistoryStore`](crate::history::HistoryStore), or `None` for actions
+    /// the bots never record.
+    pub fn action(&self) -> Option<&'static str> {
+        Some(match self {
+            LogType::Movement => "move",
+            LogType::Fight => "fight",
+            LogType::Crafting => "crafting",
+            LogType::Gathering => "gathering",
+            LogType::Rest => "rest",
+            LogType::Use => "use",
+            LogType::Equip => "equip",
+            LogType::DepositItem => "deposit",
+            LogType::WithdrawItem => "withdraw",
+            LogType::SellGe => "ge_sell",
+            _ => return None,
+        })
+    }
+}
+
+/// The result details of a logged skill action.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogSkillDetails {
+    #[serde(default)]
+    pub xp: u32,
+    #[serde(default)]
+    pub items: Vec<SimpleItem>,
+}
+
+/// The details of a logged action. Only the fields used for reconciliation
+/// are typed; the full content is kept in [`LogEntry::content`].
+#[derive(Debug, Default, Deserialize)]
+pub struct LogContent {
+    pub fight: Option<Fight>,
+    pub details: Option<LogSkillDetails>,
+}
+
+/// An action performed by a character, as logged by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub character: CharacterName,
+    pub account: String,
+    #[serde(rename = "type")]
+    pub log_type: LogType,
+    pub description: String,
+    #[serde(default)]
+    pub content: serde_json::Value,
+    #[serde(default)]
+    pub cooldown: u32,
+    pub cooldown_expiration: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LogEntry {
+    /// Parses the typed parts of [`LogEntry::content`], ignoring any that do
+    /// not match.
+    pub fn details(&self) -> LogContent {
+        LogContent {
+            fight: self
+                .content
+                .get("fight")
+                .and_then(|f| serde_json::from_value(f.clone()).ok()),
+            details: self
+                .content
+                .get("details")
+                .and_then(|d| serde_json::from_value(d.clone()).ok()),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::models::types::{ContentCode, MonsterCode, Position, ResourceCode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MapContentType {
     Monster,
@@ -12,18 +14,31 @@ pub enum MapContentType {
     Npc,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MapContent {
     pub content_type: MapContentType,
-    pub code: String,
+    pub code: ContentCode,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Map {
     pub name: String,
     pub skin: String,
-    pub x: i32,
-    pub y: i32,
+    #[serde(flatten)]
+    pub position: Position,
     #[serde(flatten)]
     pub content: Option<MapContent>,
 }
+
+impl MapContent {
+    /// The monster on the map, if it is a monster map.
+    pub fn monster_code(&self) -> Option<MonsterCode> {
+        (self.content_type == MapContentType::Monster).then(|| MonsterCode::new(self.code.as_str()))
+    }
+
+    /// The resource on the map, if it is a resource map.
+    pub fn resource_code(&self) -> Option<ResourceCode> {
+        (self.content_type == MapContentType::Resource)
+            .then(|| ResourceCode::new(self.code.as_str()))
+    }
+}
@@ -1,7 +1,20 @@
+pub mod account;
+pub mod achievement;
+pub mod bank;
 pub mod character;
 pub mod cooldown;
+pub mod equipment;
+pub mod event;
 pub mod fight;
+pub mod grand_exchange;
+pub mod item;
+pub mod leaderboard;
+pub mod log;
 pub mod map;
 pub mod monster;
 pub mod movement;
+pub mod resource;
 pub mod rest;
+pub mod skill;
+pub mod status;
+pub mod types;
@@ -1,9 +1,39 @@
 use serde::{Deserialize, Serialize};
 
+use crate::models::types::{ItemCode, MonsterCode};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropRate {
+    pub code: ItemCode,
+    pub rate: u32,
+    pub min_quantity: u32,
+    pub max_quantity: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Monster {
     pub name: String,
-    pub code: String,
+    pub code: MonsterCode,
     pub level: u32,
     pub hp: u32,
+    #[serde(default)]
+    pub attack_fire: i32,
+    #[serde(default)]
+    pub attack_earth: i32,
+    #[serde(default)]
+    pub attack_water: i32,
+    #[serde(default)]
+    pub attack_air: i32,
+    #[serde(default)]
+    pub res_fire: i32,
+    #[serde(default)]
+    pub res_earth: i32,
+    #[serde(default)]
+    pub res_water: i32,
+    #[serde(default)]
+    pub res_air: i32,
+    #[serde(default)]
+    pub critical_strike: i32,
+    #[serde(default)]
+    pub drops: Vec<DropRate>,
 }
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{monster::DropRate, skill::Skill, types::ResourceCode};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    pub name: String,
+    pub code: ResourceCode,
+    pub skill: Skill,
+    pub level: u32,
+    pub drops: Vec<DropRate>,
+}
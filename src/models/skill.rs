@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{character::Character, cooldown::Cooldown, item::SimpleItem};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Skill {
+    Mining,
+    Woodcutting,
+    Fishing,
+    Weaponcrafting,
+    Gearcrafting,
+    Jewelrycrafting,
+    Cooking,
+    Alchemy,
+}
+
+impl Skill {
+    pub const ALL: [Skill; 8] = [
+        Skill::Mining,
+        Skill::Woodcutting,
+        Skill::Fishing,
+        Skill::Weaponcrafting,
+        Skill::Gearcrafting,
+        Skill::Jewelrycrafting,
+        Skill::Cooking,
+        Skill::Alchemy,
+    ];
+
+    /// Whether the skill is levelled by gathering resources.
+    pub fn is_gathering(&self) -> bool {
+        matches!(
+            self,
+            Skill::Mining | Skill::Woodcutting | Skill::Fishing | Skill::Alchemy
+        )
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Skill::Mining => "mining",
+            Skill::Woodcutting => "woodcutting",
+            Skill::Fishing => "fishing",
+            Skill::Weaponcrafting => "weaponcrafting",
+            Skill::Gearcrafting => "gearcrafting",
+            Skill::Jewelrycrafting => "jewelrycrafting",
+            Skill::Cooking => "cooking",
+            Skill::Alchemy => "alchemy",
+        }
+    }
+}
+
+impl std::fmt::Display for Skill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Skill {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Skill::ALL
+            .into_iter()
+            .find(|skill| skill.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown skill: {}", s))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkillInfo {
+    pub xp: u32,
+    pub items: Vec<SimpleItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkillData {
+    pub cooldown: Cooldown,
+    pub details: SkillInfo,
+    pub character: Character,
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A message from the game developers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The current game season.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Season {
+    pub name: String,
+    pub number: u32,
+    pub start_date: DateTime<Utc>,
+}
+
+/// The state of the game server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub version: String,
+    pub server_time: DateTime<Utc>,
+    #[serde(default)]
+    pub max_level: u32,
+    #[serde(default)]
+    pub characters_online: u32,
+    #[serde(default)]
+    pub announcements: Vec<Announcement>,
+    pub season: Option<Season>,
+    pub last_wipe: Option<String>,
+    pub next_wipe: Option<String>,
+}
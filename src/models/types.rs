@@ -0,0 +1,332 @@
+use std::{borrow::Borrow, convert::Infallible, ops::Deref, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Declares a string newtype for a kind of code, so codes of different kinds
+/// cannot be mixed up. The newtypes dereference to `str` so they can be read
+/// like the plain strings they wrap.
+macro_rules! code_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(code: impl Into<String>) -> Self {
+                Self(code.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(Self::new(s))
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(code: &str) -> Self {
+                Self::new(code)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(code: String) -> Self {
+                Self(code)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(code: $name) -> Self {
+                code.0
+            }
+        }
+
+        impl From<&$name> for String {
+            fn from(code: &$name) -> Self {
+                code.0.clone()
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+    };
+}
+
+code_type!(
+    /// The code of an item, e.g. `copper_ore`. Empty for empty inventory and
+    /// equipment slots.
+    ItemCode
+);
+
+code_type!(
+    /// The code of a monster, e.g. `chicken`.
+    MonsterCode
+);
+
+code_type!(
+    /// The code of a resource, e.g. `copper_rocks`.
+    ResourceCode
+);
+
+code_type!(
+    /// The code of what a map or event holds, whose kind depends on the
+    /// content type, e.g. a monster or a resource. Convert it with
+    /// [`MapContent::monster_code`](crate::models::map::MapContent::monster_code)
+    /// or [`MapContent::resource_code`](crate::models::map::MapContent::resource_code).
+    ContentCode
+);
+
+code_type!(
+    /// The code of an item effect, e.g. `hp` or `attack_fire`.
+    EffectCode
+);
+
+/// The coordinates of a map.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Position {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// The number of map steps between the two positions, which is what a
+    /// move's cooldown depends on.
+    pub fn distance(&self, other: Position) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Returns the position in `positions` closest to this one.
+    pub fn closest(&self, positions: impl IntoIterator<Item = Position>) -> Option<Position> {
+        positions.into_iter().min_by_key(|p| self.distance(*p))
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<(i32, i32)> for Position {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self { x, y }
+    }
+}
+
+/// The shortest and longest character names accepted by the API.
+const NAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=12;
+
+/// Errors returned when a character name is rejected.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NameError {
+    #[error("Character name '{0}' must be 3 to 12 characters long")]
+    Length(String),
+    #[error("Character name '{0}' may only contain letters, digits, '_' and '-'")]
+    InvalidCharacter(String),
+}
+
+/// The name of a character, validated against the API's naming rules.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CharacterName(String);
+
+impl CharacterName {
+    pub fn new(name: impl Into<String>) -> Result<Self, NameError> {
+        let name = name.into();
+
+        if !NAME_LENGTH.contains(&name.chars().count()) {
+            return Err(NameError::Length(name));
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(NameError::InvalidCharacter(name));
+        }
+
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for CharacterName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for CharacterName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for CharacterName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for CharacterName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for CharacterName {
+    type Err = NameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for CharacterName {
+    type Error = NameError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl From<CharacterName> for String {
+    fn from(name: CharacterName) -> Self {
+        name.0
+    }
+}
+
+impl PartialEq<str> for CharacterName {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for CharacterName {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for CharacterName {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_names() {
+        for name in ["abc", "Penguin_42", "a-b-c", "abcdefghijkl"] {
+            assert_eq!(CharacterName::new(name).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn rejects_names_of_the_wrong_length() {
+        for name in ["", "ab", "abcdefghijklm"] {
+            assert_eq!(
+                CharacterName::new(name),
+                Err(NameError::Length(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_characters() {
+        for name in ["with space", "héros", "dot.name", "a/b/c"] {
+            assert_eq!(
+                CharacterName::new(name),
+                Err(NameError::InvalidCharacter(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn validates_deserialized_names() {
+        assert!(serde_json::from_str::<CharacterName>("\"Penguin\"").is_ok());
+        assert!(serde_json::from_str::<CharacterName>("\"no\"").is_err());
+    }
+
+    #[test]
+    fn measures_distance_in_map_steps() {
+        let origin = Position::new(0, 0);
+        assert_eq!(origin.distance(origin), 0);
+        assert_eq!(origin.distance(Position::new(3, -4)), 7);
+        assert_eq!(Position::new(-2, 5).distance(Position::new(1, 1)), 7);
+    }
+
+    #[test]
+    fn finds_the_closest_position() {
+        let from = Position::new(1, 1);
+        let positions = [
+            Position::new(5, 5),
+            Position::new(0, 2),
+            Position::new(-3, 1),
+        ];
+        assert_eq!(from.closest(positions), Some(Position::new(0, 2)));
+        assert_eq!(from.closest([]), None);
+    }
+}
@@ -0,0 +1,363 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    api::{MAX_PAGE_SIZE, client::ArtifactsClient},
+    cooldown::ServerClock,
+    history::{ActionRecord, HistoryError, HistoryStore},
+    models::{log::LogEntry, types::CharacterName},
+};
+
+/// How far apart a log entry and a recorded action can be and still be the
+/// same action, once the record is moved to the server clock. Records are
+/// timestamped just before the request is sent and logs when the server
+/// handles it, so they are apart by the request's latency plus any skew the
+/// clock sync missed.
+pub const MATCH_TOLERANCE: Duration = Duration::seconds(10);
+
+/// How far back the logs are read for a character that has never been
+/// reconciled.
+pub const DEFAULT_LOOKBACK: Duration = Duration::hours(24);
+
+/// How often [`Reconciler::run`] reconciles the logs.
+pub const RECONCILE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// The outcome of reconciling the server logs with the history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ReconcileReport {
+    /// The log entries newer than the last reconciliation.
+    pub entries: usize,
+    /// The entries that were already recorded by a bot.
+    pub matched: usize,
+    /// The entries added to the history.
+    pub imported: usize,
+}
+
+impl std::ops::AddAssign for ReconcileReport {
+    fn add_assign(&mut self, other: Self) {
+        self.entries += other.entries;
+        self.matched += other.matched;
+        self.imported += other.imported;
+    }
+}
+
+/// Imports the actions found in the server logs but missing from the
+/// history, such as actions taken on the website, by another process or
+/// while the bots were not recording.
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    api: ArtifactsClient,
+    history: Arc<HistoryStore>,
+    tolerance: Duration,
+    clock: &'static ServerClock,
+}
+
+impl Reconciler {
+    pub fn new(api: ArtifactsClient, history: Arc<HistoryStore>) -> Self {
+        Self {
+            api,
+            history,
+            tolerance: MATCH_TOLERANCE,
+            clock: ServerClock::global(),
+        }
+    }
+
+    pub fn tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the clock the logs are compared against, instead of the global
+    /// one.
+    pub fn clock(mut self, clock: &'static ServerClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The time after which the logs of `character` have not been
+    /// reconciled yet.
+    fn since(&self, character: &str) -> Result<DateTime<Utc>, HistoryError> {
+        Ok(self
+            .history
+            .reconciled_until(character)?
+            .unwrap_or_else(|| Utc::now() - DEFAULT_LOOKBACK))
+    }
+
+    /// Reconciles the logs of a single character.
+    pub async fn reconcile_character(
+        &self,
+        name: &CharacterName,
+    ) -> anyhow::Result<ReconcileReport> {
+        let since = tokio::task::block_in_place(|| self.since(name))?;
+        let mut entries = Vec::new();
+
+        for page in 1.. {
+            let logs = self.api.get_character_logs(name, page).await?;
+            let len = logs.len();
+            let done = logs.iter().any(|e| e.created_at <= since);
+            entries.extend(logs.into_iter().filter(|e| e.created_at > since));
+
+            if done || len < MAX_PAGE_SIZE as usize {
+                break;
+            }
+        }
+
+        Ok(tokio::task::block_in_place(|| {
+            self.reconcile_entries(name, &entries, since)
+        })?)
+    }
+
+    /// Reconciles the logs of every character of the account.
+    pub async fn reconcile_account(&self) -> anyhow::Result<ReconcileReport> {
+        let oldest = Utc::now() - DEFAULT_LOOKBACK;
+        let mut entries: Vec<LogEntry> = Vec::new();
+
+        for page in 1.. {
+            let logs = self.api.get_account_logs(page).await?;
+            let len = logs.len();
+            let done = logs.iter().any(|e| e.created_at <= oldest);
+            entries.extend(logs.into_iter().filter(|e| e.created_at > oldest));
+
+            if done || len < MAX_PAGE_SIZE as usize {
+                break;
+            }
+        }
+
+        let mut characters = entries
+            .iter()
+            .map(|e| e.character.clone())
+            .collect::<Vec<_>>();
+        characters.sort();
+        characters.dedup();
+
+        // The history is queried synchronously, so the worker thread is
+        // handed over while it is.
+        tokio::task::block_in_place(|| {
+            let mut report = ReconcileReport::default();
+            for character in characters {
+                let since = self.since(&character)?;
+                let logs = entries
+                    .iter()
+                    .filter(|e| e.character == character && e.created_at > since)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                report += self.reconcile_entries(&character, &logs, since)?;
+            }
+
+            Ok(report)
+        })
+    }
+
+    /// Matches the log entries of `character` newer than `since` against the
+    /// recorded actions, importing the ones that have no match.
+    ///
+    /// Entries are imported once: the time of the newest entry is stored and
+    /// used as `since` for the next reconciliation.
+    pub fn reconcile_entries(
+        &self,
+        character: &str,
+        entries: &[LogEntry],
+        since: DateTime<Utc>,
+    ) -> Result<ReconcileReport, HistoryError> {
+        // The most recent actions may not have been recorded yet, so they
+        // are left for the next run.
+        let until = self.clock.now() - self.tolerance;
+        let mut entries = entries
+            .iter()
+            .filter(|e| e.created_at > since && e.created_at <= until)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|e| e.created_at);
+
+        let mut report = ReconcileReport {
+            entries: entries.len(),
+            ..ReconcileReport::default()
+        };
+        let Some(newest) = entries.last().map(|e| e.created_at) else {
+            return Ok(report);
+        };
+
+        // Records use the local clock and logs the server's.
+        let offset = self.clock.offset();
+        let mut recorded = self
+            .history
+            .actions_between(
+                character,
+                since - offset - self.tolerance,
+                newest - offset + self.tolerance,
+            )?
+            .into_iter()
+            .map(|(timestamp, action)| (timestamp, action, false))
+            .collect::<Vec<_>>();
+
+        for entry in entries {
+            let local = entry.created_at - offset;
+            let action = entry.log_type.action();
+            let matching = recorded.iter_mut().find(|(timestamp, recorded, used)| {
+                !*used
+                    && action == Some(recorded.as_str())
+                    && (*timestamp - local).abs() <= self.tolerance
+            });
+
+            match matching {
+                Some((_, _, used)) => {
+                    *used = true;
+                    report.matched += 1;
+                }
+                None => {
+                    self.history.record(&ActionRecord::from_log(entry))?;
+                    report.imported += 1;
+                }
+            }
+        }
+
+        self.history.set_reconciled_until(character, newest)?;
+        Ok(report)
+    }
+
+    /// Reconciles the logs of `characters` now and then every `interval`
+    /// until cancelled.
+    pub async fn run(
+        &self,
+        characters: Vec<CharacterName>,
+        interval: std::time::Duration,
+        cancel: CancellationToken,
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = cancel.cancelled() => break,
+            }
+
+            for name in &characters {
+                match self.reconcile_character(name).await {
+                    Ok(report) if report.imported > 0 => info!(
+                        target: "history",
+                        "Imported {} of {} logged actions for {}",
+                        report.imported, report.entries, name
+                    ),
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(target: "history", "Failed to reconcile logs of {}: {}", name, e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn reconciler(offset: Duration) -> (Reconciler, Arc<HistoryStore>) {
+        let clock: &'static ServerClock = Box::leak(Box::new(ServerClock::new()));
+        clock.set_offset(offset);
+
+        let history = Arc::new(HistoryStore::open_in_memory().unwrap());
+        let reconciler =
+            Reconciler::new(ArtifactsClient::new("token"), history.clone()).clock(clock);
+        (reconciler, history)
+    }
+
+    fn entry(log_type: &str, created_at: DateTime<Utc>) -> LogEntry {
+        serde_json::from_value(json!({
+            "character": "hero",
+            "account": "player",
+            "type": log_type,
+            "description": "",
+            "cooldown": 5,
+            "cooldown_expiration": null,
+            "created_at": created_at,
+        }))
+        .unwrap()
+    }
+
+    fn record(action: &str, timestamp: DateTime<Utc>) -> ActionRecord {
+        ActionRecord {
+            timestamp,
+            ..ActionRecord::new("hero", action, json!({}))
+        }
+    }
+
+    #[test]
+    fn matches_entries_recorded_by_a_bot() {
+        let (reconciler, history) = reconciler(Duration::zero());
+        let sent = Utc::now() - Duration::hours(1);
+        history.record(&record("fight", sent)).unwrap();
+
+        let since = sent - Duration::minutes(1);
+        let logged = sent + Duration::seconds(1);
+        let report = reconciler
+            .reconcile_entries("hero", &[entry("fight", logged)], since)
+            .unwrap();
+
+        assert_eq!(
+            report,
+            ReconcileReport {
+                entries: 1,
+                matched: 1,
+                imported: 0,
+            }
+        );
+        assert_eq!(history.recent("hero", 10).unwrap().len(), 1);
+        assert_eq!(history.reconciled_until("hero").unwrap(), Some(logged));
+    }
+
+    #[test]
+    fn imports_entries_missing_from_the_history() {
+        let (reconciler, history) = reconciler(Duration::zero());
+        let sent = Utc::now() - Duration::hours(1);
+        history.record(&record("gathering", sent)).unwrap();
+
+        let since = sent - Duration::minutes(1);
+        let entries = [
+            entry("fight", sent),
+            // Already reconciled.
+            entry("fight", since),
+            // Too recent to have been recorded, so left for the next run.
+            entry("fight", Utc::now()),
+        ];
+        let report = reconciler
+            .reconcile_entries("hero", &entries, since)
+            .unwrap();
+
+        assert_eq!(
+            report,
+            ReconcileReport {
+                entries: 1,
+                matched: 0,
+                imported: 1,
+            }
+        );
+        let recent = history.recent("hero", 10).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(recent.iter().any(|r| r.action == "fight" && r.imported));
+        assert_eq!(history.reconciled_until("hero").unwrap(), Some(sent));
+    }
+
+    #[test]
+    fn matches_entries_on_the_server_clock() {
+        let offset = Duration::seconds(30);
+        let (reconciler, history) = reconciler(offset);
+        let sent = Utc::now() - Duration::hours(1);
+        history.record(&record("fight", sent)).unwrap();
+
+        // Logged 31s after the local send time, which is 1s on the server
+        // clock.
+        let logged = sent + offset + Duration::seconds(1);
+        let report = reconciler
+            .reconcile_entries("hero", &[entry("fight", logged)], sent)
+            .unwrap();
+
+        assert_eq!(report.matched, 1);
+        assert_eq!(report.imported, 0);
+    }
+}
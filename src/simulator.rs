@@ -0,0 +1,373 @@
+use crate::models::{character::Character, fight::Element, item::ItemEffect, monster::Monster};
+
+/// The number of turns after which a fight is considered lost.
+pub const MAX_TURNS: u32 = 100;
+
+/// The damage multiplier applied by a critical strike.
+const CRITICAL_MULTIPLIER: f64 = 1.5;
+
+/// A value for each of the four elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ElementStats {
+    pub fire: i32,
+    pub earth: i32,
+    pub water: i32,
+    pub air: i32,
+}
+
+impl ElementStats {
+    pub fn get(&self, element: Element) -> i32 {
+        match element {
+            Element::Fire => self.fire,
+            Element::Earth => self.earth,
+            Element::Water => self.water,
+            Element::Air => self.air,
+        }
+    }
+
+    pub fn get_mut(&mut self, element: Element) -> &mut i32 {
+        match element {
+            Element::Fire => &mut self.fire,
+            Element::Earth => &mut self.earth,
+            Element::Water => &mut self.water,
+            Element::Air => &mut self.air,
+        }
+    }
+}
+
+/// The stats of one side of a fight.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CombatStats {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub attack: ElementStats,
+    /// Damage bonus in percent applied to every element.
+    pub dmg: i32,
+    /// Damage bonus in percent applied to a single element.
+    pub dmg_element: ElementStats,
+    /// Resistance in percent against each element.
+    pub res: ElementStats,
+    /// Chance in percent to land a critical strike.
+    pub critical_strike: i32,
+}
+
+impl CombatStats {
+    /// Adds (or, with `sign` of `-1`, removes) the given item effects.
+    ///
+    /// Effects that have no influence on fights are ignored.
+    pub fn apply_effects(&mut self, effects: &[ItemEffect], sign: i32) {
+        for effect in effects {
+            let value = effect.value * sign;
+            let code = effect.code.as_str();
+
+            let element = |prefix: &str| {
+                code.strip_prefix(prefix)
+                    .and_then(|e| Element::ALL.into_iter().find(|el| el.as_str() == e))
+            };
+
+            if code == "hp" {
+                self.max_hp += value;
+                self.hp += value;
+            } else if code == "dmg" {
+                self.dmg += value;
+            } else if code == "critical_strike" {
+                self.critical_strike += value;
+            } else if let Some(e) = element("attack_") {
+                *self.attack.get_mut(e) += value;
+            } else if let Some(e) = element("dmg_") {
+                *self.dmg_element.get_mut(e) += value;
+            } else if let Some(e) = element("res_") {
+                *self.res.get_mut(e) += value;
+            }
+        }
+    }
+
+    /// The damage dealt to `defender` by a single attack of the given element.
+    fn element_damage(&self, defender: &CombatStats, element: Element, critical: bool) -> i32 {
+        let attack = self.attack.get(element) as f64;
+        let bonus = (self.dmg + self.dmg_element.get(element)) as f64 / 100.0;
+        let multiplier = if critical { CRITICAL_MULTIPLIER } else { 1.0 };
+        let resistance = defender.res.get(element) as f64 / 100.0;
+
+        (attack * (1.0 + bonus) * multiplier * (1.0 - resistance))
+            .round()
+            .max(0.0) as i32
+    }
+}
+
+impl From<&Character> for CombatStats {
+    fn from(character: &Character) -> Self {
+        Self {
+            hp: character.hp as i32,
+            max_hp: character.max_hp as i32,
+            attack: ElementStats {
+                fire: character.attack_fire,
+                earth: character.attack_earth,
+                water: character.attack_water,
+                air: character.attack_air,
+            },
+            dmg: character.dmg,
+            dmg_element: ElementStats {
+                fire: character.dmg_fire,
+                earth: character.dmg_earth,
+                water: character.dmg_water,
+                air: character.dmg_air,
+            },
+            res: ElementStats {
+                fire: character.res_fire,
+                earth: character.res_earth,
+                water: character.res_water,
+                air: character.res_air,
+            },
+            critical_strike: character.critical_strike,
+        }
+    }
+}
+
+impl From<&Monster> for CombatStats {
+    fn from(monster: &Monster) -> Self {
+        Self {
+            hp: monster.hp as i32,
+            max_hp: monster.hp as i32,
+            attack: ElementStats {
+                fire: monster.attack_fire,
+                earth: monster.attack_earth,
+                water: monster.attack_water,
+                air: monster.attack_air,
+            },
+            dmg: 0,
+            dmg_element: ElementStats::default(),
+            res: ElementStats {
+                fire: monster.res_fire,
+                earth: monster.res_earth,
+                water: monster.res_water,
+                air: monster.res_air,
+            },
+            critical_strike: monster.critical_strike,
+        }
+    }
+}
+
+/// The aggregated outcome of simulating a fight many times.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimulationResult {
+    /// The fraction of simulated fights that were won, from 0 to 1.
+    pub win_rate: f64,
+    pub average_turns: f64,
+    /// The average HP lost by the character.
+    pub average_hp_lost: f64,
+}
+
+/// The outcome of a single simulated fight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FightOutcome {
+    pub win: bool,
+    pub turns: u32,
+    pub character_hp: i32,
+    pub monster_hp: i32,
+}
+
+/// Simulates `iterations` fights between `character` and `monster`.
+///
+/// The simulation uses a fixed seed, so the same inputs always produce the
+/// same result.
+pub fn simulate(
+    character: &CombatStats,
+    monster: &CombatStats,
+    iterations: u32,
+) -> SimulationResult {
+    let mut rng = Rng::new(0x5eed_a27f_ac75);
+    let iterations = iterations.max(1);
+
+    let mut wins = 0;
+    let mut turns = 0;
+    let mut hp_lost = 0;
+
+    for _ in 0..iterations {
+        let outcome = simulate_once(character, monster, &mut rng);
+        wins += outcome.win as u32;
+        turns += outcome.turns as u64;
+        hp_lost += (character.hp - outcome.character_hp).max(0) as u64;
+    }
+
+    SimulationResult {
+        win_rate: wins as f64 / iterations as f64,
+        average_turns: turns as f64 / iterations as f64,
+        average_hp_lost: hp_lost as f64 / iterations as f64,
+    }
+}
+
+/// Simulates a single fight. The character always attacks first.
+///
+/// Each turn the attacker hits with every element it has attack in. A hit can
+/// be a critical strike, and the defender blocks a hit with a chance of a
+/// tenth of its resistance to that element.
+pub fn simulate_once(
+    character: &CombatStats,
+    monster: &CombatStats,
+    rng: &mut Rng,
+) -> FightOutcome {
+    let mut character_hp = character.hp;
+    let mut monster_hp = monster.hp;
+
+    for turn in 1..=MAX_TURNS {
+        let (attacker, defender, defender_hp) = if turn % 2 == 1 {
+            (character, monster, &mut monster_hp)
+        } else {
+            (monster, character, &mut character_hp)
+        };
+
+        for element in Element::ALL {
+            if attacker.attack.get(element) <= 0 {
+                continue;
+            }
+
+            let block_chance = defender.res.get(element).max(0) as f64 / 1000.0;
+            if rng.chance(block_chance) {
+                continue;
+            }
+
+            let critical = rng.chance(attacker.critical_strike as f64 / 100.0);
+            *defender_hp -= attacker.element_damage(defender, element, critical);
+        }
+
+        if monster_hp <= 0 || character_hp <= 0 {
+            return FightOutcome {
+                win: monster_hp <= 0,
+                turns: turn,
+                character_hp: character_hp.max(0),
+                monster_hp: monster_hp.max(0),
+            };
+        }
+    }
+
+    FightOutcome {
+        win: false,
+        turns: MAX_TURNS,
+        character_hp,
+        monster_hp,
+    }
+}
+
+/// A small xorshift random number generator, good enough for simulations.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Returns a uniformly distributed value in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.next_f64() < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn stats(hp: i32, fire: i32) -> CombatStats {
+        CombatStats {
+            hp,
+            max_hp: hp,
+            attack: ElementStats {
+                fire,
+                ..ElementStats::default()
+            },
+            ..CombatStats::default()
+        }
+    }
+
+    #[test]
+    fn applies_and_removes_effects() {
+        let effects: Vec<ItemEffect> = serde_json::from_value(json!([
+            { "code": "hp", "value": 20 },
+            { "code": "attack_fire", "value": 5 },
+            { "code": "dmg_fire", "value": 10 },
+            { "code": "res_water", "value": 15 },
+            { "code": "haste", "value": 3 },
+        ]))
+        .unwrap();
+
+        let mut character = stats(100, 10);
+        character.apply_effects(&effects, 1);
+        assert_eq!(character.max_hp, 120);
+        assert_eq!(character.attack.fire, 15);
+        assert_eq!(character.dmg_element.fire, 10);
+        assert_eq!(character.res.water, 15);
+
+        character.apply_effects(&effects, -1);
+        assert_eq!(character, stats(100, 10));
+    }
+
+    #[test]
+    fn applies_bonuses_and_resistances_to_damage() {
+        let mut attacker = stats(100, 100);
+        attacker.dmg = 10;
+        attacker.dmg_element.fire = 10;
+        let mut defender = stats(100, 0);
+        defender.res.fire = 50;
+
+        assert_eq!(attacker.element_damage(&defender, Element::Fire, false), 60);
+        assert_eq!(attacker.element_damage(&defender, Element::Fire, true), 90);
+        assert_eq!(attacker.element_damage(&defender, Element::Water, false), 0);
+    }
+
+    #[test]
+    fn character_attacks_first() {
+        // Both sides kill the other in a single hit.
+        let outcome = simulate_once(&stats(10, 10), &stats(10, 10), &mut Rng::new(1));
+        assert_eq!(
+            outcome,
+            FightOutcome {
+                win: true,
+                turns: 1,
+                character_hp: 10,
+                monster_hp: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn simulates_deterministic_fights() {
+        let result = simulate(&stats(100, 30), &stats(60, 20), 10);
+        assert_eq!(result.win_rate, 1.0);
+        assert_eq!(result.average_turns, 3.0);
+        assert_eq!(result.average_hp_lost, 20.0);
+
+        let result = simulate(&stats(30, 5), &stats(60, 20), 10);
+        assert_eq!(result.win_rate, 0.0);
+        assert_eq!(result.average_hp_lost, 30.0);
+    }
+
+    #[test]
+    fn uses_a_fixed_seed() {
+        let mut character = stats(100, 20);
+        character.critical_strike = 30;
+        let mut monster = stats(120, 15);
+        monster.res.fire = 200;
+
+        assert_eq!(
+            simulate(&character, &monster, 100),
+            simulate(&character, &monster, 100)
+        );
+    }
+}
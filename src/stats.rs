@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    bot::{
+        control::{BotEvent, BotEventKind},
+        strategy::StepAction,
+    },
+    models::{fight::FightResult, types::ItemCode},
+};
+
+/// The window over which the hourly rates are computed.
+const RATE_WINDOW: Duration = Duration::hours(1);
+
+/// The number of actions kept per character for the charts.
+const MAX_SAMPLES: usize = 10_000;
+
+/// A single action, as kept for the charts and rolling rates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub timestamp: DateTime<Utc>,
+    pub xp: u32,
+    pub gold: u32,
+}
+
+/// The totals and recent actions of a single character's bot.
+#[derive(Debug, Clone)]
+pub struct CharacterStats {
+    /// The time of the first recorded event.
+    pub since: DateTime<Utc>,
+    pub actions: u32,
+    pub xp: u64,
+    pub gold: u64,
+    pub wins: u32,
+    pub losses: u32,
+    pub turns: u64,
+    pub rests: u32,
+    pub hp_restored: u64,
+    pub cooldown_seconds: u64,
+    /// The total quantity of each item dropped or gathered.
+    pub drops: BTreeMap<ItemCode, u64>,
+    pub samples: VecDeque<Sample>,
+}
+
+impl CharacterStats {
+    fn new(since: DateTime<Utc>) -> Self {
+        Self {
+            since,
+            actions: 0,
+            xp: 0,
+            gold: 0,
+            wins: 0,
+            losses: 0,
+            turns: 0,
+            rests: 0,
+            hp_restored: 0,
+            cooldown_seconds: 0,
+            drops: BTreeMap::new(),
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub fn fights(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    /// Returns the fraction of fights won, or `None` before the first fight.
+    pub fn win_rate(&self) -> Option<f64> {
+        let fights = self.fights();
+        (fights > 0).then(|| self.wins as f64 / fights as f64)
+    }
+
+    pub fn average_turns(&self) -> Option<f64> {
+        let fights = self.fights();
+        (fights > 0).then(|| self.turns as f64 / fights as f64)
+    }
+
+    /// The time covered by the rates at `now`: the last hour, or less if the
+    /// session is younger. Never less than a minute so the first actions do
+    /// not produce huge rates.
+    fn rate_window(&self, now: DateTime<Utc>) -> Duration {
+        (now - self.since).clamp(Duration::minutes(1), RATE_WINDOW)
+    }
+
+    fn per_hour(&self, now: DateTime<Utc>, value: impl Fn(&Sample) -> u32) -> f64 {
+        let window = self.rate_window(now);
+        let total = self
+            .samples
+            .iter()
+            .filter(|s| s.timestamp >= now - window)
+            .map(|s| value(s) as f64)
+            .sum::<f64>();
+
+        total * 3600.0 / window.num_seconds() as f64
+    }
+
+    /// The XP gained per hour over the last hour.
+    pub fn xp_per_hour(&self, now: DateTime<Utc>) -> f64 {
+        self.per_hour(now, |s| s.xp)
+    }
+
+    /// The gold won per hour over the last hour.
+    pub fn gold_per_hour(&self, now: DateTime<Utc>) -> f64 {
+        self.per_hour(now, |s| s.gold)
+    }
+
+    /// Returns the seconds spent in cooldown and the seconds spent otherwise
+    /// since the first event.
+    pub fn cooldown_vs_idle(&self, now: DateTime<Utc>) -> (u64, u64) {
+        let elapsed = (now - self.since).num_seconds().max(0) as u64;
+        let cooldown = self.cooldown_seconds.min(elapsed);
+        (cooldown, elapsed - cooldown)
+    }
+
+    /// Sums `value` into `count` buckets of `width` ending at `now`, oldest
+    /// first.
+    pub fn buckets(
+        &self,
+        now: DateTime<Utc>,
+        width: Duration,
+        count: usize,
+        value: impl Fn(&Sample) -> u32,
+    ) -> Vec<u64> {
+        let mut buckets = vec![0; count];
+        let start = now - width * count as i32;
+
+        for sample in self.samples.iter().filter(|s| s.timestamp > start) {
+            let index =
+                ((sample.timestamp - start).num_seconds() / width.num_seconds().max(1)) as usize;
+            if let Some(bucket) = buckets.get_mut(index.min(count.saturating_sub(1))) {
+                *bucket += value(sample) as u64;
+            }
+        }
+
+        buckets
+    }
+
+    /// Returns the running total of `value` after each sample, as
+    /// `(minutes since the first event, total)` points.
+    pub fn cumulative(&self, value: impl Fn(&Sample) -> u32) -> Vec<(f64, f64)> {
+        let mut total = 0.0;
+        self.samples
+            .iter()
+            .map(|s| {
+                total += value(s) as f64;
+                let minutes = (s.timestamp - self.since).num_seconds() as f64 / 60.0;
+                (minutes, total)
+            })
+            .collect()
+    }
+}
+
+/// Rolling statistics aggregated from the bot events of every character.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    pub characters: BTreeMap<String, CharacterStats>,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn character(&self, name: &str) -> Option<&CharacterStats> {
+        self.characters.get(name)
+    }
+
+    /// Adds a bot event to the statistics. Only actions are counted, but any
+    /// event marks the start of a character's session.
+    pub fn record(&mut self, event: &BotEvent) {
+        let stats = self
+            .characters
+            .entry(event.character.clone())
+            .or_insert_with(|| CharacterStats::new(event.timestamp));
+        stats.since = stats.since.min(event.timestamp);
+
+        let BotEventKind::Action {
+            action,
+            xp,
+            cooldown_seconds,
+            fight_result,
+            gold,
+            drops,
+            turns,
+            hp_restored,
+        } = &event.kind
+        else {
+            return;
+        };
+
+        stats.actions += 1;
+        stats.xp += *xp as u64;
+        stats.gold += *gold as u64;
+        stats.turns += *turns as u64;
+        stats.cooldown_seconds += *cooldown_seconds as u64;
+
+        match fight_result {
+            Some(FightResult::Win) => stats.wins += 1,
+            Some(FightResult::Loss) => stats.losses += 1,
+            None => {}
+        }
+
+        if *action == StepAction::Rest {
+            stats.rests += 1;
+            stats.hp_restored += *hp_restored as u64;
+        }
+
+        for drop in drops {
+            *stats.drops.entry(drop.code.clone()).or_default() += drop.quantity as u64;
+        }
+
+        if stats.samples.len() == MAX_SAMPLES {
+            stats.samples.pop_front();
+        }
+        stats.samples.push_back(Sample {
+            timestamp: event.timestamp,
+            xp: *xp,
+            gold: *gold,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::item::SimpleItem;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::seconds(seconds)
+    }
+
+    fn event(seconds: i64, kind: BotEventKind) -> BotEvent {
+        BotEvent {
+            timestamp: at(seconds),
+            character: "hero".to_string(),
+            kind,
+        }
+    }
+
+    fn action(seconds: i64, action: StepAction, xp: u32, cooldown_seconds: u32) -> BotEvent {
+        event(
+            seconds,
+            BotEventKind::Action {
+                action,
+                xp,
+                cooldown_seconds,
+                fight_result: None,
+                gold: 0,
+                drops: Vec::new(),
+                turns: 0,
+                hp_restored: 0,
+            },
+        )
+    }
+
+    fn fight(seconds: i64, result: FightResult, xp: u32, gold: u32, turns: u32) -> BotEvent {
+        event(
+            seconds,
+            BotEventKind::Action {
+                action: StepAction::Fight,
+                xp,
+                cooldown_seconds: 20,
+                fight_result: Some(result),
+                gold,
+                drops: vec![SimpleItem {
+                    code: ItemCode::new("feather"),
+                    quantity: 2,
+                }],
+                turns,
+                hp_restored: 0,
+            },
+        )
+    }
+
+    fn session(events: &[BotEvent]) -> CharacterStats {
+        let mut stats = SessionStats::new();
+        for event in events {
+            stats.record(event);
+        }
+        stats.character("hero").unwrap().clone()
+    }
+
+    #[test]
+    fn totals_actions() {
+        let stats = session(&[
+            event(60, BotEventKind::Paused),
+            fight(120, FightResult::Win, 100, 10, 4),
+            fight(180, FightResult::Loss, 0, 0, 6),
+            event(
+                240,
+                BotEventKind::Action {
+                    action: StepAction::Rest,
+                    xp: 0,
+                    cooldown_seconds: 5,
+                    fight_result: None,
+                    gold: 0,
+                    drops: Vec::new(),
+                    turns: 0,
+                    hp_restored: 50,
+                },
+            ),
+            event(
+                0,
+                BotEventKind::Error {
+                    message: "timeout".to_string(),
+                },
+            ),
+        ]);
+
+        assert_eq!(stats.since, at(0));
+        assert_eq!(stats.actions, 3);
+        assert_eq!(stats.xp, 100);
+        assert_eq!(stats.gold, 10);
+        assert_eq!((stats.wins, stats.losses), (1, 1));
+        assert_eq!(stats.win_rate(), Some(0.5));
+        assert_eq!(stats.average_turns(), Some(5.0));
+        assert_eq!((stats.rests, stats.hp_restored), (1, 50));
+        assert_eq!(stats.cooldown_seconds, 45);
+        assert_eq!(stats.drops.get(&ItemCode::new("feather")), Some(&4));
+        assert_eq!(stats.samples.len(), 3);
+    }
+
+    #[test]
+    fn has_no_fight_rates_before_the_first_fight() {
+        let stats = session(&[action(0, StepAction::Gather, 10, 25)]);
+
+        assert_eq!(stats.win_rate(), None);
+        assert_eq!(stats.average_turns(), None);
+    }
+
+    #[test]
+    fn computes_hourly_rates_over_the_last_hour() {
+        let stats = session(&[
+            action(0, StepAction::Gather, 100, 25),
+            fight(30 * 60, FightResult::Win, 300, 30, 3),
+        ]);
+
+        // Half an hour into the session, everything counts.
+        assert_eq!(stats.rate_window(at(30 * 60)), Duration::minutes(30));
+        assert_eq!(stats.xp_per_hour(at(30 * 60)), 800.0);
+        assert_eq!(stats.gold_per_hour(at(30 * 60)), 60.0);
+
+        // After two hours, only the last hour counts.
+        assert_eq!(stats.rate_window(at(2 * 3600)), RATE_WINDOW);
+        assert_eq!(stats.xp_per_hour(at(80 * 60)), 300.0);
+        assert_eq!(stats.xp_per_hour(at(2 * 3600)), 0.0);
+    }
+
+    #[test]
+    fn rates_cover_at_least_a_minute() {
+        let stats = session(&[action(0, StepAction::Gather, 10, 25)]);
+
+        assert_eq!(stats.rate_window(at(5)), Duration::minutes(1));
+        assert_eq!(stats.xp_per_hour(at(5)), 600.0);
+    }
+
+    #[test]
+    fn sums_samples_into_buckets() {
+        let stats = session(&[
+            action(25 * 60, StepAction::Gather, 1, 25),
+            action(35 * 60, StepAction::Gather, 10, 25),
+            action(55 * 60, StepAction::Gather, 100, 25),
+            action(60 * 60, StepAction::Gather, 1000, 25),
+        ]);
+
+        let buckets = stats.buckets(at(60 * 60), Duration::minutes(10), 3, |s| s.xp);
+
+        assert_eq!(buckets, vec![10, 0, 1100]);
+    }
+
+    #[test]
+    fn splits_time_between_cooldown_and_idle() {
+        let stats = session(&[
+            action(0, StepAction::Gather, 10, 25),
+            action(25, StepAction::Gather, 10, 20),
+        ]);
+
+        assert_eq!(stats.cooldown_vs_idle(at(100)), (45, 55));
+        // Cooldowns running past now are not counted beyond the elapsed time.
+        assert_eq!(stats.cooldown_vs_idle(at(30)), (30, 0));
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{
+    Notify,
+    broadcast::{self, error::RecvError},
+    watch,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    api::client::ArtifactsClient,
+    bot::strategy::{StepAction, StepOutcome},
+    cooldown,
+    events::EventNotice,
+    models::{
+        character::Character, event::ActiveEvent, fight::FightResult, item::SimpleItem,
+        types::CharacterName,
+    },
+};
+
+/// The longest [`WorldState::poll`] goes without fetching the characters.
+///
+/// Action responses already carry the updated characters, so this only
+/// picks up changes made outside of the bots.
+pub const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The interval of a poller fetching the characters unconditionally, which
+/// the requests saved by [`WorldState::poll`] are reported against.
+const FIXED_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long after a cooldown expires without a newer state the character is
+/// fetched. Bots act as soon as their cooldown expires, so only idle
+/// characters are fetched.
+const COOLDOWN_GRACE: chrono::Duration = chrono::Duration::seconds(2);
+
+/// How long to wait before fetching the characters again after a failure.
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The number of events buffered for subscribers that fall behind.
+const EVENT_CAPACITY: usize = 256;
+
+/// Something that changed in the world, as seen by action responses and the
+/// character poller.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldEvent {
+    /// A character's state was replaced by a newer one.
+    CharacterUpdated { character: Box<Character> },
+    /// A character performed an action and is now in cooldown.
+    CooldownStarted {
+        character: CharacterName,
+        action: StepAction,
+        seconds: u32,
+        expiration: DateTime<Utc>,
+    },
+    /// A character fought a monster.
+    FightFinished {
+        character: CharacterName,
+        result: FightResult,
+        xp: u32,
+        gold: u32,
+        turns: u32,
+    },
+    /// A character received an item from a fight or from gathering.
+    ItemGained {
+        character: CharacterName,
+        item: SimpleItem,
+    },
+    /// A world event spawned on a map.
+    EventStarted { event: Box<ActiveEvent> },
+    /// A world event despawned or expired.
+    EventEnded { event: Box<ActiveEvent> },
+}
+
+/// Why [`WorldState::poll`] fetched the characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PollReason {
+    Startup,
+    CooldownExpired,
+    Requested,
+    Retry,
+    Fallback,
+}
+
+/// The latest known state of every character, fed by action responses and
+/// the character poller.
+///
+/// Subscribers either [`watch`](WorldState::watch) the characters or
+/// [`subscribe`](WorldState::subscribe) to the individual [`WorldEvent`]s,
+/// instead of fetching the characters themselves.
+#[derive(Debug, Clone)]
+pub struct WorldState {
+    characters: Arc<watch::Sender<Vec<Character>>>,
+    events: broadcast::Sender<WorldEvent>,
+    refresh: Arc<Notify>,
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldState {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            characters: Arc::new(watch::Sender::new(Vec::new())),
+            events,
+            refresh: Arc::new(Notify::new()),
+        }
+    }
+
+    /// The latest state of every known character, in the order they were
+    /// first seen.
+    pub fn characters(&self) -> Vec<Character> {
+        self.characters.borrow().clone()
+    }
+
+    pub fn character(&self, name: &CharacterName) -> Option<Character> {
+        self.characters
+            .borrow()
+            .iter()
+            .find(|c| c.name == *name)
+            .cloned()
+    }
+
+    /// Returns a receiver notified whenever any character changes.
+    pub fn watch(&self) -> watch::Receiver<Vec<Character>> {
+        self.characters.subscribe()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<WorldEvent> {
+        self.events.subscribe()
+    }
+
+    /// Stores the state returned by an action, which is always the most
+    /// recent one.
+    pub fn update_character(&self, character: Character) {
+        self.store(character, false);
+    }
+
+    /// Stores the characters fetched by a poll.
+    ///
+    /// A poll can be answered before an action sent at the same time, so a
+    /// polled character whose cooldown ends before the stored one is stale
+    /// and ignored.
+    pub fn sync(&self, characters: Vec<Character>) {
+        for character in characters {
+            self.store(character, true);
+        }
+    }
+
+    /// Stores the character of an action's outcome and announces what the
+    /// action did.
+    pub fn record(&self, outcome: &StepOutcome) {
+        let name = &outcome.character.name;
+
+        self.update_character(outcome.character.clone());
+        self.emit(WorldEvent::CooldownStarted {
+            character: name.clone(),
+            action: outcome.action,
+            seconds: outcome.cooldown_seconds,
+            expiration: outcome.character.cooldown_expiration,
+        });
+
+        if let Some(result) = outcome.fight_result {
+            self.emit(WorldEvent::FightFinished {
+                character: name.clone(),
+                result,
+                xp: outcome.xp,
+                gold: outcome.gold,
+                turns: outcome.turns,
+            });
+        }
+
+        for item in &outcome.drops {
+            self.emit(WorldEvent::ItemGained {
+                character: name.clone(),
+                item: item.clone(),
+            });
+        }
+    }
+
+    fn store(&self, character: Character, polled: bool) {
+        let changed = self.characters.send_if_modified(|characters| {
+            match characters.iter_mut().find(|c| c.name == character.name) {
+                Some(current)
+                    if polled && current.cooldown_expiration > character.cooldown_expiration =>
+                {
+                    false
+                }
+                Some(current) if *current == character => false,
+                Some(current) => {
+                    *current = character.clone();
+                    true
+                }
+                None => {
+                    characters.push(character.clone());
+                    true
+                }
+            }
+        });
+
+        if changed {
+            self.emit(WorldEvent::CharacterUpdated {
+                character: Box::new(character),
+            });
+        }
+    }
+
+    /// Announces the world events that start and end, as noticed by an
+    /// [`EventWatcher`](crate::events::EventWatcher), until it is dropped.
+    pub async fn follow_events(&self, mut notices: broadcast::Receiver<EventNotice>) {
+        loop {
+            match notices.recv().await {
+                Ok(EventNotice::Started(event)) => self.emit(WorldEvent::EventStarted {
+                    event: Box::new(event),
+                }),
+                Ok(EventNotice::Ended(event)) => self.emit(WorldEvent::EventEnded {
+                    event: Box::new(event),
+                }),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Skipped {} event notices", skipped);
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Asks the poller to fetch the characters now, e.g. after an action
+    /// failed and its response did not describe the character.
+    pub fn refresh(&self) {
+        self.refresh.notify_one();
+    }
+
+    fn emit(&self, event: WorldEvent) {
+        // Sending only fails when nobody is subscribed.
+        _ = self.events.send(event);
+    }
+
+    /// Returns when the characters should next be fetched, given the server
+    /// time of the last fetch, and why.
+    ///
+    /// A character whose cooldown expired after the last fetch and whose
+    /// state has not changed since is fetched shortly after the expiration.
+    fn next_poll(&self, last: DateTime<Utc>, fallback: Duration) -> (DateTime<Utc>, PollReason) {
+        let fallback = chrono::Duration::from_std(fallback)
+            .ok()
+            .and_then(|fallback| last.checked_add_signed(fallback))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        self.characters
+            .borrow()
+            .iter()
+            .map(|c| c.cooldown_expiration)
+            .filter(|expiration| *expiration > last)
+            .min()
+            .map(|expiration| expiration + COOLDOWN_GRACE)
+            .filter(|at| *at < fallback)
+            .map_or((fallback, PollReason::Fallback), |at| {
+                (at, PollReason::CooldownExpired)
+            })
+    }
+
+    /// Fetches the characters until cancelled, after an idle character's
+    /// cooldown expires, when [`refresh`](WorldState::refresh) is called and
+    /// at least every `fallback`.
+    ///
+    /// Logs how many requests were saved compared to fetching them every few
+    /// seconds when cancelled.
+    pub async fn poll(&self, api: ArtifactsClient, fallback: Duration, cancel: CancellationToken) {
+        let started = Instant::now();
+        let mut changes = self.watch();
+        let mut polls: u64 = 0;
+        let mut last = cooldown::now();
+        let mut failed = false;
+        let mut reason = Some(PollReason::Startup);
+
+        loop {
+            if let Some(reason) = reason.take() {
+                debug!("Fetching characters ({:?})", reason);
+                polls += 1;
+                last = cooldown::now();
+
+                match api.get_characters().await {
+                    Ok(characters) => {
+                        failed = false;
+                        self.sync(characters);
+                    }
+                    Err(e) => {
+                        failed = true;
+                        warn!("Failed to fetch characters: {}", e);
+                    }
+                }
+            }
+
+            let (at, next) = if failed {
+                (
+                    last + chrono::Duration::from_std(RETRY_INTERVAL).unwrap_or_default(),
+                    PollReason::Retry,
+                )
+            } else {
+                self.next_poll(last, fallback)
+            };
+            let wait = cooldown::remaining(at).to_std().unwrap_or_default();
+
+            // A change can move the next cooldown expiration, so the wait is
+            // computed again whenever one happens.
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => reason = Some(next),
+                _ = self.refresh.notified() => reason = Some(PollReason::Requested),
+                _ = changes.changed() => {}
+                _ = cancel.cancelled() => break,
+            }
+        }
+
+        let elapsed = started.elapsed();
+        let fixed = elapsed.as_secs() / FIXED_POLL_INTERVAL.as_secs() + 1;
+        info!(
+            "Fetched characters {} times in {}s, saving {} requests over polling every {}s",
+            polls,
+            elapsed.as_secs(),
+            fixed.saturating_sub(polls),
+            FIXED_POLL_INTERVAL.as_secs()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::character::fixtures;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn character(name: &str, cooldown_expiration: &str) -> Character {
+        fixtures::character(json!({
+            "name": name,
+            "cooldown_expiration": cooldown_expiration,
+        }))
+    }
+
+    #[test]
+    fn polls_shortly_after_the_next_cooldown_expires() {
+        let world = WorldState::new();
+        world.sync(vec![
+            character("hero", "2024-01-01T00:00:30Z"),
+            character("sidekick", "2024-01-01T00:00:10Z"),
+        ]);
+
+        let (next, reason) = world.next_poll(at("2024-01-01T00:00:00Z"), FALLBACK_POLL_INTERVAL);
+
+        assert_eq!(next, at("2024-01-01T00:00:12Z"));
+        assert_eq!(reason, PollReason::CooldownExpired);
+    }
+
+    #[test]
+    fn ignores_cooldowns_that_expired_before_the_last_poll() {
+        let world = WorldState::new();
+        world.sync(vec![
+            character("hero", "2024-01-01T00:00:00Z"),
+            character("sidekick", "2024-01-01T00:00:20Z"),
+        ]);
+
+        let (next, reason) = world.next_poll(at("2024-01-01T00:00:00Z"), FALLBACK_POLL_INTERVAL);
+
+        assert_eq!(next, at("2024-01-01T00:00:22Z"));
+        assert_eq!(reason, PollReason::CooldownExpired);
+    }
+
+    #[test]
+    fn falls_back_when_no_cooldown_expires_sooner() {
+        let world = WorldState::new();
+        world.sync(vec![character("hero", "2024-01-01T00:00:59Z")]);
+
+        let (next, reason) = world.next_poll(at("2024-01-01T00:00:00Z"), FALLBACK_POLL_INTERVAL);
+
+        assert_eq!(next, at("2024-01-01T00:01:00Z"));
+        assert_eq!(reason, PollReason::Fallback);
+
+        let (next, reason) = world.next_poll(at("2024-01-01T00:01:00Z"), FALLBACK_POLL_INTERVAL);
+
+        assert_eq!(next, at("2024-01-01T00:02:00Z"));
+        assert_eq!(reason, PollReason::Fallback);
+    }
+
+    #[test]
+    fn sync_ignores_a_stale_character() {
+        let world = WorldState::new();
+        world.update_character(character("hero", "2024-01-01T00:00:30Z"));
+
+        let mut stale = character("hero", "2024-01-01T00:00:10Z");
+        stale.gold = 100;
+        world.sync(vec![stale]);
+
+        let hero = world.character(&"hero".parse().unwrap()).unwrap();
+        assert_eq!(hero.cooldown_expiration, at("2024-01-01T00:00:30Z"));
+        assert_eq!(hero.gold, 0);
+
+        let mut newer = character("hero", "2024-01-01T00:00:40Z");
+        newer.gold = 100;
+        world.sync(vec![newer]);
+
+        assert_eq!(world.character(&"hero".parse().unwrap()).unwrap().gold, 100);
+    }
+}